@@ -6,7 +6,7 @@ pub mod types;
 
 pub use agent_catalog::AcpAgentCatalog;
 pub use message_conversion::{acp_response_to_chunks, ai_messages_to_content_blocks, text_to_content_block, AcpResponseChunk};
-pub use runtime::AcpManager;
+pub use runtime::{is_valid_env_key, validate_mcp_servers, AcpManager};
 use std::path::PathBuf;
 use types::AcpAgentConfig;
 
@@ -1,15 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Component, Path, PathBuf};
 use std::process::Stdio;
+use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
-#[cfg(not(target_os = "windows"))]
-use std::path::Path;
 #[cfg(not(target_os = "windows"))]
 use std::sync::OnceLock;
 
 use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
 use tokio::io::AsyncBufReadExt;
 use tokio::process::Command;
 use tokio::sync::{mpsc, oneshot};
@@ -19,10 +20,13 @@ use uuid::Uuid;
 
 use agent_client_protocol::{
     Agent, CancelNotification, Client, ClientCapabilities, ClientSideConnection, ContentBlock,
-    Implementation, InitializeRequest, InitializeResponse, LoadSessionRequest, LoadSessionResponse,
-    McpServer, Meta, NewSessionRequest, NewSessionResponse, PromptRequest, PromptResponse,
-    ProtocolVersion, RequestPermissionOutcome, RequestPermissionRequest, RequestPermissionResponse,
+    ExtRequest, ExtResponse, HttpHeader, Implementation, InitializeRequest, InitializeResponse,
+    LoadSessionRequest, LoadSessionResponse, McpServer, Meta, NewSessionRequest,
+    NewSessionResponse, PromptRequest, PromptResponse, ProtocolVersion, ReadTextFileRequest,
+    ReadTextFileResponse,
+    RequestPermissionOutcome, RequestPermissionRequest, RequestPermissionResponse,
     SessionModelState, SessionNotification, SetSessionModelRequest, SetSessionModelResponse,
+    WriteTextFileRequest, WriteTextFileResponse,
 };
 
 use super::types::{
@@ -30,6 +34,54 @@ use super::types::{
     AcpEventSink, AcpPermissionRequestEvent, AcpSessionUpdateEvent,
 };
 
+/// How long an agent gets to answer `initialize` before the connection is abandoned
+const DEFAULT_INIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many recent stderr lines are kept per agent connection by default
+const DEFAULT_STDERR_CAPACITY: usize = 200;
+
+/// How many stderr lines are appended to an initialize failure
+const INIT_ERROR_STDERR_LINES: usize = 5;
+
+/// Maps session id -> id of the prompt currently running in that session
+type ActivePrompts = Arc<Mutex<HashMap<String, String>>>;
+
+/// Maps session id -> working directory the session was created or loaded with; file system
+/// requests from the agent are confined to it
+type SessionRoots = Arc<Mutex<HashMap<String, PathBuf>>>;
+
+/// Ring buffer holding the most recent stderr lines of an agent process
+struct StderrBuffer {
+    lines: Mutex<VecDeque<String>>,
+    capacity: usize,
+}
+
+impl StderrBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            lines: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    fn push(&self, line: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Returns up to `count` of the most recent lines, oldest first.
+    fn tail(&self, count: usize) -> Vec<String> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        lines.iter().skip(lines.len().saturating_sub(count)).cloned().collect()
+    }
+}
+
 /// Cache entry for a session with its last access time
 #[derive(Clone)]
 struct SessionCacheEntry {
@@ -72,6 +124,10 @@ pub struct AcpManager {
     session_cache: Arc<Mutex<HashMap<String, SessionCacheEntry>>>,
     /// Session timeout - sessions idle longer than this will be cleaned up
     session_timeout: Duration,
+    /// Initialize timeout - agents that don't complete the handshake in time are killed
+    init_timeout: Duration,
+    /// Number of recent stderr lines kept per connection
+    stderr_capacity: usize,
 }
 
 impl Default for AcpManager {
@@ -93,9 +149,23 @@ impl AcpManager {
             event_sink,
             session_cache: Arc::new(Mutex::new(HashMap::new())),
             session_timeout,
+            init_timeout: DEFAULT_INIT_TIMEOUT,
+            stderr_capacity: DEFAULT_STDERR_CAPACITY,
         }
     }
 
+    /// Overrides how long `connect` waits for the agent to complete the initialize handshake.
+    pub fn with_init_timeout(mut self, init_timeout: Duration) -> Self {
+        self.init_timeout = init_timeout;
+        self
+    }
+
+    /// Overrides how many recent stderr lines are kept per agent connection.
+    pub fn with_stderr_capacity(mut self, stderr_capacity: usize) -> Self {
+        self.stderr_capacity = stderr_capacity;
+        self
+    }
+
     /// Generate a hash key for an agent configuration
     fn agent_config_key(config: &AcpAgentConfig) -> String {
         use std::collections::hash_map::DefaultHasher;
@@ -121,6 +191,7 @@ impl AcpManager {
         cwd: String,
         mcp_servers: Vec<McpServer>,
     ) -> Result<String> {
+        validate_mcp_servers(&mcp_servers).map_err(|err| anyhow!(err))?;
         let key = Self::agent_config_key(&config);
 
         // Check if we have a cached session
@@ -197,14 +268,29 @@ impl AcpManager {
     }
 
     pub async fn connect(&self, config: AcpAgentConfig) -> Result<AcpConnectionInfo> {
-        let id = Uuid::new_v4();
+        self.connect_with_id(Uuid::new_v4(), config).await
+    }
+
+    /// Starts an agent under a caller-chosen `id`. The connection is registered before the
+    /// agent finishes initializing, so `disconnect(id)` stops it even if this future is dropped
+    /// midway; it is unregistered again if initialization fails.
+    pub async fn connect_with_id(
+        &self,
+        id: Uuid,
+        config: AcpAgentConfig,
+    ) -> Result<AcpConnectionInfo> {
         let state = Arc::new(Mutex::new(AcpConnectionState::new()));
         let (command_tx, command_rx) = mpsc::unbounded_channel();
         let (ready_tx, ready_rx) = oneshot::channel::<Result<InitializeResponse>>();
 
         let task_state = state.clone();
-        let event_sink = self.event_sink.clone();
-        let pending_permissions = self.pending_permissions.clone();
+        let stderr = Arc::new(StderrBuffer::new(self.stderr_capacity));
+        let context = ConnectionContext {
+            event_sink: self.event_sink.clone(),
+            pending_permissions: self.pending_permissions.clone(),
+            init_timeout: self.init_timeout,
+            stderr: stderr.clone(),
+        };
         let handle_config = config.clone();
         let join = thread::spawn(move || {
             let runtime = match tokio::runtime::Builder::new_current_thread()
@@ -226,34 +312,37 @@ impl AcpManager {
                     task_state,
                     command_rx,
                     ready_tx,
-                    event_sink,
-                    pending_permissions,
+                    context,
                 ));
             if let Err(err) = result {
                 tracing::error!("acp connection {id} failed: {err}");
             }
         });
 
-        let init = ready_rx
-            .await
-            .context("acp connection initialization channel closed")??;
-
-        let info = {
-            let guard = state.lock().unwrap_or_else(|e| e.into_inner());
-            guard.snapshot(id, Some(&init))
-        };
-
         self.connections.lock().unwrap_or_else(|e| e.into_inner()).insert(
             id,
             AcpConnectionHandle {
-                state,
+                state: state.clone(),
                 command_tx,
                 join,
                 config: handle_config,
+                stderr,
             },
         );
 
-        Ok(info)
+        let init = match ready_rx.await {
+            Ok(Ok(init)) => init,
+            result => {
+                self.connections.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+                return Err(match result {
+                    Ok(Err(err)) => err,
+                    _ => anyhow!("acp connection initialization channel closed"),
+                });
+            }
+        };
+
+        let guard = state.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(guard.snapshot(id, Some(&init)))
     }
 
     pub fn get_info(&self, id: Uuid) -> Option<AcpConnectionInfo> {
@@ -265,6 +354,44 @@ impl AcpManager {
         Some(info)
     }
 
+    /// Returns the agent's most recent stderr lines, oldest first (empty for unknown ids).
+    pub fn get_agent_stderr(&self, id: Uuid) -> Vec<String> {
+        let guard = self.connections.lock().unwrap_or_else(|e| e.into_inner());
+        guard
+            .get(&id)
+            .map(|handle| handle.stderr.tail(usize::MAX))
+            .unwrap_or_default()
+    }
+
+    /// Reports whether the agent behind a connection is still running.
+    ///
+    /// The command loop marks the connection closed as soon as the agent process exits, so this
+    /// never queues behind an in-flight prompt. A connection whose thread has ended is marked
+    /// `Closed` (emitting the state event if that had not happened yet) and reported as dead.
+    pub async fn ping_connection(&self, id: Uuid) -> Result<bool> {
+        let (state, finished) = {
+            let guard = self.connections.lock().unwrap_or_else(|e| e.into_inner());
+            let handle = guard
+                .get(&id)
+                .ok_or_else(|| anyhow!("acp connection {id} not found"))?;
+            (handle.state.clone(), handle.join.is_finished())
+        };
+        let mut guard = state.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.status == AcpConnectionStatus::Closed {
+            return Ok(false);
+        }
+        if !finished {
+            return Ok(true);
+        }
+        guard.set_closed(Some("agent connection ended".to_string()));
+        drop(guard);
+        (self.event_sink)(AcpEvent::ConnectionState(AcpConnectionStateEvent {
+            connection_id: id.to_string(),
+            status: AcpConnectionStatus::Closed,
+        }));
+        Ok(false)
+    }
+
     pub async fn disconnect(&self, id: Uuid) -> Result<()> {
         let handle = self
             .connections
@@ -281,12 +408,28 @@ impl AcpManager {
         Ok(())
     }
 
+    /// Shuts down every agent connection, waiting for each agent to exit.
+    pub async fn disconnect_all(&self) {
+        let ids: Vec<Uuid> = self
+            .connections
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .copied()
+            .collect();
+        for id in ids {
+            // A connection may already be gone if a client disconnected it meanwhile
+            let _ = self.disconnect(id).await;
+        }
+    }
+
     pub async fn new_session(
         &self,
         connection_id: Uuid,
         cwd: String,
         mcp_servers: Vec<McpServer>,
     ) -> Result<NewSessionResponse> {
+        validate_mcp_servers(&mcp_servers).map_err(|err| anyhow!(err))?;
         let config = self.get_connection_config(connection_id)?;
         let command_tx = self.get_command_tx(connection_id)?;
         let mut request = NewSessionRequest::new(cwd).mcp_servers(mcp_servers);
@@ -321,6 +464,7 @@ impl AcpManager {
         cwd: String,
         mcp_servers: Vec<McpServer>,
     ) -> Result<LoadSessionResponse> {
+        validate_mcp_servers(&mcp_servers).map_err(|err| anyhow!(err))?;
         let config = self.get_connection_config(connection_id)?;
         let command_tx = self.get_command_tx(connection_id)?;
         let mut request =
@@ -349,15 +493,22 @@ impl AcpManager {
         Ok(response)
     }
 
+    /// Sends a prompt; session updates it produces are tagged with `prompt_id`.
     pub async fn prompt(
         &self,
         session_id: String,
         prompt: Vec<ContentBlock>,
+        prompt_id: String,
     ) -> Result<PromptResponse> {
         let connection_id = self.connection_for_session(&session_id)?;
         let command_tx = self.get_command_tx(connection_id)?;
         let request = PromptRequest::new(session_id, prompt);
-        send_request(&command_tx, |respond_to| AcpCommand::Prompt { request, respond_to }).await
+        send_request(&command_tx, |respond_to| AcpCommand::Prompt {
+            prompt_id,
+            request,
+            respond_to,
+        })
+        .await
     }
 
     pub async fn cancel(&self, session_id: String) -> Result<()> {
@@ -367,6 +518,28 @@ impl AcpManager {
         send_request(&command_tx, |respond_to| AcpCommand::Cancel { request, respond_to }).await
     }
 
+    /// Calls an agent extension method; `method` is sent with the `_` prefix ACP requires.
+    pub async fn ext_method(
+        &self,
+        connection_id: Uuid,
+        method: String,
+        params: Value,
+    ) -> Result<Value> {
+        if method.trim().is_empty() {
+            return Err(anyhow!("extension method name is empty"));
+        }
+        let command_tx = self.get_command_tx(connection_id)?;
+        let params = serde_json::value::to_raw_value(&params)
+            .context("failed to encode extension params")?;
+        let request = ExtRequest::new(method, params.into());
+        let response = send_request(&command_tx, |respond_to| AcpCommand::ExtMethod {
+            request,
+            respond_to,
+        })
+        .await?;
+        serde_json::from_str(response.0.get()).context("failed to decode extension response")
+    }
+
     pub fn reply_permission(
         &self,
         request_id: String,
@@ -477,11 +650,20 @@ where
         .map_err(|_| anyhow!("acp connection command dropped"))?
 }
 
+/// Manager-owned handles and settings a connection's runtime thread works with.
+struct ConnectionContext {
+    event_sink: AcpEventSink,
+    pending_permissions: Arc<Mutex<HashMap<String, oneshot::Sender<RequestPermissionOutcome>>>>,
+    init_timeout: Duration,
+    stderr: Arc<StderrBuffer>,
+}
+
 struct AcpConnectionHandle {
     state: Arc<Mutex<AcpConnectionState>>,
     command_tx: mpsc::UnboundedSender<AcpCommand>,
     join: thread::JoinHandle<()>,
     config: AcpAgentConfig,
+    stderr: Arc<StderrBuffer>,
 }
 
 #[derive(Debug, Clone)]
@@ -545,6 +727,7 @@ enum AcpCommand {
         respond_to: oneshot::Sender<Result<LoadSessionResponse>>,
     },
     Prompt {
+        prompt_id: String,
         request: PromptRequest,
         respond_to: oneshot::Sender<Result<PromptResponse>>,
     },
@@ -556,15 +739,43 @@ enum AcpCommand {
         request: CancelNotification,
         respond_to: oneshot::Sender<Result<()>>,
     },
+    ExtMethod {
+        request: ExtRequest,
+        respond_to: oneshot::Sender<Result<ExtResponse>>,
+    },
 }
 
 #[derive(Clone)]
 struct AcpClient {
     connection_id: Uuid,
+    capabilities: ClientCapabilities,
     event_sink: AcpEventSink,
+    active_prompts: ActivePrompts,
+    session_roots: SessionRoots,
     pending_permissions: Arc<Mutex<HashMap<String, oneshot::Sender<RequestPermissionOutcome>>>>,
 }
 
+impl AcpClient {
+    /// Resolves the path of a file system request against the requesting session's cwd.
+    fn session_path(
+        &self,
+        session_id: &str,
+        path: &Path,
+    ) -> agent_client_protocol::Result<PathBuf> {
+        let root = self
+            .session_roots
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| {
+                agent_client_protocol::Error::invalid_params()
+                    .data(format!("unknown session: {session_id}"))
+            })?;
+        confined_request_path(&root, path)
+    }
+}
+
 #[async_trait::async_trait(?Send)]
 impl Client for AcpClient {
     async fn request_permission(
@@ -595,18 +806,103 @@ impl Client for AcpClient {
         Ok(RequestPermissionResponse::new(outcome))
     }
 
+    async fn read_text_file(
+        &self,
+        args: ReadTextFileRequest,
+    ) -> agent_client_protocol::Result<ReadTextFileResponse> {
+        if !self.capabilities.fs.read_text_file {
+            return Err(agent_client_protocol::Error::method_not_found());
+        }
+        let path = self.session_path(&args.session_id.to_string(), &args.path)?;
+        let content = tokio::fs::read_to_string(path).await.map_err(fs_request_error)?;
+        Ok(ReadTextFileResponse::new(select_lines(&content, args.line, args.limit)))
+    }
+
+    async fn write_text_file(
+        &self,
+        args: WriteTextFileRequest,
+    ) -> agent_client_protocol::Result<WriteTextFileResponse> {
+        if !self.capabilities.fs.write_text_file {
+            return Err(agent_client_protocol::Error::method_not_found());
+        }
+        let path = self.session_path(&args.session_id.to_string(), &args.path)?;
+        tokio::fs::write(path, args.content).await.map_err(fs_request_error)?;
+        Ok(WriteTextFileResponse::new())
+    }
+
     async fn session_notification(
         &self,
         args: SessionNotification,
     ) -> agent_client_protocol::Result<()> {
+        let prompt_id = self
+            .active_prompts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&args.session_id.to_string())
+            .cloned();
         (self.event_sink)(AcpEvent::SessionUpdate(AcpSessionUpdateEvent {
             connection_id: self.connection_id.to_string(),
+            prompt_id,
             notification: args,
         }));
         Ok(())
     }
 }
 
+/// Checks a file system request path, which ACP requires to be absolute, and resolves it
+/// (following symlinks) to a location inside `root`. Files that don't exist yet resolve
+/// through their parent directory, so writes can create files but not directories.
+pub(super) fn confined_request_path(
+    root: &Path,
+    path: &Path,
+) -> agent_client_protocol::Result<PathBuf> {
+    let invalid = |reason: &str| {
+        agent_client_protocol::Error::invalid_params()
+            .data(format!("{reason}: {}", path.display()))
+    };
+    if !path.is_absolute() {
+        return Err(invalid("path must be absolute"));
+    }
+    if path.components().any(|component| component == Component::ParentDir) {
+        return Err(invalid("path must not contain `..`"));
+    }
+    let root = root.canonicalize().map_err(fs_request_error)?;
+    let resolved = match path.canonicalize() {
+        Ok(resolved) => resolved,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+                return Err(fs_request_error(err));
+            };
+            parent.canonicalize().map_err(fs_request_error)?.join(name)
+        }
+        Err(err) => return Err(fs_request_error(err)),
+    };
+    if resolved.starts_with(&root) {
+        Ok(resolved)
+    } else {
+        Err(invalid("path is outside the session working directory"))
+    }
+}
+
+fn fs_request_error(err: std::io::Error) -> agent_client_protocol::Error {
+    agent_client_protocol::Error::internal_error().data(err.to_string())
+}
+
+/// Applies the optional 1-based `line` offset and `limit` of a read request.
+fn select_lines(content: &str, line: Option<u32>, limit: Option<u32>) -> String {
+    if line.is_none() && limit.is_none() {
+        return content.to_string();
+    }
+    let start = line.map_or(0, |line| line.saturating_sub(1) as usize);
+    let limit = limit.map_or(usize::MAX, |limit| limit as usize);
+    content
+        .lines()
+        .skip(start)
+        .take(limit)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Initialize an ACP agent connection by spawning the agent process and establishing protocol handshake.
 ///
 /// Returns the initialized connection and child process, or an error if initialization fails.
@@ -615,9 +911,11 @@ async fn initialize_agent_connection(
     config: &AcpAgentConfig,
     state: &Arc<Mutex<AcpConnectionState>>,
     ready_tx: oneshot::Sender<Result<InitializeResponse>>,
-    event_sink: &AcpEventSink,
-    pending_permissions: &Arc<Mutex<HashMap<String, oneshot::Sender<RequestPermissionOutcome>>>>,
+    context: &ConnectionContext,
+    active_prompts: &ActivePrompts,
+    session_roots: &SessionRoots,
 ) -> Result<(ClientSideConnection, tokio::process::Child)> {
+    let init_timeout = context.init_timeout;
     let mut child = spawn_agent(config)
         .with_context(|| format!("failed to spawn ACP agent {}", config.command))?;
 
@@ -629,18 +927,25 @@ async fn initialize_agent_connection(
         .stdin
         .take()
         .context("ACP agent stdin was not captured")?;
-    let stderr = child.stderr.take();
-
-    if let Some(stderr) = stderr {
+    let stderr_task = child.stderr.take().map(|stderr| {
+        let buffer = context.stderr.clone();
         tokio::task::spawn_local(async move {
-            log_stderr(id, stderr).await;
-        });
-    }
+            log_stderr(id, stderr, &buffer).await;
+        })
+    });
 
+    let capabilities = config
+        .capabilities
+        .as_ref()
+        .map(ClientCapabilities::from)
+        .unwrap_or_default();
     let client = AcpClient {
         connection_id: id,
-        event_sink: event_sink.clone(),
-        pending_permissions: pending_permissions.clone(),
+        capabilities: capabilities.clone(),
+        event_sink: context.event_sink.clone(),
+        active_prompts: active_prompts.clone(),
+        session_roots: session_roots.clone(),
+        pending_permissions: context.pending_permissions.clone(),
     };
     let (connection, io_task) = ClientSideConnection::new(
         client,
@@ -654,21 +959,28 @@ async fn initialize_agent_connection(
     let io_handle = tokio::task::spawn_local(io_task);
 
     let init_request = InitializeRequest::new(ProtocolVersion::LATEST)
-        .client_capabilities(ClientCapabilities::default())
+        .client_capabilities(capabilities)
         .client_info(
             Implementation::new("parallel-cli-runner", env!("CARGO_PKG_VERSION"))
                 .title("Parallel CLI Runner"),
         );
 
-    let init_response = match connection.initialize(init_request).await {
-        Ok(response) => response,
-        Err(err) => {
-            let _ = ready_tx.send(Err(anyhow!("initialize failed: {err:?}")));
-            if let Ok(mut guard) = state.lock() {
-                guard.set_closed(Some(format!("initialize failed: {err:?}")));
-            }
-            let _ = child.kill().await;
-            return Err(anyhow!("initialize failed: {err:?}"));
+    let initialize = tokio::time::timeout(init_timeout, connection.initialize(init_request));
+    let init_response = match initialize.await {
+        Ok(Ok(response)) => response,
+        Ok(Err(err)) => {
+            let message = format!("initialize failed: {err:?}");
+            return Err(
+                abort_initialize(message, child, stderr_task, &context.stderr, state, ready_tx)
+                    .await,
+            );
+        }
+        Err(_) => {
+            let message = format!("initialize timed out after {init_timeout:?}");
+            return Err(
+                abort_initialize(message, child, stderr_task, &context.stderr, state, ready_tx)
+                    .await,
+            );
         }
     };
 
@@ -678,12 +990,9 @@ async fn initialize_agent_connection(
             init_response.protocol_version,
             ProtocolVersion::LATEST
         );
-        let _ = ready_tx.send(Err(anyhow!(message.clone())));
-        if let Ok(mut guard) = state.lock() {
-            guard.set_closed(Some(message));
-        }
-        let _ = child.kill().await;
-        return Err(anyhow!("unsupported protocol version"));
+        return Err(
+            abort_initialize(message, child, stderr_task, &context.stderr, state, ready_tx).await,
+        );
     }
 
     if let Ok(mut guard) = state.lock() {
@@ -692,7 +1001,7 @@ async fn initialize_agent_connection(
     }
 
     let _ = ready_tx.send(Ok(init_response));
-    (event_sink)(AcpEvent::ConnectionState(AcpConnectionStateEvent {
+    (context.event_sink)(AcpEvent::ConnectionState(AcpConnectionStateEvent {
         connection_id: id.to_string(),
         status: AcpConnectionStatus::Ready,
     }));
@@ -709,12 +1018,16 @@ async fn initialize_agent_connection(
 
 /// Run the main command loop for an ACP agent connection.
 ///
-/// Processes commands from the channel until shutdown, process exit, or IO failure.
+/// Processes commands from the channel until shutdown, process exit, or IO failure. Prompts
+/// run as local tasks so a `session/cancel` (or any other command) reaches the agent while a
+/// prompt is still in flight.
 async fn run_command_loop(
-    connection: &mut ClientSideConnection,
+    connection: &Rc<ClientSideConnection>,
     mut command_rx: mpsc::UnboundedReceiver<AcpCommand>,
     child: &mut tokio::process::Child,
     state: &Arc<Mutex<AcpConnectionState>>,
+    active_prompts: &ActivePrompts,
+    session_roots: &SessionRoots,
 ) -> bool {
     let mut child_exited = false;
 
@@ -726,16 +1039,46 @@ async fn run_command_loop(
                         break;
                     }
                     Some(AcpCommand::NewSession { request, respond_to }) => {
+                        let cwd = request.cwd.clone();
                         let result = connection.new_session(request).await;
+                        if let Ok(response) = &result {
+                            session_roots
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .insert(response.session_id.to_string(), cwd);
+                        }
                         let _ = respond_to.send(result.map_err(|err| anyhow!("session/new failed: {err:?}")));
                     }
                     Some(AcpCommand::LoadSession { request, respond_to }) => {
+                        let session_id = request.session_id.to_string();
+                        let cwd = request.cwd.clone();
                         let result = connection.load_session(request).await;
+                        if result.is_ok() {
+                            session_roots
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .insert(session_id, cwd);
+                        }
                         let _ = respond_to.send(result.map_err(|err| anyhow!("session/load failed: {err:?}")));
                     }
-                    Some(AcpCommand::Prompt { request, respond_to }) => {
-                        let result = connection.prompt(request).await;
-                        let _ = respond_to.send(result.map_err(|err| anyhow!("session/prompt failed: {err:?}")));
+                    Some(AcpCommand::Prompt { prompt_id, request, respond_to }) => {
+                        let session_id = request.session_id.to_string();
+                        active_prompts
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .insert(session_id.clone(), prompt_id);
+                        let connection = connection.clone();
+                        let active_prompts = active_prompts.clone();
+                        tokio::task::spawn_local(async move {
+                            let result = connection.prompt(request).await;
+                            active_prompts
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .remove(&session_id);
+                            let result =
+                                result.map_err(|err| anyhow!("session/prompt failed: {err:?}"));
+                            let _ = respond_to.send(result);
+                        });
                     }
                     Some(AcpCommand::SetSessionModel { request, respond_to }) => {
                         let result = connection.set_session_model(request).await;
@@ -745,14 +1088,22 @@ async fn run_command_loop(
                         let result = connection.cancel(request).await;
                         let _ = respond_to.send(result.map_err(|err| anyhow!("session/cancel failed: {err:?}")));
                     }
+                    Some(AcpCommand::ExtMethod { request, respond_to }) => {
+                        let method = request.method.clone();
+                        let result = connection.ext_method(request).await;
+                        let _ = respond_to.send(result.map_err(|err| anyhow!("_{method} failed: {err:?}")));
+                    }
                     None => break,
                 }
             }
             status = child.wait() => {
-                if let Err(err) = status {
-                    if let Ok(mut guard) = state.lock() {
-                        guard.set_closed(Some(format!("agent exited: {err}")));
-                    }
+                let reason = match status {
+                    Ok(status) if status.success() => None,
+                    Ok(status) => Some(format!("agent exited: {status}")),
+                    Err(err) => Some(format!("agent exited: {err}")),
+                };
+                if let Ok(mut guard) = state.lock() {
+                    guard.set_closed(reason);
                 }
                 child_exited = true;
             }
@@ -785,28 +1136,40 @@ async fn run_connection(
     state: Arc<Mutex<AcpConnectionState>>,
     command_rx: mpsc::UnboundedReceiver<AcpCommand>,
     ready_tx: oneshot::Sender<Result<InitializeResponse>>,
-    event_sink: AcpEventSink,
-    pending_permissions: Arc<Mutex<HashMap<String, oneshot::Sender<RequestPermissionOutcome>>>>,
+    context: ConnectionContext,
 ) -> Result<()> {
+    let active_prompts = ActivePrompts::default();
+    let session_roots = SessionRoots::default();
+
     // Initialize the agent connection
-    let (mut connection, mut child) = initialize_agent_connection(
+    let (connection, mut child) = initialize_agent_connection(
         id,
         &config,
         &state,
         ready_tx,
-        &event_sink,
-        &pending_permissions,
+        &context,
+        &active_prompts,
+        &session_roots,
     )
     .await?;
 
     // Run the command processing loop
-    run_command_loop(&mut connection, command_rx, &mut child, &state).await;
+    let connection = Rc::new(connection);
+    run_command_loop(
+        &connection,
+        command_rx,
+        &mut child,
+        &state,
+        &active_prompts,
+        &session_roots,
+    )
+    .await;
 
     // Kill the child process on shutdown
     let _ = child.kill().await;
 
     // Perform cleanup and emit close event
-    shutdown_connection(&state, &event_sink, id);
+    shutdown_connection(&state, &context.event_sink, id);
 
     Ok(())
 }
@@ -928,7 +1291,75 @@ fn parse_env_output(output: &[u8]) -> HashMap<String, String> {
     env
 }
 
-fn is_valid_env_key(key: &str) -> bool {
+/// Checks MCP server specs up front so malformed entries fail before reaching the agent.
+pub fn validate_mcp_servers(servers: &[McpServer]) -> std::result::Result<(), String> {
+    let mut names = HashSet::new();
+    for server in servers {
+        let name = match server {
+            McpServer::Stdio(stdio) => {
+                if stdio.command.as_os_str().is_empty() {
+                    return Err(format!("mcp server {:?} is missing a command", stdio.name));
+                }
+                if stdio.args.iter().any(|arg| arg.contains('\0')) {
+                    return Err(format!(
+                        "mcp server {:?} has an argument containing NUL",
+                        stdio.name
+                    ));
+                }
+                if let Some(env) = stdio.env.iter().find(|env| !is_valid_env_key(&env.name)) {
+                    return Err(format!(
+                        "mcp server {:?} has an invalid env key {:?}",
+                        stdio.name, env.name
+                    ));
+                }
+                &stdio.name
+            }
+            McpServer::Http(http) => {
+                validate_mcp_endpoint(&http.name, &http.url, &http.headers)?;
+                &http.name
+            }
+            McpServer::Sse(sse) => {
+                validate_mcp_endpoint(&sse.name, &sse.url, &sse.headers)?;
+                &sse.name
+            }
+            _ => continue,
+        };
+        if name.trim().is_empty() {
+            return Err("mcp server name must not be empty".to_string());
+        }
+        if !names.insert(name.as_str()) {
+            return Err(format!("duplicate mcp server name {name:?}"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_mcp_endpoint(
+    name: &str,
+    url: &str,
+    headers: &[HttpHeader],
+) -> std::result::Result<(), String> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(format!("mcp server {name:?} needs an http(s) url, got {url:?}"));
+    }
+    let invalid_header = headers.iter().find(|header| {
+        header.name.is_empty()
+            || header
+                .name
+                .chars()
+                .any(|c| c == ':' || c.is_whitespace() || c.is_control())
+    });
+    if let Some(header) = invalid_header {
+        return Err(format!(
+            "mcp server {name:?} has an invalid header name {:?}",
+            header.name
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `key` is a portable environment variable name (`[A-Za-z_][A-Za-z0-9_]*`).
+pub fn is_valid_env_key(key: &str) -> bool {
     let mut chars = key.chars();
     let Some(first) = chars.next() else {
         return false;
@@ -939,7 +1370,34 @@ fn is_valid_env_key(key: &str) -> bool {
     chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
 }
 
-async fn log_stderr(id: Uuid, stderr: tokio::process::ChildStderr) {
+/// Kills an agent whose handshake failed and reports `message`, followed by its last stderr lines.
+async fn abort_initialize(
+    message: String,
+    mut child: tokio::process::Child,
+    stderr_task: Option<tokio::task::JoinHandle<()>>,
+    stderr: &StderrBuffer,
+    state: &Arc<Mutex<AcpConnectionState>>,
+    ready_tx: oneshot::Sender<Result<InitializeResponse>>,
+) -> anyhow::Error {
+    let _ = child.kill().await;
+    // Once the agent is gone its stderr hits EOF; let the reader drain what is left
+    if let Some(task) = stderr_task {
+        let _ = tokio::time::timeout(Duration::from_millis(200), task).await;
+    }
+    let recent = stderr.tail(INIT_ERROR_STDERR_LINES);
+    let message = if recent.is_empty() {
+        message
+    } else {
+        format!("{message}\nagent stderr:\n{}", recent.join("\n"))
+    };
+    let _ = ready_tx.send(Err(anyhow!(message.clone())));
+    if let Ok(mut guard) = state.lock() {
+        guard.set_closed(Some(message.clone()));
+    }
+    anyhow!(message)
+}
+
+async fn log_stderr(id: Uuid, stderr: tokio::process::ChildStderr, buffer: &StderrBuffer) {
     let mut reader = tokio::io::BufReader::new(stderr);
     let mut line = String::new();
     loop {
@@ -950,6 +1408,7 @@ async fn log_stderr(id: Uuid, stderr: tokio::process::ChildStderr) {
                 let trimmed = line.trim_end();
                 if !trimmed.is_empty() {
                     tracing::warn!("acp agent {id} stderr: {trimmed}");
+                    buffer.push(trimmed.to_string());
                 }
             }
             Err(_) => break,
@@ -87,6 +87,7 @@ fn example_agent_config() -> AcpAgentConfig {
         ],
         env: std::collections::HashMap::new(),
         cwd: Some(std::env::current_dir().unwrap().to_str().unwrap().to_string()),
+        capabilities: None,
     }
 }
 
@@ -322,7 +323,7 @@ async fn test_acp_prompt_and_session_updates() {
 
     let result = harness
         .manager
-        .prompt(session_id.clone(), prompt_content)
+        .prompt(session_id.clone(), prompt_content, "prompt-1".to_string())
         .await;
 
     assert!(result.is_ok(), "Failed to send prompt: {:?}", result.err());
@@ -331,7 +332,19 @@ async fn test_acp_prompt_and_session_updates() {
     use agent_client_protocol::StopReason;
     assert_eq!(response.stop_reason, StopReason::EndTurn);
 
-    // Note: The example agent may emit session updates; we don't assert them here.
+    // Every update streamed during the prompt carries its prompt id
+    let updates: Vec<_> = harness
+        .drain_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            AcpEvent::SessionUpdate(update) => Some(update),
+            _ => None,
+        })
+        .collect();
+    assert!(!updates.is_empty(), "Expected session updates from the prompt");
+    assert!(updates
+        .iter()
+        .all(|update| update.prompt_id.as_deref() == Some("prompt-1")));
 
     // Clean up
     harness
@@ -369,7 +382,7 @@ async fn test_acp_cancel() {
 
     let _ = harness
         .manager
-        .prompt(session_id.clone(), prompt_content)
+        .prompt(session_id.clone(), prompt_content, "prompt-cancel".to_string())
         .await
         .expect("Failed to send prompt");
 
@@ -481,6 +494,115 @@ async fn test_acp_connection_info() {
         .expect("Failed to disconnect");
 }
 
+#[tokio::test]
+async fn test_acp_ping_connection() {
+    let harness = TestHarness::new();
+    let config = example_agent_config();
+
+    let connection_info = harness
+        .manager
+        .connect(config)
+        .await
+        .expect("Failed to connect");
+    let connection_id = connection_info.id.parse().unwrap();
+
+    let alive = harness
+        .manager
+        .ping_connection(connection_id)
+        .await
+        .expect("Failed to ping");
+    assert!(alive, "Connected agent should respond to ping");
+
+    // Clean up
+    harness
+        .manager
+        .disconnect(connection_id)
+        .await
+        .expect("Failed to disconnect");
+
+    let result = harness.manager.ping_connection(connection_id).await;
+    assert!(result.is_err(), "Should fail to ping a disconnected connection");
+}
+
+#[tokio::test]
+async fn test_acp_ext_method() {
+    let harness = TestHarness::new();
+    let config = example_agent_config();
+
+    let connection_info = harness
+        .manager
+        .connect(config)
+        .await
+        .expect("Failed to connect");
+    let connection_id = connection_info.id.parse().unwrap();
+
+    let response = harness
+        .manager
+        .ext_method(
+            connection_id,
+            "example.echo".to_string(),
+            serde_json::json!({ "value": 1 }),
+        )
+        .await
+        .expect("Failed to call extension method");
+    assert_eq!(response, serde_json::json!({}));
+
+    let result = harness
+        .manager
+        .ext_method(connection_id, "  ".to_string(), serde_json::Value::Null)
+        .await;
+    assert!(result.is_err(), "Should reject an empty method name");
+
+    // Clean up
+    harness
+        .manager
+        .disconnect(connection_id)
+        .await
+        .expect("Failed to disconnect");
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tokio::test]
+async fn test_acp_connect_times_out_when_agent_never_initializes() {
+    let manager = AcpManager::new(Arc::new(|_| {})).with_init_timeout(Duration::from_millis(200));
+
+    // `sleep` never answers the initialize request
+    let config = AcpAgentConfig {
+        command: "sleep".to_string(),
+        args: vec!["30".to_string()],
+        env: std::collections::HashMap::new(),
+        cwd: None,
+        capabilities: None,
+    };
+
+    let result = tokio::time::timeout(Duration::from_secs(10), manager.connect(config))
+        .await
+        .expect("connect should not hang");
+    let err = result.expect_err("connect should fail when initialize times out");
+    assert!(err.to_string().contains("timed out"), "unexpected error: {err}");
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tokio::test]
+async fn test_acp_connect_failure_includes_agent_stderr() {
+    let manager = AcpManager::new(Arc::new(|_| {})).with_init_timeout(Duration::from_secs(2));
+
+    // The agent explains itself on stderr and exits without answering initialize
+    let config = AcpAgentConfig {
+        command: "sh".to_string(),
+        args: vec!["-c".to_string(), "echo 'missing API key' >&2; exit 1".to_string()],
+        env: std::collections::HashMap::new(),
+        cwd: None,
+        capabilities: None,
+    };
+
+    let result = tokio::time::timeout(Duration::from_secs(10), manager.connect(config))
+        .await
+        .expect("connect should not hang");
+    let err = result.expect_err("connect should fail when the agent exits");
+    assert!(err.to_string().contains("missing API key"), "unexpected error: {err}");
+}
+
 #[tokio::test]
 async fn test_acp_disconnect_nonexistent_connection() {
     let harness = TestHarness::new();
@@ -511,7 +633,7 @@ async fn test_acp_prompt_nonexistent_session() {
 
     let result = harness
         .manager
-        .prompt("nonexistent_session".to_string(), prompt_content)
+        .prompt("nonexistent_session".to_string(), prompt_content, "prompt-missing".to_string())
         .await;
     assert!(result.is_err(), "Should fail to prompt nonexistent session");
 }
@@ -649,3 +771,97 @@ async fn test_acp_reconnect_after_disconnect() {
         .await
         .expect("Failed to disconnect");
 }
+
+#[test]
+fn test_validate_mcp_servers_accepts_valid_specs() {
+    use agent_client_protocol::McpServer;
+    let servers: Vec<McpServer> = serde_json::from_str(
+        r#"[
+            {
+                "name": "files",
+                "command": "/usr/bin/mcp-files",
+                "args": ["--root", "/tmp"],
+                "env": [{"name": "MCP_TOKEN", "value": "x"}]
+            },
+            {
+                "type": "http",
+                "name": "remote",
+                "url": "https://mcp.example.com",
+                "headers": [{"name": "Authorization", "value": "Bearer x"}]
+            }
+        ]"#,
+    )
+    .expect("Failed to parse McpServer");
+
+    assert!(super::validate_mcp_servers(&servers).is_ok());
+}
+
+#[test]
+fn test_validate_mcp_servers_rejects_malformed_specs() {
+    use agent_client_protocol::McpServer;
+    let cases = [
+        r#"[{"name": "files", "command": "", "args": [], "env": []}]"#,
+        r#"[{"name": "files", "command": "mcp", "args": [], "env": [{"name": "A-B", "value": ""}]}]"#,
+        r#"[{"type": "sse", "name": "remote", "url": "ftp://mcp.example.com", "headers": []}]"#,
+        r#"[{"name": "", "command": "mcp", "args": [], "env": []}]"#,
+        r#"[
+            {"name": "dup", "command": "a", "args": [], "env": []},
+            {"name": "dup", "command": "b", "args": [], "env": []}
+        ]"#,
+    ];
+    for case in cases {
+        let servers: Vec<McpServer> =
+            serde_json::from_str(case).expect("Failed to parse McpServer");
+        assert!(super::validate_mcp_servers(&servers).is_err(), "Expected {case} to be rejected");
+    }
+}
+
+#[test]
+fn test_agent_config_capabilities_map_to_client_capabilities() {
+    use agent_client_protocol::ClientCapabilities;
+    let config: AcpAgentConfig = serde_json::from_str(
+        r#"{"command": "agent", "capabilities": {"readTextFile": true}}"#,
+    )
+    .expect("Failed to parse AcpAgentConfig");
+
+    let capabilities = ClientCapabilities::from(config.capabilities.as_ref().unwrap());
+    assert!(capabilities.fs.read_text_file);
+    assert!(!capabilities.fs.write_text_file);
+    assert!(!capabilities.terminal);
+}
+
+#[test]
+fn test_file_requests_are_confined_to_session_cwd() {
+    use super::runtime::confined_request_path;
+    let root = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let outside = tempfile::TempDir::new().expect("Failed to create temp dir");
+    std::fs::create_dir(root.path().join("src")).unwrap();
+    std::fs::write(root.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(outside.path().join("secret.txt"), "secret\n").unwrap();
+
+    let inside = root.path().join("src/main.rs");
+    assert!(confined_request_path(root.path(), &inside).is_ok());
+    // New files may be created in existing directories of the session
+    assert!(confined_request_path(root.path(), &root.path().join("src/new.rs")).is_ok());
+
+    let rejected = [
+        std::path::PathBuf::from("src/main.rs"),
+        outside.path().join("secret.txt"),
+        root.path().join("src/../../secret.txt"),
+        root.path().join("missing/new.rs"),
+    ];
+    for path in rejected {
+        assert!(
+            confined_request_path(root.path(), &path).is_err(),
+            "Expected {} to be rejected",
+            path.display()
+        );
+    }
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(outside.path(), root.path().join("link")).unwrap();
+        let through_link = root.path().join("link/secret.txt");
+        assert!(confined_request_path(root.path(), &through_link).is_err());
+    }
+}
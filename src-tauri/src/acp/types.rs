@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use agent_client_protocol::{Implementation, RequestPermissionRequest, SessionNotification};
+use agent_client_protocol::{
+    ClientCapabilities, FileSystemCapability, Implementation, RequestPermissionRequest,
+    SessionNotification,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +16,29 @@ pub struct AcpAgentConfig {
     #[serde(default)]
     pub env: HashMap<String, String>,
     pub cwd: Option<String>,
+    /// Capabilities advertised to this agent; `None` advertises the protocol defaults
+    #[serde(default)]
+    pub capabilities: Option<ClientCapabilitiesDto>,
+}
+
+/// Client capabilities a connection advertises during `initialize`. Terminals aren't
+/// implemented, so they are never advertised.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientCapabilitiesDto {
+    #[serde(default)]
+    pub read_text_file: bool,
+    #[serde(default)]
+    pub write_text_file: bool,
+}
+
+impl From<&ClientCapabilitiesDto> for ClientCapabilities {
+    fn from(dto: &ClientCapabilitiesDto) -> Self {
+        ClientCapabilities::new()
+            .fs(FileSystemCapability::new()
+                .read_text_file(dto.read_text_file)
+                .write_text_file(dto.write_text_file))
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -46,6 +72,9 @@ pub struct AcpConnectionStateEvent {
 #[serde(rename_all = "camelCase")]
 pub struct AcpSessionUpdateEvent {
     pub connection_id: String,
+    /// Id of the prompt that produced this update, if one is in flight
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_id: Option<String>,
     pub notification: SessionNotification,
 }
 
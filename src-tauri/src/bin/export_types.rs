@@ -2,10 +2,121 @@ use std::fs;
 use std::path::PathBuf;
 
 use parallel_cli_runner_lib::git::{
-    BranchInfoDto, CommitInfoDto, DiffCompareKind, DiffDeltaStatus, DiffFileSummaryDto,
-    DiffMetaDto, DiffRequestDto, DiffRequestOptionsDto, DiffResponseDto, DiffStatDto,
-    FileChangeType, FileStats, FileStatusDto, RemoteInfoDto, RepoInfoDto, RepoStatusDto,
-    StashInfoDto, SubmoduleInfoDto, TagInfoDto, WorktreeInfoDto,
+    BranchInfoDto, CommitInfoDto, CommitLintDto, DiffCompareKind, DiffDeltaStatus,
+    DiffFileSummaryDto, DiffMetaDto, DiffRequestDto, DiffRequestOptionsDto, DiffResponseDto,
+    DiffStatDto, FileChangeType, FileStats, FileStatusDto, GitNetworkOptions, ReflogEntryDto,
+    CommitPageDto, ConflictSide, ConflictVersionsDto, RemoteInfoDto, RepoOperationState,
+    SquashPreviewDto, AmendPreviewDto, TreeEntryDto, WorktreeStatusDto, ScanProgress,
+    GitCredentialRequestDto, MergeDiffMode, RepoStatsDto, RepoOverviewDto, AuthorStatDto,
+    MergePreviewDto, FastForwardMode, ObjectKind, ObjectDto, WorktreeSuggestionDto,
+    RepoInfoDto, RepoStatusDto, StashInfoDto, SubmoduleInfoDto, TagInfoDto, WorktreeInfoDto,
+};
+use parallel_cli_runner_lib::ws::{
+    AcpAgentConfigParams,
+    AcpConnectionIdParams,
+    AcpExtMethodParams,
+    AcpPermissionOutcomeDto,
+    AcpPermissionReplyParams,
+    AcpSessionCancelParams,
+    AcpSessionLoadParams,
+    AcpSessionNewParams,
+    AcpSessionPromptParams,
+    AcpValidateMcpParams,
+    BroadcastLineParams,
+    ClipboardWriteParams,
+    CreateSessionParams,
+    CreateSessionsParams,
+    CwdParams,
+    EventsLaggedPayload,
+    GitAddRemoteParams,
+    GitAddWorktreeParams,
+    GitAmendPreviewParams,
+    GitApplyPatchParams,
+    GitApplyStashParams,
+    GitArchiveParams,
+    GitAuthorStatsParams,
+    GitBranchSyncStatusParams,
+    GitCanMergeParams,
+    GitCheckoutBranchParams,
+    GitCommitFilesParams,
+    GitCommitParams,
+    GitCommitsInRemoteParams,
+    GitConflictVersionsParams,
+    GitCreateBranchParams,
+    GitCredentialReplyParams,
+    GitDeleteBranchParams,
+    GitDiffParams,
+    GitDiffWorktreesParams,
+    GitDiscardFilesParams,
+    GitDropCommitsParams,
+    GitDropStashParams,
+    GitExpandContextParams,
+    GitFetchParams,
+    GitFormatPatchParams,
+    GitGcParams,
+    GitHasAnyRepoParams,
+    GitLastCommitForPathParams,
+    GitListCommitsParams,
+    GitListCommitsRangeParams,
+    GitListReflogParams,
+    GitListTagsParams,
+    GitListTreeParams,
+    GitLogForPathParams,
+    GitMergeParams,
+    GitMoveWorktreeParams,
+    GitPullParams,
+    GitPushParams,
+    GitReadObjectParams,
+    GitRebaseParams,
+    GitRemoveRemoteParams,
+    GitRemoveWorktreeParams,
+    GitRenameStashParams,
+    GitReorderCommitsParams,
+    GitResetParams,
+    GitResolveConflictParams,
+    GitRestoreToReflogParams,
+    GitRevertParams,
+    GitScanReposParams,
+    GitSetRemoteUrlParams,
+    GitSetSparseCheckoutParams,
+    GitSmartCheckoutParams,
+    GitSquashParams,
+    GitStageAllParams,
+    GitStageFilesParams,
+    GitStashSaveParams,
+    GitSuggestWorktreeNameParams,
+    GitUnpushedCommitsParams,
+    GitUnstageAllParams,
+    GitUnstageFilesParams,
+    GitValidateCommitMessageParams,
+    GroupMemberStatus,
+    GroupStatusParams,
+    LogLinePayload,
+    OpenDialogParams,
+    OpenPathParams,
+    RepoChangedPayload,
+    ResizeSessionParams,
+    RevealPathParams,
+    ScanRootChangedPayload,
+    ServerInfo,
+    SessionGroupStatus,
+    SessionIdParams,
+    SessionSignal,
+    SessionSpec,
+    SetLogLevelParams,
+    SetSessionCwdParams,
+    SignalSessionParams,
+    StreamLogParams,
+    TailLogParams,
+    TransportBatchItem,
+    TransportBatchRequest,
+    TransportBatchResponse,
+    TransportCancel,
+    TransportError,
+    TransportEvent,
+    TransportRequest,
+    TransportResponse,
+    WriteSessionParams,
 };
 use ts_rs::TS;
 
@@ -60,11 +171,173 @@ fn main() {
     git_ts.push_str("\n\n");
     git_ts.push_str(&StashInfoDto::decl().replace("type ", "export type "));
     git_ts.push_str("\n\n");
+    git_ts.push_str(&ReflogEntryDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
     git_ts.push_str(&TagInfoDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&CommitLintDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&SquashPreviewDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&RepoOperationState::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&ConflictVersionsDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&CommitPageDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&RepoStatsDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&MergeDiffMode::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&GitCredentialRequestDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&ScanProgress::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&WorktreeStatusDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&TreeEntryDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&AmendPreviewDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&RepoOverviewDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&AuthorStatDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&MergePreviewDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&FastForwardMode::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&ObjectKind::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&ObjectDto::decl().replace("type ", "export type "));
+    git_ts.push_str("\n\n");
+    git_ts.push_str(&WorktreeSuggestionDto::decl().replace("type ", "export type "));
 
     fs::write(types_dir.join("git.ts"), git_ts).expect("failed to write git.ts");
 
+    // transport.ts
+    let mut transport_ts = String::from("// This file was generated by [ts-rs]. Do not edit this file manually.\n");
+    transport_ts.push_str("// To regenerate: cargo run --bin export_types --manifest-path src-tauri/Cargo.toml\n\n");
+    let transport_decls = [
+        TransportRequest::decl(),
+        TransportBatchRequest::decl(),
+        TransportBatchItem::decl(),
+        TransportCancel::decl(),
+        TransportResponse::decl(),
+        TransportBatchResponse::decl(),
+        TransportError::decl(),
+        TransportEvent::decl(),
+        ServerInfo::decl(),
+        EventsLaggedPayload::decl(),
+        RepoChangedPayload::decl(),
+        ScanRootChangedPayload::decl(),
+        SetLogLevelParams::decl(),
+        TailLogParams::decl(),
+        StreamLogParams::decl(),
+        LogLinePayload::decl(),
+        CreateSessionParams::decl(),
+        SessionSpec::decl(),
+        CreateSessionsParams::decl(),
+        GroupStatusParams::decl(),
+        GroupMemberStatus::decl(),
+        SessionGroupStatus::decl(),
+        SessionIdParams::decl(),
+        WriteSessionParams::decl(),
+        SetSessionCwdParams::decl(),
+        SessionSignal::decl(),
+        SignalSessionParams::decl(),
+        ResizeSessionParams::decl(),
+        BroadcastLineParams::decl(),
+        CwdParams::decl(),
+        GitDiffParams::decl(),
+        GitListCommitsParams::decl(),
+        GitListCommitsRangeParams::decl(),
+        GitLastCommitForPathParams::decl(),
+        GitLogForPathParams::decl(),
+        GitUnpushedCommitsParams::decl(),
+        GitScanReposParams::decl(),
+        GitHasAnyRepoParams::decl(),
+        GitAuthorStatsParams::decl(),
+        GitCanMergeParams::decl(),
+        GitListTagsParams::decl(),
+        GitListReflogParams::decl(),
+        GitValidateCommitMessageParams::decl(),
+        GitRestoreToReflogParams::decl(),
+        GitApplyStashParams::decl(),
+        GitRenameStashParams::decl(),
+        GitNetworkOptions::decl(),
+        GitPullParams::decl(),
+        GitPushParams::decl(),
+        GitCommitParams::decl(),
+        GitAmendPreviewParams::decl(),
+        GitStageFilesParams::decl(),
+        GitResetParams::decl(),
+        GitRevertParams::decl(),
+        GitCommitFilesParams::decl(),
+        GitSquashParams::decl(),
+        GitDropCommitsParams::decl(),
+        GitReorderCommitsParams::decl(),
+        GitCommitsInRemoteParams::decl(),
+        GitMergeParams::decl(),
+        GitRebaseParams::decl(),
+        GitCreateBranchParams::decl(),
+        GitCheckoutBranchParams::decl(),
+        GitSmartCheckoutParams::decl(),
+        GitStageAllParams::decl(),
+        GitUnstageAllParams::decl(),
+        GitUnstageFilesParams::decl(),
+        GitDiscardFilesParams::decl(),
+        ConflictSide::decl(),
+        GitResolveConflictParams::decl(),
+        GitConflictVersionsParams::decl(),
+        GitExpandContextParams::decl(),
+        GitListTreeParams::decl(),
+        GitReadObjectParams::decl(),
+        GitBranchSyncStatusParams::decl(),
+        GitArchiveParams::decl(),
+        GitApplyPatchParams::decl(),
+        GitFormatPatchParams::decl(),
+        GitGcParams::decl(),
+        GitSetRemoteUrlParams::decl(),
+        GitAddRemoteParams::decl(),
+        GitRemoveRemoteParams::decl(),
+        GitFetchParams::decl(),
+        GitCredentialReplyParams::decl(),
+        GitDiffWorktreesParams::decl(),
+        GitMoveWorktreeParams::decl(),
+        GitAddWorktreeParams::decl(),
+        GitSuggestWorktreeNameParams::decl(),
+        GitSetSparseCheckoutParams::decl(),
+        GitRemoveWorktreeParams::decl(),
+        GitStashSaveParams::decl(),
+        GitDeleteBranchParams::decl(),
+        GitDropStashParams::decl(),
+        OpenDialogParams::decl(),
+        OpenPathParams::decl(),
+        RevealPathParams::decl(),
+        ClipboardWriteParams::decl(),
+        AcpConnectionIdParams::decl(),
+        AcpExtMethodParams::decl(),
+        AcpValidateMcpParams::decl(),
+        AcpSessionNewParams::decl(),
+        AcpSessionLoadParams::decl(),
+        AcpSessionPromptParams::decl(),
+        AcpSessionCancelParams::decl(),
+        AcpPermissionReplyParams::decl(),
+        AcpPermissionOutcomeDto::decl(),
+        AcpAgentConfigParams::decl(),
+    ];
+    transport_ts.push_str(
+        &transport_decls
+            .map(|decl| decl.replace("type ", "export type "))
+            .join("\n\n"),
+    );
+
+    fs::write(types_dir.join("transport.ts"), transport_ts)
+        .expect("failed to write transport.ts");
+
     println!("TypeScript types exported to: {}", types_dir.join("git.ts").display());
+    println!("TypeScript types exported to: {}", types_dir.join("transport.ts").display());
     println!("\nNote: git-ui.ts types are manually maintained and serve as UI-layer types.");
     println!("They are intentionally separate from DTOs and may have different field names.");
 }
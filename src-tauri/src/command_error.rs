@@ -5,7 +5,11 @@ use crate::error::{AppError, codes};
 #[derive(Debug, Clone, Serialize)]
 pub struct CommandError {
     pub code: String,
+    /// One-line summary suitable for a toast or dialog title
     pub message: String,
+    /// Full multi-line output behind `message`, such as git's stderr
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
 }
 
 impl CommandError {
@@ -13,19 +17,30 @@ impl CommandError {
         Self {
             code: code.into(),
             message: message.into(),
+            details: None,
         }
     }
 
     pub fn internal(err: impl ToString) -> Self {
         Self::new(codes::INTERNAL_ERROR, err.to_string())
     }
+
+    /// Error for work the user aborted, so clients can tell it apart from a failure.
+    pub fn cancelled() -> Self {
+        Self::new(codes::CANCELLED, "request cancelled")
+    }
 }
 
 impl From<AppError> for CommandError {
     fn from(err: AppError) -> Self {
         let code = err.code().to_string();
         let message = err.user_message();
-        Self { code, message }
+        let details = err.details();
+        Self {
+            code,
+            message,
+            details,
+        }
     }
 }
 
@@ -5,6 +5,7 @@ use tauri::{AppHandle, Emitter, Manager};
 use uuid::Uuid;
 use agent_client_protocol::{
     ContentBlock, McpServer, PermissionOptionId, RequestPermissionOutcome, SelectedPermissionOutcome,
+    StopReason,
 };
 
 use crate::command_error::CommandError;
@@ -26,6 +27,12 @@ pub struct AcpConnectionIdParams {
     id: String,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcpValidateMcpParams {
+    mcp_servers: Vec<McpServer>,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AcpSessionNewParams {
@@ -48,6 +55,7 @@ pub struct AcpSessionLoadParams {
 pub struct AcpSessionPromptParams {
     session_id: String,
     prompt: Vec<ContentBlock>,
+    prompt_id: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -127,7 +135,9 @@ pub async fn acp_chat(
 
     tauri::async_runtime::spawn(async move {
         // Send the prompt
-        let result = manager_clone.prompt(session_id.clone(), content_blocks).await;
+        let result = manager_clone
+            .prompt(session_id.clone(), content_blocks, stream_id_clone.clone())
+            .await;
 
         match result {
             Ok(prompt_response) => {
@@ -188,6 +198,35 @@ pub async fn acp_disconnect(
         .map_err(|e| CommandError::internal(format!("Failed to disconnect ACP agent: {e}")))
 }
 
+#[tauri::command(rename_all = "camelCase")]
+pub async fn acp_ping(app: AppHandle, params: AcpConnectionIdParams) -> Result<bool, CommandError> {
+    let manager = app.state::<Arc<AcpManager>>().inner().clone();
+    let connection_id = super::parse_uuid(&params.id)?;
+    manager
+        .ping_connection(connection_id)
+        .await
+        .map_err(|e| CommandError::new("not_found", e.to_string()))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub fn acp_get_stderr(
+    app: AppHandle,
+    params: AcpConnectionIdParams,
+) -> Result<Vec<String>, CommandError> {
+    let manager = app.state::<Arc<AcpManager>>().inner().clone();
+    let connection_id = super::parse_uuid(&params.id)?;
+    if manager.get_info(connection_id).is_none() {
+        return Err(CommandError::new("not_found", "acp connection not found"));
+    }
+    Ok(manager.get_agent_stderr(connection_id))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn acp_validate_mcp(params: AcpValidateMcpParams) -> Result<(), CommandError> {
+    acp::validate_mcp_servers(&params.mcp_servers)
+        .map_err(|err| CommandError::new("invalid_argument", err))
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn acp_session_new(
     app: AppHandle,
@@ -222,13 +261,19 @@ pub async fn acp_session_load(
 pub async fn acp_session_prompt(
     app: AppHandle,
     params: AcpSessionPromptParams,
-) -> Result<(), CommandError> {
+) -> Result<String, CommandError> {
     let manager = app.state::<Arc<AcpManager>>().inner().clone();
-    manager
-        .prompt(params.session_id, params.prompt)
+    let prompt_id = params
+        .prompt_id
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let response = manager
+        .prompt(params.session_id, params.prompt, prompt_id.clone())
         .await
         .map_err(|e| CommandError::internal(format!("ACP prompt failed: {e}")))?;
-    Ok(())
+    if response.stop_reason == StopReason::Cancelled {
+        return Err(CommandError::cancelled());
+    }
+    Ok(prompt_id)
 }
 
 #[tauri::command(rename_all = "camelCase")]
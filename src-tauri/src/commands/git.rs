@@ -1,9 +1,10 @@
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, State};
 
 use crate::command_error::CommandError;
 use crate::git::{self, DiffRequestDto, DiffResponseDto, RepoInfoDto, RepoStatusDto};
 use crate::utils::{with_cwd, with_repo_root};
 use std::path::PathBuf;
+use std::sync::atomic::Ordering;
 
 #[tauri::command]
 pub async fn git_detect_repo(cwd: String) -> Result<Option<String>, CommandError> {
@@ -12,16 +13,39 @@ pub async fn git_detect_repo(cwd: String) -> Result<Option<String>, CommandError
     })
 }
 
-#[tauri::command]
+#[tauri::command(rename_all = "camelCase")]
 pub async fn git_scan_repos(
     app: AppHandle,
+    scans: State<'_, git::ActiveScan>,
     cwd: String,
+    follow_symlinks: Option<bool>,
 ) -> Result<Vec<RepoInfoDto>, CommandError> {
-    with_cwd(cwd, |path| {
-        git::scan_repos(path, |p| {
+    let cancel = scans.begin();
+    let follow_symlinks = follow_symlinks.unwrap_or(false);
+    let repos = with_cwd(cwd, |path| {
+        git::scan_repos(path, follow_symlinks, &cancel, |p| {
             let _ = app.emit("scan-progress", p);
         })
-    })
+    })?;
+    if cancel.load(Ordering::Relaxed) {
+        return Err(CommandError::cancelled());
+    }
+    Ok(repos)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_has_any_repo(
+    cwd: String,
+    max_checks: Option<usize>,
+) -> Result<bool, CommandError> {
+    let max_checks = max_checks.unwrap_or(git::DEFAULT_REPO_PROBE_CHECKS);
+    with_cwd(cwd, |path| git::has_any_repo(path, max_checks))
+}
+
+#[tauri::command]
+pub async fn git_cancel_scan(scans: State<'_, git::ActiveScan>) -> Result<(), CommandError> {
+    scans.cancel();
+    Ok(())
 }
 
 #[tauri::command]
@@ -29,6 +53,21 @@ pub async fn git_status(cwd: String) -> Result<RepoStatusDto, CommandError> {
     with_cwd(cwd, git::status)
 }
 
+#[tauri::command]
+pub async fn git_repo_overview(cwd: String) -> Result<git::RepoOverviewDto, CommandError> {
+    with_cwd(cwd, git::repo_overview)
+}
+
+#[tauri::command]
+pub async fn git_is_clean(cwd: String) -> Result<bool, CommandError> {
+    with_cwd(cwd, git::is_clean)
+}
+
+#[tauri::command]
+pub async fn git_operation_state(cwd: String) -> Result<git::RepoOperationState, CommandError> {
+    with_cwd(cwd, git::operation_state)
+}
+
 #[tauri::command]
 pub async fn git_diff(cwd: String, pathspecs: Vec<String>) -> Result<String, CommandError> {
     with_cwd(cwd, |path| git::diff(path, &pathspecs))
@@ -44,6 +83,16 @@ pub async fn git_list_branches(cwd: String) -> Result<Vec<git::BranchInfoDto>, C
     with_cwd(cwd, git::list_branches)
 }
 
+#[tauri::command]
+pub async fn git_repo_stats(cwd: String) -> Result<git::RepoStatsDto, CommandError> {
+    with_cwd(cwd, git::repo_stats)
+}
+
+#[tauri::command]
+pub async fn git_gc(cwd: String, aggressive: Option<bool>) -> Result<(), CommandError> {
+    with_cwd(cwd, |path| git::gc(path, aggressive.unwrap_or(false)))
+}
+
 #[tauri::command]
 pub async fn git_list_remote_branches(
     cwd: String,
@@ -51,6 +100,14 @@ pub async fn git_list_remote_branches(
     with_cwd(cwd, git::list_remote_branches)
 }
 
+#[tauri::command]
+pub async fn git_branch_sync_status(
+    cwd: String,
+    branch: String,
+) -> Result<(i32, i32), CommandError> {
+    with_cwd(cwd, |path| git::branch_sync_status(path, &branch))
+}
+
 #[tauri::command]
 pub async fn git_list_commits(
     cwd: String,
@@ -60,6 +117,15 @@ pub async fn git_list_commits(
     with_cwd(cwd, |path| git::list_commits(path, limit, skip))
 }
 
+#[tauri::command]
+pub async fn git_list_commits_paged(
+    cwd: String,
+    limit: usize,
+    skip: Option<usize>,
+) -> Result<git::CommitPageDto, CommandError> {
+    with_cwd(cwd, |path| git::list_commits_paged(path, limit, skip))
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn git_list_commits_range(
     cwd: String,
@@ -69,6 +135,43 @@ pub async fn git_list_commits_range(
     with_cwd(cwd, |path| git::list_commits_range(path, &include_branch, &exclude_branch))
 }
 
+#[tauri::command]
+pub async fn git_last_commit_for_path(
+    cwd: String,
+    path: String,
+    rev: Option<String>,
+) -> Result<Option<git::CommitInfoDto>, CommandError> {
+    with_cwd(cwd, |repo| git::last_commit_for_path(repo, &path, rev))
+}
+
+#[tauri::command]
+pub async fn git_log_for_path(
+    cwd: String,
+    path: String,
+    limit: usize,
+    follow: Option<bool>,
+) -> Result<Vec<git::CommitInfoDto>, CommandError> {
+    with_cwd(cwd, |repo| git::log_for_path(repo, &path, limit, follow.unwrap_or(false)))
+}
+
+#[tauri::command]
+pub async fn git_unpushed_commits(
+    cwd: String,
+    branch: Option<String>,
+) -> Result<Vec<git::CommitInfoDto>, CommandError> {
+    with_cwd(cwd, |repo| git::unpushed_commits(repo, branch))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_author_stats(
+    cwd: String,
+    limit: usize,
+    exclude_merges: Option<bool>,
+) -> Result<Vec<git::AuthorStatDto>, CommandError> {
+    let exclude_merges = exclude_merges.unwrap_or(false);
+    with_cwd(cwd, |path| git::author_stats(path, limit, exclude_merges))
+}
+
 #[tauri::command]
 pub async fn git_list_worktrees(
     cwd: String,
@@ -76,11 +179,38 @@ pub async fn git_list_worktrees(
     with_cwd(cwd, git::list_worktrees)
 }
 
+#[tauri::command]
+pub async fn git_worktrees_with_stats(
+    cwd: String,
+) -> Result<Vec<git::WorktreeStatusDto>, CommandError> {
+    with_cwd(cwd, git::worktrees_with_stats)
+}
+
 #[tauri::command]
 pub async fn git_list_remotes(cwd: String) -> Result<Vec<git::RemoteInfoDto>, CommandError> {
     with_cwd(cwd, git::list_remotes)
 }
 
+#[tauri::command]
+pub async fn git_set_remote_url(
+    cwd: String,
+    name: String,
+    url: String,
+    push: Option<bool>,
+) -> Result<(), CommandError> {
+    with_cwd(cwd, |path| git::set_remote_url(path, &name, &url, push.unwrap_or(false)))
+}
+
+#[tauri::command]
+pub async fn git_add_remote(cwd: String, name: String, url: String) -> Result<(), CommandError> {
+    with_cwd(cwd, |path| git::add_remote(path, &name, &url))
+}
+
+#[tauri::command]
+pub async fn git_remove_remote(cwd: String, name: String) -> Result<(), CommandError> {
+    with_cwd(cwd, |path| git::remove_remote(path, &name))
+}
+
 #[tauri::command]
 pub async fn git_list_submodules(
     cwd: String,
@@ -102,6 +232,33 @@ pub async fn git_list_tags(
     with_cwd(cwd, |path| git::list_tags(path, limit, skip))
 }
 
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_list_reflog(
+    cwd: String,
+    reference: Option<String>,
+    limit: usize,
+) -> Result<Vec<git::ReflogEntryDto>, CommandError> {
+    with_cwd(cwd, |path| git::list_reflog(path, reference, limit))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_restore_to_reflog(
+    cwd: String,
+    reference: String,
+    index: usize,
+) -> Result<(), CommandError> {
+    with_cwd(cwd, |path| git::restore_to_reflog(path, &reference, index))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_validate_commit_message(
+    message: String,
+    max_subject_length: Option<usize>,
+) -> Result<git::CommitLintDto, CommandError> {
+    let max_subject_length = max_subject_length.unwrap_or(git::DEFAULT_MAX_SUBJECT_LENGTH);
+    git::validate_commit_message_with_limit(&message, max_subject_length).map_err(CommandError::from)
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn git_apply_stash(cwd: String, index: i32) -> Result<(), CommandError> {
     with_cwd(cwd, |path| git::apply_stash(path, index))
@@ -113,13 +270,136 @@ pub async fn git_drop_stash(cwd: String, index: i32) -> Result<(), CommandError>
 }
 
 #[tauri::command(rename_all = "camelCase")]
-pub async fn git_pull(cwd: String) -> Result<(), CommandError> {
-    with_cwd(cwd, git::pull)
+pub async fn git_rename_stash(cwd: String, index: i32, message: String) -> Result<(), CommandError> {
+    with_cwd(cwd, |path| git::rename_stash(path, index, &message))
 }
 
 #[tauri::command(rename_all = "camelCase")]
-pub async fn git_push(cwd: String, force: bool) -> Result<(), CommandError> {
-    with_cwd(cwd, |path| git::push(path, force))
+pub async fn git_commit_files(
+    cwd: String,
+    commit: String,
+) -> Result<Vec<git::DiffFileSummaryDto>, CommandError> {
+    with_cwd(cwd, |path| git::commit_files(path, &commit))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_stash_diff(cwd: String, index: i32) -> Result<git::DiffResponseDto, CommandError> {
+    with_cwd(cwd, |path| git::stash_diff(path, index))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_fetch(
+    app: AppHandle,
+    credentials: State<'_, git::CredentialBroker>,
+    cwd: String,
+    remote: Option<String>,
+    network: Option<git::GitNetworkOptions>,
+) -> Result<(), CommandError> {
+    let network = network.unwrap_or_default();
+    let remote = remote.unwrap_or_else(|| "origin".to_string());
+    let credentials = credentials.inner().clone();
+    run_blocking(move || {
+        with_cwd(cwd, |path| {
+            let prompt = credentials.prompt(|request| {
+                let _ = app.emit("git-credential-request", request);
+            });
+            git::fetch(path, &remote, &network, &prompt)
+        })
+    })
+    .await
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_credential_reply(
+    credentials: State<'_, git::CredentialBroker>,
+    request_id: String,
+    username: Option<String>,
+    password: Option<String>,
+) -> Result<(), CommandError> {
+    let credential = match (username, password) {
+        (Some(username), Some(password)) => Some(git::GitCredential { username, password }),
+        (None, None) => None,
+        _ => {
+            return Err(CommandError::new(
+                "invalid_argument",
+                "send both username and password to answer, or neither to cancel",
+            ));
+        }
+    };
+    credentials.reply(&request_id, credential)?;
+    Ok(())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_pull(
+    app: AppHandle,
+    credentials: State<'_, git::CredentialBroker>,
+    cwd: String,
+    network: Option<git::GitNetworkOptions>,
+) -> Result<(), CommandError> {
+    let network = network.unwrap_or_default();
+    let credentials = credentials.inner().clone();
+    run_blocking(move || {
+        with_cwd(cwd, |path| {
+            let prompt = credentials.prompt(|request| {
+                let _ = app.emit("git-credential-request", request);
+            });
+            git::pull(path, &network, &prompt)
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn git_invalidate_proxy_cache() {
+    git::invalidate_proxy_cache();
+}
+
+#[tauri::command(rename_all = "camelCase")]
+#[allow(clippy::too_many_arguments)]
+pub async fn git_push(
+    app: AppHandle,
+    credentials: State<'_, git::CredentialBroker>,
+    cwd: String,
+    force: bool,
+    force_with_lease: Option<bool>,
+    remote: Option<String>,
+    local_branch: Option<String>,
+    remote_branch: Option<String>,
+    set_upstream: Option<bool>,
+    network: Option<git::GitNetworkOptions>,
+) -> Result<(), CommandError> {
+    let options = git::GitPushOptions {
+        force,
+        force_with_lease: force_with_lease.unwrap_or(false),
+        remote,
+        local_branch,
+        remote_branch,
+        set_upstream: set_upstream.unwrap_or(false),
+    };
+    let network = network.unwrap_or_default();
+    let credentials = credentials.inner().clone();
+    run_blocking(move || {
+        with_cwd(cwd, |path| {
+            let prompt = credentials.prompt(|request| {
+                let _ = app.emit("git-credential-request", request);
+            });
+            git::push(path, &options, &network, &prompt)
+        })
+    })
+    .await
+}
+
+/// Runs git work that can block for a long time, such as a network round trip waiting on
+/// a credential prompt, on the blocking pool instead of an async worker.
+async fn run_blocking<T, F>(task: F) -> Result<T, CommandError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, CommandError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(CommandError::internal)?
 }
 
 #[tauri::command(rename_all = "camelCase")]
@@ -128,8 +408,18 @@ pub async fn git_commit(
     message: String,
     stage_all: bool,
     amend: bool,
+    co_authors: Option<Vec<String>>,
 ) -> Result<(), CommandError> {
-    with_cwd(cwd, |path| git::commit(path, &message, stage_all, amend))
+    let co_authors = co_authors.unwrap_or_default();
+    with_cwd(cwd, |path| git::commit(path, &message, stage_all, amend, &co_authors))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_amend_preview(
+    cwd: String,
+    stage_all: bool,
+) -> Result<git::AmendPreviewDto, CommandError> {
+    with_cwd(cwd, |path| git::amend_preview(path, stage_all))
 }
 
 #[tauri::command(rename_all = "camelCase")]
@@ -147,6 +437,48 @@ pub async fn git_discard_files(cwd: String, paths: Vec<String>) -> Result<(), Co
     with_cwd(cwd, |path| git::discard_paths(path, &paths))
 }
 
+#[tauri::command]
+pub async fn git_resolve_conflict(
+    cwd: String,
+    path: String,
+    side: git::ConflictSide,
+) -> Result<(), CommandError> {
+    with_cwd(cwd, |repo| git::resolve_conflict(repo, &path, side))
+}
+
+#[tauri::command]
+pub async fn git_conflict_versions(
+    cwd: String,
+    path: String,
+) -> Result<git::ConflictVersionsDto, CommandError> {
+    with_cwd(cwd, |repo| git::conflict_versions(repo, &path))
+}
+
+#[tauri::command]
+pub async fn git_expand_context(
+    cwd: String,
+    path: String,
+    rev: String,
+    start_line: usize,
+    count: usize,
+) -> Result<Vec<String>, CommandError> {
+    with_cwd(cwd, |repo| git::expand_context(repo, &path, &rev, start_line, count))
+}
+
+#[tauri::command]
+pub async fn git_list_tree(
+    cwd: String,
+    rev: String,
+    path: String,
+) -> Result<Vec<git::TreeEntryDto>, CommandError> {
+    with_cwd(cwd, |repo| git::list_tree(repo, &rev, &path))
+}
+
+#[tauri::command]
+pub async fn git_read_object(cwd: String, oid: String) -> Result<git::ObjectDto, CommandError> {
+    with_cwd(cwd, |repo| git::read_object(repo, &oid))
+}
+
 #[tauri::command]
 pub async fn git_stage_all(cwd: String) -> Result<(), CommandError> {
     with_cwd(cwd, git::stage_all)
@@ -157,14 +489,33 @@ pub async fn git_unstage_all(cwd: String) -> Result<(), CommandError> {
     with_cwd(cwd, git::unstage_all)
 }
 
+#[tauri::command]
+pub async fn git_can_merge(
+    cwd: String,
+    target: String,
+    source: String,
+) -> Result<git::MergePreviewDto, CommandError> {
+    with_cwd(cwd, |path| git::can_merge_cleanly(path, &target, &source))
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn git_merge_into_branch(
     repo_root: String,
     target_branch: String,
     source_branch: String,
+    auto_stash: Option<bool>,
+    fast_forward: Option<git::FastForwardMode>,
 ) -> Result<(), CommandError> {
+    let auto_stash = auto_stash.unwrap_or(true);
+    let fast_forward = fast_forward.unwrap_or_default();
     with_repo_root(repo_root, |path| {
-        git::merge_into_branch(path, &target_branch, &source_branch)
+        git::merge_into_branch_with_mode(
+            path,
+            &target_branch,
+            &source_branch,
+            auto_stash,
+            fast_forward,
+        )
     })
 }
 
@@ -197,8 +548,13 @@ pub async fn git_detach_worktree_head(cwd: String) -> Result<(), CommandError> {
 }
 
 #[tauri::command(rename_all = "camelCase")]
-pub async fn git_smart_checkout_branch(cwd: String, branch_name: String) -> Result<(), CommandError> {
-    with_cwd(cwd, |path| git::smart_checkout_branch(path, &branch_name))
+pub async fn git_smart_checkout_branch(
+    cwd: String,
+    branch_name: String,
+    auto_stash: Option<bool>,
+) -> Result<(), CommandError> {
+    let auto_stash = auto_stash.unwrap_or(true);
+    with_cwd(cwd, |path| git::smart_checkout_branch(path, &branch_name, auto_stash))
 }
 
 #[tauri::command(rename_all = "camelCase")]
@@ -211,9 +567,90 @@ pub async fn git_revert(cwd: String, commit: String) -> Result<(), CommandError>
     with_cwd(cwd, |path| git::revert(path, &commit))
 }
 
+#[tauri::command]
+pub async fn git_rebase_abort(cwd: String) -> Result<(), CommandError> {
+    with_cwd(cwd, git::rebase_abort)
+}
+
+#[tauri::command]
+pub async fn git_rebase_continue(cwd: String) -> Result<(), CommandError> {
+    with_cwd(cwd, git::rebase_continue)
+}
+
+#[tauri::command]
+pub async fn git_revert_abort(cwd: String) -> Result<(), CommandError> {
+    with_cwd(cwd, git::revert_abort)
+}
+
+#[tauri::command]
+pub async fn git_cherry_pick_abort(cwd: String) -> Result<(), CommandError> {
+    with_cwd(cwd, git::cherry_pick_abort)
+}
+
+#[tauri::command]
+pub async fn git_clear_pending_message(cwd: String) -> Result<(), CommandError> {
+    with_cwd(cwd, git::clear_pending_message)
+}
+
 #[tauri::command(rename_all = "camelCase")]
-pub async fn git_squash_commits(cwd: String, commits: Vec<String>) -> Result<(), CommandError> {
-    with_cwd(cwd, |path| git::squash_commits(path, &commits))
+pub async fn git_archive(
+    cwd: String,
+    rev: String,
+    format: String,
+    out_path: String,
+) -> Result<(), CommandError> {
+    with_cwd(cwd, |path| {
+        let format = format.parse::<git::ArchiveFormat>()?;
+        git::archive(path, &rev, format, &PathBuf::from(&out_path))
+    })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_apply_patch(
+    cwd: String,
+    patch: String,
+    check_only: Option<bool>,
+    three_way: Option<bool>,
+) -> Result<(), CommandError> {
+    with_cwd(cwd, |path| {
+        git::apply_patch(
+            path,
+            &patch,
+            check_only.unwrap_or(false),
+            three_way.unwrap_or(false),
+        )
+    })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_format_patch(
+    cwd: String,
+    range: String,
+    out_dir: String,
+) -> Result<Vec<String>, CommandError> {
+    with_cwd(cwd, |path| git::format_patch(path, &range, &PathBuf::from(&out_dir)))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_squash_commits(
+    cwd: String,
+    commits: Vec<String>,
+    message: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<git::SquashPreviewDto, CommandError> {
+    with_cwd(cwd, |path| {
+        git::squash_commits(path, &commits, message.as_deref(), dry_run.unwrap_or(false))
+    })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_drop_commits(cwd: String, commits: Vec<String>) -> Result<(), CommandError> {
+    with_cwd(cwd, |path| git::drop_commits(path, &commits))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_reorder_commits(cwd: String, commits: Vec<String>) -> Result<(), CommandError> {
+    with_cwd(cwd, |path| git::reorder_commits(path, &commits))
 }
 
 #[tauri::command(rename_all = "camelCase")]
@@ -221,6 +658,17 @@ pub async fn git_commits_in_remote(cwd: String, commits: Vec<String>) -> Result<
     with_cwd(cwd, |path| git::commits_in_remote(path, &commits))
 }
 
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_diff_worktrees(
+    repo_root: String,
+    path_a: String,
+    path_b: String,
+) -> Result<DiffResponseDto, CommandError> {
+    with_repo_root(repo_root, |root| {
+        git::diff_worktrees(root, &PathBuf::from(path_a), &PathBuf::from(path_b))
+    })
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn git_add_worktree(
     repo_root: String,
@@ -234,6 +682,27 @@ pub async fn git_add_worktree(
     })
 }
 
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_validate_add_worktree(
+    repo_root: String,
+    path: String,
+    branch: String,
+    start_point: String,
+) -> Result<(), CommandError> {
+    with_repo_root(repo_root, |root| {
+        let worktree_path = PathBuf::from(path);
+        git::validate_add_worktree(root, &worktree_path, &branch, &start_point)
+    })
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_suggest_worktree_name(
+    repo_root: String,
+    base: String,
+) -> Result<git::WorktreeSuggestionDto, CommandError> {
+    with_repo_root(repo_root, |root| git::suggest_worktree_name(root, &base))
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn git_remove_worktree(
     repo_root: String,
@@ -246,6 +715,41 @@ pub async fn git_remove_worktree(
     })
 }
 
+#[tauri::command]
+pub async fn git_repair_worktrees(cwd: String) -> Result<(), CommandError> {
+    with_cwd(cwd, git::repair_worktrees)
+}
+
+#[tauri::command]
+pub async fn git_worktree_disk_usage(cwd: String) -> Result<u64, CommandError> {
+    with_cwd(cwd, git::worktree_disk_usage)
+}
+
+#[tauri::command]
+pub async fn git_get_sparse_checkout(cwd: String) -> Result<Vec<String>, CommandError> {
+    with_cwd(cwd, git::get_sparse_checkout)
+}
+
+#[tauri::command]
+pub async fn git_set_sparse_checkout(
+    cwd: String,
+    patterns: Vec<String>,
+    cone: bool,
+) -> Result<(), CommandError> {
+    with_cwd(cwd, |path| git::set_sparse_checkout(path, &patterns, cone))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn git_move_worktree(
+    repo_root: String,
+    from: String,
+    to: String,
+) -> Result<(), CommandError> {
+    with_repo_root(repo_root, |root| {
+        git::move_worktree(root, &PathBuf::from(from), &PathBuf::from(to))
+    })
+}
+
 #[tauri::command(rename_all = "camelCase")]
 pub async fn git_stash_save(cwd: String, message: Option<String>, include_untracked: bool) -> Result<(), CommandError> {
     with_cwd(cwd, |path| git::stash_save(path, message, include_untracked))
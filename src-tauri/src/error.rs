@@ -27,6 +27,14 @@ pub mod codes {
     pub const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
     pub const PARSE_ERROR: &str = "PARSE_ERROR";
     pub const VALIDATION_ERROR: &str = "VALIDATION_ERROR";
+    /// Lowercase because it is also the transport-level code for cancelled ws requests.
+    pub const CANCELLED: &str = "cancelled";
+    pub const NOTHING_STAGED: &str = "nothing_staged";
+    pub const NOTHING_TO_COMMIT: &str = "nothing_to_commit";
+    pub const REBASE_CONFLICT: &str = "rebase_conflict";
+    pub const INVALID_BRANCH: &str = "INVALID_BRANCH";
+    pub const PATH_OCCUPIED: &str = "PATH_OCCUPIED";
+    pub const INVALID_START_POINT: &str = "INVALID_START_POINT";
 }
 
 // ============================================================================
@@ -90,6 +98,30 @@ pub enum AppError {
         field_errors: HashMap<String, String>,
     },
 
+    /// Commit without staging, while the index matches HEAD but the working tree has changes
+    #[error("nothing staged")]
+    NothingStaged,
+
+    /// Commit of a tree identical to HEAD's, with a clean working tree
+    #[error("nothing to commit")]
+    NothingToCommit,
+
+    /// Rebase stopped on conflicting changes and is waiting to be continued or aborted
+    #[error("rebase conflict: {0}")]
+    RebaseConflict(String),
+
+    /// Branch name can't be used for a new worktree: malformed, or checked out elsewhere
+    #[error("invalid branch: {0}")]
+    InvalidBranch(String),
+
+    /// Destination for a new worktree already exists or is registered to another worktree
+    #[error("path occupied: {0}")]
+    PathOccupied(String),
+
+    /// Start point for a new branch does not resolve to a commit
+    #[error("invalid start point: {0}")]
+    InvalidStartPoint(String),
+
     /// Generic internal error
     #[error("internal error: {0}")]
     Internal(String),
@@ -112,6 +144,12 @@ impl AppError {
             AppError::NotARepository(_) => codes::NOT_A_REPOSITORY,
             AppError::ParseError { .. } => codes::PARSE_ERROR,
             AppError::ValidationError { .. } => codes::VALIDATION_ERROR,
+            AppError::NothingStaged => codes::NOTHING_STAGED,
+            AppError::NothingToCommit => codes::NOTHING_TO_COMMIT,
+            AppError::RebaseConflict(_) => codes::REBASE_CONFLICT,
+            AppError::InvalidBranch(_) => codes::INVALID_BRANCH,
+            AppError::PathOccupied(_) => codes::PATH_OCCUPIED,
+            AppError::InvalidStartPoint(_) => codes::INVALID_START_POINT,
             AppError::Internal(_) => codes::INTERNAL_ERROR,
             AppError::Context(_) => codes::INTERNAL_ERROR,
         }
@@ -127,9 +165,9 @@ impl AppError {
                 if stderr.contains("permission denied") {
                     "Permission denied. Please check your file permissions.".to_string()
                 } else if stderr.contains("conflict") {
-                    format!("Git conflict: {}", stderr)
+                    format!("Git conflict: {}", stderr_summary(stderr))
                 } else {
-                    stderr.clone()
+                    stderr_summary(stderr).to_string()
                 }
             }
             AppError::Git2(err) => {
@@ -169,6 +207,19 @@ impl AppError {
             AppError::ValidationError { message, .. } => {
                 message.clone()
             }
+            AppError::NothingStaged => {
+                "No changes are staged. Stage the changes you want to commit first.".to_string()
+            }
+            AppError::NothingToCommit => {
+                "Nothing to commit, the working tree is clean.".to_string()
+            }
+            AppError::RebaseConflict(_) => {
+                "Rebase stopped on conflicts. Resolve them, then continue or abort the rebase."
+                    .to_string()
+            }
+            AppError::InvalidBranch(msg)
+            | AppError::PathOccupied(msg)
+            | AppError::InvalidStartPoint(msg) => msg.clone(),
             AppError::Internal(msg) => {
                 format!("An internal error occurred. Please try again. Details: {}", msg)
             }
@@ -178,6 +229,18 @@ impl AppError {
         }
     }
 
+    /// Get the full output behind a one-line `user_message`, when there is more to show.
+    pub fn details(&self) -> Option<String> {
+        match self {
+            AppError::GitFailed { stderr, .. } => {
+                let stderr = stderr.trim();
+                (stderr.lines().count() > 1).then(|| stderr.to_string())
+            }
+            AppError::RebaseConflict(output) => Some(output.trim().to_string()),
+            _ => None,
+        }
+    }
+
     /// Check if this error is retryable (e.g., transient network issues).
     pub fn is_retryable(&self) -> bool {
         match self {
@@ -211,6 +274,17 @@ impl AppError {
     }
 }
 
+/// Picks the line of git's stderr that best explains a failure: the first `fatal:` or
+/// `error:` line, otherwise the first non-empty one.
+fn stderr_summary(stderr: &str) -> &str {
+    let mut lines = stderr.lines().map(str::trim).filter(|line| !line.is_empty());
+    let first = lines.clone().next();
+    lines
+        .find(|line| line.starts_with("fatal:") || line.starts_with("error:"))
+        .or(first)
+        .unwrap_or("Git command failed")
+}
+
 // ============================================================================
 // Conversions from other error types
 // ============================================================================
@@ -381,6 +455,53 @@ mod tests {
         assert!(app_err.user_message().contains("not found"));
     }
 
+    #[test]
+    fn test_git_failed_summary_and_details() {
+        let err = AppError::GitFailed {
+            code: Some(1),
+            stderr: "To origin\n ! [rejected] main -> main (fetch first)\n\
+                     error: failed to push some refs to 'origin'\n"
+                .to_string(),
+        };
+        assert_eq!(err.user_message(), "error: failed to push some refs to 'origin'");
+        let details = err.details().expect("multi-line stderr has details");
+        assert!(details.contains("! [rejected]"));
+        assert!(details.contains('\n'));
+
+        let err = AppError::GitFailed {
+            code: Some(1),
+            stderr: "fatal: bad revision\n".to_string(),
+        };
+        assert_eq!(err.user_message(), "fatal: bad revision");
+        assert!(err.details().is_none());
+    }
+
+    #[test]
+    fn test_commit_error_codes() {
+        assert_eq!(AppError::NothingStaged.code(), codes::NOTHING_STAGED);
+        assert_eq!(AppError::NothingToCommit.code(), codes::NOTHING_TO_COMMIT);
+        assert_ne!(
+            AppError::NothingStaged.user_message(),
+            AppError::NothingToCommit.user_message()
+        );
+    }
+
+    #[test]
+    fn test_rebase_conflict_keeps_git_output() {
+        let err = AppError::RebaseConflict("error: could not apply 1a2b3c4... Change\n".into());
+        assert_eq!(err.code(), codes::REBASE_CONFLICT);
+        assert_eq!(err.details().as_deref(), Some("error: could not apply 1a2b3c4... Change"));
+    }
+
+    #[test]
+    fn test_worktree_validation_codes() {
+        let err = AppError::PathOccupied("../feature already exists".into());
+        assert_eq!(err.code(), codes::PATH_OCCUPIED);
+        assert_eq!(err.user_message(), "../feature already exists");
+        assert_eq!(AppError::InvalidBranch(String::new()).code(), codes::INVALID_BRANCH);
+        assert_eq!(AppError::InvalidStartPoint(String::new()).code(), codes::INVALID_START_POINT);
+    }
+
     #[test]
     fn test_validation_error() {
         let mut field_errors = HashMap::new();
@@ -4,10 +4,121 @@
 #[cfg(test)]
 mod tests {
     use crate::git::{
-        BranchInfoDto, CommitInfoDto, DiffCompareKind, DiffDeltaStatus, DiffFileSummaryDto,
-        DiffMetaDto, DiffRequestDto, DiffRequestOptionsDto, DiffResponseDto, DiffStatDto,
-        FileChangeType, FileStats, FileStatusDto, RemoteInfoDto, RepoInfoDto, RepoStatusDto,
-        StashInfoDto, SubmoduleInfoDto, TagInfoDto, WorktreeInfoDto,
+        BranchInfoDto, CommitInfoDto, CommitLintDto, DiffCompareKind, DiffDeltaStatus,
+        DiffFileSummaryDto, DiffMetaDto, DiffRequestDto, DiffRequestOptionsDto, DiffResponseDto,
+        DiffStatDto, FileChangeType, FileStats, FileStatusDto, GitNetworkOptions, ReflogEntryDto,
+        CommitPageDto, ConflictSide, ConflictVersionsDto, RemoteInfoDto, RepoOperationState,
+        SquashPreviewDto, AmendPreviewDto, TreeEntryDto, WorktreeStatusDto, ScanProgress,
+        GitCredentialRequestDto, MergeDiffMode, RepoStatsDto, RepoOverviewDto, AuthorStatDto,
+        MergePreviewDto, FastForwardMode, ObjectKind, ObjectDto, WorktreeSuggestionDto,
+        RepoInfoDto, RepoStatusDto, StashInfoDto, SubmoduleInfoDto, TagInfoDto, WorktreeInfoDto,
+    };
+    use crate::ws::{
+        AcpAgentConfigParams,
+        AcpConnectionIdParams,
+        AcpExtMethodParams,
+        AcpPermissionOutcomeDto,
+        AcpPermissionReplyParams,
+        AcpSessionCancelParams,
+        AcpSessionLoadParams,
+        AcpSessionNewParams,
+        AcpSessionPromptParams,
+        AcpValidateMcpParams,
+        BroadcastLineParams,
+        ClipboardWriteParams,
+        CreateSessionParams,
+        CreateSessionsParams,
+        CwdParams,
+        EventsLaggedPayload,
+        GitAddRemoteParams,
+        GitAddWorktreeParams,
+        GitAmendPreviewParams,
+        GitApplyPatchParams,
+        GitApplyStashParams,
+        GitArchiveParams,
+        GitAuthorStatsParams,
+        GitBranchSyncStatusParams,
+        GitCanMergeParams,
+        GitCheckoutBranchParams,
+        GitCommitFilesParams,
+        GitCommitParams,
+        GitCommitsInRemoteParams,
+        GitConflictVersionsParams,
+        GitCreateBranchParams,
+        GitCredentialReplyParams,
+        GitDeleteBranchParams,
+        GitDiffParams,
+        GitDiffWorktreesParams,
+        GitDiscardFilesParams,
+        GitDropCommitsParams,
+        GitDropStashParams,
+        GitExpandContextParams,
+        GitFetchParams,
+        GitFormatPatchParams,
+        GitGcParams,
+        GitHasAnyRepoParams,
+        GitLastCommitForPathParams,
+        GitListCommitsParams,
+        GitListCommitsRangeParams,
+        GitListReflogParams,
+        GitListTagsParams,
+        GitListTreeParams,
+        GitLogForPathParams,
+        GitMergeParams,
+        GitMoveWorktreeParams,
+        GitPullParams,
+        GitPushParams,
+        GitReadObjectParams,
+        GitRebaseParams,
+        GitRemoveRemoteParams,
+        GitRemoveWorktreeParams,
+        GitRenameStashParams,
+        GitReorderCommitsParams,
+        GitResetParams,
+        GitResolveConflictParams,
+        GitRestoreToReflogParams,
+        GitRevertParams,
+        GitScanReposParams,
+        GitSetRemoteUrlParams,
+        GitSetSparseCheckoutParams,
+        GitSmartCheckoutParams,
+        GitSquashParams,
+        GitStageAllParams,
+        GitStageFilesParams,
+        GitStashSaveParams,
+        GitSuggestWorktreeNameParams,
+        GitUnpushedCommitsParams,
+        GitUnstageAllParams,
+        GitUnstageFilesParams,
+        GitValidateCommitMessageParams,
+        GroupMemberStatus,
+        GroupStatusParams,
+        LogLinePayload,
+        OpenDialogParams,
+        OpenPathParams,
+        RepoChangedPayload,
+        ResizeSessionParams,
+        RevealPathParams,
+        ScanRootChangedPayload,
+        ServerInfo,
+        SessionGroupStatus,
+        SessionIdParams,
+        SessionSignal,
+        SessionSpec,
+        SetLogLevelParams,
+        SetSessionCwdParams,
+        SignalSessionParams,
+        StreamLogParams,
+        TailLogParams,
+        TransportBatchItem,
+        TransportBatchRequest,
+        TransportBatchResponse,
+        TransportCancel,
+        TransportError,
+        TransportEvent,
+        TransportRequest,
+        TransportResponse,
+        WriteSessionParams,
     };
     use std::fs;
     use std::path::PathBuf;
@@ -62,7 +173,47 @@ mod tests {
         expected_ts.push_str("\n\n");
         expected_ts.push_str(&StashInfoDto::decl().replace("type ", "export type "));
         expected_ts.push_str("\n\n");
+        expected_ts.push_str(&ReflogEntryDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
         expected_ts.push_str(&TagInfoDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&CommitLintDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&SquashPreviewDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&RepoOperationState::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&ConflictVersionsDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&CommitPageDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&RepoStatsDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&MergeDiffMode::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&GitCredentialRequestDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&ScanProgress::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&WorktreeStatusDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&TreeEntryDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&AmendPreviewDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&RepoOverviewDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&AuthorStatDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&MergePreviewDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&FastForwardMode::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&ObjectKind::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&ObjectDto::decl().replace("type ", "export type "));
+        expected_ts.push_str("\n\n");
+        expected_ts.push_str(&WorktreeSuggestionDto::decl().replace("type ", "export type "));
 
         // Read actual types file
         let actual_ts = fs::read_to_string(&git_ts_path)
@@ -84,4 +235,151 @@ mod tests {
             );
         }
     }
+
+    /// Test that verifies the transport TypeScript types are up to date with the ws structs.
+    /// If this test fails, run: cargo run --bin export_types --manifest-path src-tauri/Cargo.toml
+    #[test]
+    fn transport_types_are_synced() {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let types_dir = PathBuf::from(manifest_dir).parent().unwrap().join("src/types");
+        let transport_ts_path = types_dir.join("transport.ts");
+
+        // Generate expected types in memory
+        let mut expected_ts = String::from("// This file was generated by [ts-rs]. Do not edit this file manually.\n");
+        expected_ts.push_str("// To regenerate: cargo run --bin export_types --manifest-path src-tauri/Cargo.toml\n\n");
+        let transport_decls = [
+            TransportRequest::decl(),
+            TransportBatchRequest::decl(),
+            TransportBatchItem::decl(),
+            TransportCancel::decl(),
+            TransportResponse::decl(),
+            TransportBatchResponse::decl(),
+            TransportError::decl(),
+            TransportEvent::decl(),
+            ServerInfo::decl(),
+            EventsLaggedPayload::decl(),
+            RepoChangedPayload::decl(),
+            ScanRootChangedPayload::decl(),
+            SetLogLevelParams::decl(),
+            TailLogParams::decl(),
+            StreamLogParams::decl(),
+            LogLinePayload::decl(),
+            CreateSessionParams::decl(),
+            SessionSpec::decl(),
+            CreateSessionsParams::decl(),
+            GroupStatusParams::decl(),
+            GroupMemberStatus::decl(),
+            SessionGroupStatus::decl(),
+            SessionIdParams::decl(),
+            WriteSessionParams::decl(),
+            SetSessionCwdParams::decl(),
+            SessionSignal::decl(),
+            SignalSessionParams::decl(),
+            ResizeSessionParams::decl(),
+            BroadcastLineParams::decl(),
+            CwdParams::decl(),
+            GitDiffParams::decl(),
+            GitListCommitsParams::decl(),
+            GitListCommitsRangeParams::decl(),
+            GitLastCommitForPathParams::decl(),
+            GitLogForPathParams::decl(),
+            GitUnpushedCommitsParams::decl(),
+            GitScanReposParams::decl(),
+            GitHasAnyRepoParams::decl(),
+            GitAuthorStatsParams::decl(),
+            GitCanMergeParams::decl(),
+            GitListTagsParams::decl(),
+            GitListReflogParams::decl(),
+            GitValidateCommitMessageParams::decl(),
+            GitRestoreToReflogParams::decl(),
+            GitApplyStashParams::decl(),
+            GitRenameStashParams::decl(),
+            GitNetworkOptions::decl(),
+            GitPullParams::decl(),
+            GitPushParams::decl(),
+            GitCommitParams::decl(),
+            GitAmendPreviewParams::decl(),
+            GitStageFilesParams::decl(),
+            GitResetParams::decl(),
+            GitRevertParams::decl(),
+            GitCommitFilesParams::decl(),
+            GitSquashParams::decl(),
+            GitDropCommitsParams::decl(),
+            GitReorderCommitsParams::decl(),
+            GitCommitsInRemoteParams::decl(),
+            GitMergeParams::decl(),
+            GitRebaseParams::decl(),
+            GitCreateBranchParams::decl(),
+            GitCheckoutBranchParams::decl(),
+            GitSmartCheckoutParams::decl(),
+            GitStageAllParams::decl(),
+            GitUnstageAllParams::decl(),
+            GitUnstageFilesParams::decl(),
+            GitDiscardFilesParams::decl(),
+            ConflictSide::decl(),
+            GitResolveConflictParams::decl(),
+            GitConflictVersionsParams::decl(),
+            GitExpandContextParams::decl(),
+            GitListTreeParams::decl(),
+            GitReadObjectParams::decl(),
+            GitBranchSyncStatusParams::decl(),
+            GitArchiveParams::decl(),
+            GitApplyPatchParams::decl(),
+            GitFormatPatchParams::decl(),
+            GitGcParams::decl(),
+            GitSetRemoteUrlParams::decl(),
+            GitAddRemoteParams::decl(),
+            GitRemoveRemoteParams::decl(),
+            GitFetchParams::decl(),
+            GitCredentialReplyParams::decl(),
+            GitDiffWorktreesParams::decl(),
+            GitMoveWorktreeParams::decl(),
+            GitAddWorktreeParams::decl(),
+            GitSuggestWorktreeNameParams::decl(),
+            GitSetSparseCheckoutParams::decl(),
+            GitRemoveWorktreeParams::decl(),
+            GitStashSaveParams::decl(),
+            GitDeleteBranchParams::decl(),
+            GitDropStashParams::decl(),
+            OpenDialogParams::decl(),
+            OpenPathParams::decl(),
+            RevealPathParams::decl(),
+            ClipboardWriteParams::decl(),
+            AcpConnectionIdParams::decl(),
+            AcpExtMethodParams::decl(),
+            AcpValidateMcpParams::decl(),
+            AcpSessionNewParams::decl(),
+            AcpSessionLoadParams::decl(),
+            AcpSessionPromptParams::decl(),
+            AcpSessionCancelParams::decl(),
+            AcpPermissionReplyParams::decl(),
+            AcpPermissionOutcomeDto::decl(),
+            AcpAgentConfigParams::decl(),
+        ];
+        expected_ts.push_str(
+            &transport_decls
+                .map(|decl| decl.replace("type ", "export type "))
+                .join("\n\n"),
+        );
+
+        // Read actual types file
+        let actual_ts = fs::read_to_string(&transport_ts_path)
+            .unwrap_or_else(|_| {
+                panic!(
+                    "TypeScript types file not found at {}. \
+                    Run: cargo run --bin export_types --manifest-path src-tauri/Cargo.toml",
+                    transport_ts_path.display()
+                )
+            });
+
+        // Compare
+        if expected_ts != actual_ts {
+            panic!(
+                "TypeScript types are out of sync with ws transport structs.\n\
+                Run: cargo run --bin export_types --manifest-path src-tauri/Cargo.toml\n\
+                File: {}",
+                transport_ts_path.display()
+            );
+        }
+    }
 }
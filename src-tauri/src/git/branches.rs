@@ -143,9 +143,19 @@ pub fn checkout_local_branch(repo_root: &Path, branch_name: &str) -> Result<(),
     checkout_branch(&repo, &refname)
 }
 
-pub fn smart_checkout_branch(repo_root: &Path, branch_name: &str) -> Result<(), GitError> {
+/// Switches to `branch_name`. With `auto_stash`, local changes are stashed first and
+/// re-applied afterwards; without it, they are carried over like `git switch` does and
+/// the checkout fails if any of them would be overwritten.
+pub fn smart_checkout_branch(
+    repo_root: &Path,
+    branch_name: &str,
+    auto_stash: bool,
+) -> Result<(), GitError> {
     let mut repo = open_repo(repo_root)?;
     let refname = local_branch_refname(branch_name);
+    if !auto_stash {
+        return checkout_branch_keeping_changes(&repo, &refname);
+    }
 
     // 1. Stash changes
     let created_stash = maybe_create_auto_stash(
@@ -177,6 +187,15 @@ pub fn smart_checkout_branch(repo_root: &Path, branch_name: &str) -> Result<(),
     Ok(())
 }
 
+/// Returns `(ahead, behind)` for a local branch against its upstream, using only the
+/// remote-tracking refs already on disk. Branches without an upstream report `(0, 0)`.
+pub fn branch_sync_status(cwd: &Path, branch: &str) -> Result<(i32, i32), GitError> {
+    let repo = open_repo(cwd)?;
+    let branch_ref = repo.find_branch(branch, BranchType::Local)?;
+    let (ahead, behind) = get_branch_ahead_behind(&repo, &branch_ref)?;
+    Ok((ahead as i32, behind as i32))
+}
+
 fn branch_last_commit(branch: &git2::Branch<'_>) -> Result<String, GitError> {
     let commit = branch.get().peel_to_commit()?;
     Ok(commit.summary().unwrap_or_default().to_string())
@@ -230,6 +249,25 @@ pub fn checkout_branch(repo: &Repository, refname: &str) -> Result<(), GitError>
     Ok(())
 }
 
+fn checkout_branch_keeping_changes(repo: &Repository, refname: &str) -> Result<(), GitError> {
+    let target = repo.find_reference(refname)?.peel_to_commit()?;
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.safe();
+    match repo.checkout_tree(target.as_object(), Some(&mut checkout)) {
+        Err(err) if err.code() == ErrorCode::Conflict => {
+            return Err(GitError::GitFailed {
+                code: None,
+                stderr: "your local changes would be overwritten by checkout; \
+                         commit or stash them first"
+                    .to_string(),
+            });
+        }
+        result => result?,
+    }
+    repo.set_head(refname)?;
+    Ok(())
+}
+
 fn is_repo_dirty(repo: &Repository) -> Result<bool, GitError> {
     let mut opts = git2::StatusOptions::new();
     opts.show(git2::StatusShow::IndexAndWorkdir)
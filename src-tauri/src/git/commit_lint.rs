@@ -0,0 +1,96 @@
+use crate::git::error::GitError;
+use crate::git::types::CommitLintDto;
+
+pub const DEFAULT_MAX_SUBJECT_LENGTH: usize = 72;
+
+pub fn validate_commit_message(message: &str) -> Result<CommitLintDto, GitError> {
+    validate_commit_message_with_limit(message, DEFAULT_MAX_SUBJECT_LENGTH)
+}
+
+pub fn validate_commit_message_with_limit(
+    message: &str,
+    max_subject_length: usize,
+) -> Result<CommitLintDto, GitError> {
+    let mut errors = Vec::new();
+    let mut lines = message.lines();
+    let header = lines.next().unwrap_or_default().trim_end();
+
+    if header.trim().is_empty() {
+        errors.push("commit message must not be empty".to_string());
+        return Ok(CommitLintDto {
+            valid: false,
+            errors,
+            parsed_type: None,
+            scope: None,
+        });
+    }
+
+    let (parsed_type, scope, subject) = parse_header(header, &mut errors);
+
+    if let Some(subject) = subject {
+        if subject.trim().is_empty() {
+            errors.push("subject must not be empty".to_string());
+        }
+        let subject_length = subject.chars().count();
+        if subject_length > max_subject_length {
+            errors.push(format!(
+                "subject must be at most {max_subject_length} characters (got {subject_length})"
+            ));
+        }
+    }
+
+    if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+        errors.push("body must be separated from the header by a blank line".to_string());
+    }
+
+    Ok(CommitLintDto {
+        valid: errors.is_empty(),
+        errors,
+        parsed_type,
+        scope,
+    })
+}
+
+/// Parses `type(scope)!: subject`, recording grammar errors as it goes.
+fn parse_header<'a>(
+    header: &'a str,
+    errors: &mut Vec<String>,
+) -> (Option<String>, Option<String>, Option<&'a str>) {
+    let Some((prefix, subject)) = header.split_once(':') else {
+        errors.push("header must match \"type(scope): subject\"".to_string());
+        return (None, None, None);
+    };
+    let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+
+    let (commit_type, scope) = match prefix.split_once('(') {
+        Some((commit_type, rest)) => match rest.strip_suffix(')') {
+            Some(scope) if !scope.trim().is_empty() && !scope.contains(['(', ')']) => {
+                (commit_type, Some(scope.to_string()))
+            }
+            _ => {
+                errors.push("scope must be a non-empty value in parentheses".to_string());
+                (commit_type, None)
+            }
+        },
+        None => (prefix, None),
+    };
+
+    let parsed_type = if commit_type.is_empty() {
+        errors.push("type must not be empty".to_string());
+        None
+    } else if !commit_type
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    {
+        errors.push(format!("type \"{commit_type}\" must be lowercase letters"));
+        None
+    } else {
+        Some(commit_type.to_string())
+    };
+
+    if !subject.starts_with(' ') {
+        errors.push("a single space must follow the colon".to_string());
+    }
+
+    (parsed_type, scope, Some(subject.trim()))
+}
@@ -0,0 +1,199 @@
+use crate::git::error::GitError;
+use crate::git::types::GitCredentialRequestDto;
+use git2::{Cred, CredentialType, RemoteCallbacks};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How long a network operation waits for the client to answer a credential request.
+const CREDENTIAL_PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Private keys tried, in order, when the ssh agent has no usable identity.
+const DEFAULT_SSH_KEYS: &[&str] = &["id_ed25519", "id_rsa"];
+
+/// Username and password (or token) supplied for a remote.
+#[derive(Clone, Debug)]
+pub struct GitCredential {
+    pub username: String,
+    pub password: String,
+}
+
+/// Source of credentials for network operations that git can't authenticate alone.
+pub trait CredentialProvider {
+    /// Returns credentials for `url`, or `None` to give up on authenticating.
+    fn credentials(&self, url: &str, username: Option<&str>) -> Option<GitCredential>;
+
+    /// Called when the remote rejected credentials previously returned for `url`.
+    fn rejected(&self, _url: &str) {}
+}
+
+/// Provider for callers that have no way to ask the user.
+pub struct NoCredentials;
+
+impl CredentialProvider for NoCredentials {
+    fn credentials(&self, _url: &str, _username: Option<&str>) -> Option<GitCredential> {
+        None
+    }
+}
+
+type PendingCredentials = HashMap<String, mpsc::Sender<Option<GitCredential>>>;
+
+/// Hands credential requests from blocking network operations to whichever client answers
+/// first, and routes the reply back to the waiting operation. Answers are cached per host
+/// for the life of the broker so each host is only asked about once per session.
+#[derive(Clone, Default)]
+pub struct CredentialBroker {
+    pending: Arc<Mutex<PendingCredentials>>,
+    cache: Arc<Mutex<HashMap<String, GitCredential>>>,
+}
+
+impl CredentialBroker {
+    /// Returns a provider that announces requests through `emit`.
+    pub fn prompt<F: Fn(GitCredentialRequestDto)>(&self, emit: F) -> CredentialPrompt<'_, F> {
+        CredentialPrompt { broker: self, emit }
+    }
+
+    /// Returns cached credentials for the host of `url`, or announces a credential request
+    /// through `emit` and blocks until a reply arrives or the prompt times out. `None`
+    /// means the client declined or never answered.
+    pub fn request(
+        &self,
+        url: &str,
+        username: Option<&str>,
+        emit: impl Fn(GitCredentialRequestDto),
+    ) -> Option<GitCredential> {
+        let host = credential_host(url);
+        if let Some(credential) = self.cache().get(&host) {
+            return Some(credential.clone());
+        }
+
+        let request_id = Uuid::new_v4().to_string();
+        let (tx, rx) = mpsc::channel();
+        self.pending().insert(request_id.clone(), tx);
+
+        emit(GitCredentialRequestDto {
+            request_id: request_id.clone(),
+            url: url.to_string(),
+            host: host.clone(),
+            username: username.map(str::to_string),
+        });
+        let reply = rx.recv_timeout(CREDENTIAL_PROMPT_TIMEOUT).ok().flatten();
+
+        self.pending().remove(&request_id);
+        if let Some(credential) = &reply {
+            self.cache().insert(host, credential.clone());
+        }
+        reply
+    }
+
+    /// Drops cached credentials for the host of `url` so the next request asks again.
+    pub fn forget(&self, url: &str) {
+        self.cache().remove(&credential_host(url));
+    }
+
+    /// Answers a pending request. Pass `None` to cancel it.
+    pub fn reply(
+        &self,
+        request_id: &str,
+        credential: Option<GitCredential>,
+    ) -> Result<(), GitError> {
+        let sender = self.pending().remove(request_id).ok_or_else(|| GitError::GitFailed {
+            code: None,
+            stderr: format!("credential request {request_id} not found"),
+        })?;
+        let _ = sender.send(credential);
+        Ok(())
+    }
+
+    fn pending(&self) -> MutexGuard<'_, PendingCredentials> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn cache(&self) -> MutexGuard<'_, HashMap<String, GitCredential>> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// [`CredentialProvider`] backed by a [`CredentialBroker`] and an event emitter.
+pub struct CredentialPrompt<'a, F> {
+    broker: &'a CredentialBroker,
+    emit: F,
+}
+
+impl<F: Fn(GitCredentialRequestDto)> CredentialProvider for CredentialPrompt<'_, F> {
+    fn credentials(&self, url: &str, username: Option<&str>) -> Option<GitCredential> {
+        self.broker.request(url, username, &self.emit)
+    }
+
+    fn rejected(&self, url: &str) {
+        self.broker.forget(url);
+    }
+}
+
+/// Extracts the `host[:port]` that credentials are cached under from an https or
+/// scp-style remote URL.
+pub fn credential_host(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?']).next().unwrap_or(rest);
+    let authority = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    if url.contains("://") {
+        authority.to_string()
+    } else {
+        // scp-style `user@host:path`
+        authority.split(':').next().unwrap_or(authority).to_string()
+    }
+}
+
+/// Builds git2 callbacks that authenticate the way the git CLI would: the ssh agent
+/// first, then the default key files in `~/.ssh`, then `provider` for a username and
+/// password. Each source is tried once so a rejected credential can't loop forever.
+pub fn remote_callbacks(provider: &dyn CredentialProvider) -> RemoteCallbacks<'_> {
+    let mut tried_agent = false;
+    let mut key_paths = default_ssh_key_paths().into_iter();
+    let mut prompted = false;
+
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username_from_url, allowed| {
+        let username = username_from_url.unwrap_or("git");
+
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username);
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            if !tried_agent {
+                tried_agent = true;
+                return Cred::ssh_key_from_agent(username);
+            }
+            if let Some(key_path) = key_paths.next() {
+                return Cred::ssh_key(username, None, &key_path, None);
+            }
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && !prompted {
+            prompted = true;
+            if let Some(credential) = provider.credentials(url, username_from_url) {
+                return Cred::userpass_plaintext(&credential.username, &credential.password);
+            }
+            return Err(git2::Error::from_str("authentication cancelled"));
+        }
+        if allowed.contains(CredentialType::DEFAULT) {
+            return Cred::default();
+        }
+        Err(git2::Error::from_str(&format!("no credentials available for {url}")))
+    });
+    callbacks
+}
+
+fn default_ssh_key_paths() -> Vec<PathBuf> {
+    let Some(home) = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) else {
+        return Vec::new();
+    };
+    let ssh_dir = PathBuf::from(home).join(".ssh");
+    DEFAULT_SSH_KEYS
+        .iter()
+        .map(|name| ssh_dir.join(name))
+        .filter(|path| path.is_file())
+        .collect()
+}
@@ -1,12 +1,25 @@
 use crate::git::error::GitError;
+use crate::git::scanner::canonicalize_path;
 use crate::git::status::open_repo;
 use crate::git::types::{
-    DiffCompareKind, DiffDeltaStatus, DiffFileSummaryDto, DiffMetaDto, DiffRequestDto,
-    DiffRequestOptionsDto, DiffResponseDto,
+    ConflictVersionsDto, DiffCompareKind, DiffDeltaStatus, DiffFileSummaryDto, DiffMetaDto,
+    DiffRequestDto, DiffRequestOptionsDto, DiffResponseDto, MergeDiffMode,
+};
+use git2::{
+    Delta, Diff, DiffDelta, DiffFile, DiffFindOptions, DiffFormat, DiffOptions, ErrorCode, Object,
+    Patch, Repository, Tree,
 };
-use git2::{Delta, Diff, DiffFindOptions, DiffFormat, DiffOptions, ErrorCode};
 use sha2::{Digest, Sha256};
-use std::path::Path;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Default cap on generated diff text, so an accidental diff of a huge generated file
+/// can't exhaust memory.
+const DEFAULT_MAX_DIFF_BYTES: usize = 16 * 1024 * 1024;
+
+/// Similarity percentage `git diff -M` uses for rename detection when none is given.
+const DEFAULT_RENAME_THRESHOLD: u16 = 50;
 
 pub fn get_unified_diff(req: DiffRequestDto) -> Result<DiffResponseDto, GitError> {
     let repo = open_repo(Path::new(&req.repo_path))?;
@@ -14,6 +27,35 @@ pub fn get_unified_diff(req: DiffRequestDto) -> Result<DiffResponseDto, GitError
     let (mut opts, context_lines, _include_untracked) =
         build_diff_options(&paths, req.options.as_ref());
     let compare_kind = req.compare_kind.clone();
+    let merge_diff_mode = req.options.as_ref().and_then(|opts| opts.merge_diff_mode);
+    let max_output_bytes = req
+        .options
+        .as_ref()
+        .and_then(|opts| opts.max_output_bytes)
+        .unwrap_or(DEFAULT_MAX_DIFF_BYTES);
+    let use_merge_base = req.left.is_some()
+        && req
+            .options
+            .as_ref()
+            .and_then(|opts| opts.use_merge_base)
+            .unwrap_or(false);
+    let stat_only = req
+        .options
+        .as_ref()
+        .and_then(|opts| opts.stat_only)
+        .unwrap_or(false);
+    let mut left = req.left.clone();
+
+    if compare_kind == DiffCompareKind::RefRef
+        && merge_diff_mode == Some(MergeDiffMode::Combined)
+        && !use_merge_base
+    {
+        if let Some(response) =
+            combined_merge_diff(&repo, &req, &paths, context_lines, max_output_bytes)?
+        {
+            return Ok(response);
+        }
+    }
 
     let diff = match compare_kind {
         DiffCompareKind::WorktreeHead => {
@@ -26,17 +68,21 @@ pub fn get_unified_diff(req: DiffRequestDto) -> Result<DiffResponseDto, GitError
             repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts))?
         }
         DiffCompareKind::RefRef => {
-            let left = req
-                .left
-                .as_deref()
-                .ok_or_else(|| GitError::Git2(git2::Error::from_str("missing left ref")))?;
             let right = req
                 .right
                 .as_deref()
                 .ok_or_else(|| GitError::Git2(git2::Error::from_str("missing right ref")))?;
-            let left_tree = repo.revparse_single(left)?.peel_to_tree()?;
-            let right_tree = repo.revparse_single(right)?.peel_to_tree()?;
-            repo.diff_tree_to_tree(Some(&left_tree), Some(&right_tree), Some(&mut opts))?
+            let right_object = repo.revparse_single(right)?;
+            let right_tree = right_object.peel_to_tree()?;
+            let left_tree = match req.left.as_deref() {
+                Some(left_ref) if use_merge_base => {
+                    let base = merge_base_commit(&repo, left_ref, &right_object)?;
+                    left = Some(base.id().to_string());
+                    Some(base.tree()?)
+                }
+                _ => ref_ref_left_tree(&repo, req.left.as_deref(), &right_object, merge_diff_mode)?,
+            };
+            repo.diff_tree_to_tree(left_tree.as_ref(), Some(&right_tree), Some(&mut opts))?
         }
         DiffCompareKind::IndexHead => {
             let head_tree = match repo.head() {
@@ -50,12 +96,35 @@ pub fn get_unified_diff(req: DiffRequestDto) -> Result<DiffResponseDto, GitError
     };
 
     let mut diff = diff;
-    let mut find_opts = DiffFindOptions::new();
+    let (mut find_opts, rename_threshold) = build_find_options(req.options.as_ref());
     diff.find_similar(Some(&mut find_opts))?;
 
-    let diff_text = diff_to_unified_string(&diff)?;
-    let diff_hash = hash_bytes(diff_text.as_bytes());
-    let file_summaries = diff_file_summaries(&diff)?;
+    let mut file_summaries = diff_file_summaries(&diff)?;
+    mark_eol_only(&repo, &diff, &mut file_summaries)?;
+    let ignore_eol = req.options.as_ref().and_then(|opts| opts.ignore_eol);
+    let (diff_text, truncated, diff_hash) = if stat_only {
+        count_lines(&diff, &mut file_summaries)?;
+        // Hash the `git diff --numstat` form so callers can still tell when counts change
+        let count = |n: Option<u32>| n.map_or_else(|| "-".to_string(), |n| n.to_string());
+        let numstat: String = file_summaries
+            .iter()
+            .map(|summary| {
+                let (added, deleted) = (count(summary.insertions), count(summary.deletions));
+                format!("{added}\t{deleted}\t{}\n", summary.path)
+            })
+            .collect();
+        (String::new(), false, hash_bytes(numstat.as_bytes()))
+    } else {
+        let ignore_eol = ignore_eol.unwrap_or(false);
+        let skip_paths: Vec<&str> = file_summaries
+            .iter()
+            .filter(|summary| ignore_eol && summary.eol_only)
+            .map(|summary| summary.path.as_str())
+            .collect();
+        let (diff_text, truncated) = diff_to_unified_string(&diff, max_output_bytes, &skip_paths)?;
+        let diff_hash = hash_bytes(diff_text.as_bytes());
+        (diff_text, truncated, diff_hash)
+    };
     let conflicted_paths = match compare_kind {
         DiffCompareKind::WorktreeHead | DiffCompareKind::IndexHead => {
             index_conflicted_paths(&repo)?
@@ -68,16 +137,280 @@ pub fn get_unified_diff(req: DiffRequestDto) -> Result<DiffResponseDto, GitError
         diff_hash,
         meta: DiffMetaDto {
             compare_kind,
-            left: req.left,
+            left,
             right: req.right,
             paths,
             context_lines,
             file_summaries,
             conflicted_paths,
+            truncated,
+            rename_threshold,
+        },
+    })
+}
+
+/// Picks the left side of a `RefRef` diff. A merge commit with an explicit merge mode is
+/// always compared against its first parent. Otherwise `left` wins, falling back to the
+/// right commit's first parent, or the empty tree for a root commit.
+fn ref_ref_left_tree<'repo>(
+    repo: &'repo git2::Repository,
+    left: Option<&str>,
+    right: &Object<'repo>,
+    merge_diff_mode: Option<MergeDiffMode>,
+) -> Result<Option<Tree<'repo>>, GitError> {
+    let right_commit = right.peel_to_commit().ok();
+    let is_merge = right_commit
+        .as_ref()
+        .is_some_and(|commit| commit.parent_count() > 1);
+
+    if let Some(left) = left {
+        if !(is_merge && merge_diff_mode.is_some()) {
+            return Ok(Some(repo.revparse_single(left)?.peel_to_tree()?));
+        }
+    }
+    match right_commit {
+        Some(commit) if commit.parent_count() > 0 => Ok(Some(commit.parent(0)?.tree()?)),
+        Some(_) => Ok(None),
+        None => Err(GitError::Git2(git2::Error::from_str("missing left ref"))),
+    }
+}
+
+/// Finds the commit where `left` and `right` diverged, for three-dot diffs.
+fn merge_base_commit<'repo>(
+    repo: &'repo git2::Repository,
+    left: &str,
+    right: &Object<'repo>,
+) -> Result<git2::Commit<'repo>, GitError> {
+    let left_id = repo.revparse_single(left)?.peel_to_commit()?.id();
+    let right_id = right.peel_to_commit()?.id();
+    let base = match repo.merge_base(left_id, right_id) {
+        Ok(base) => base,
+        Err(err) if err.code() == ErrorCode::NotFound => {
+            return Err(GitError::GitFailed {
+                code: None,
+                stderr: format!("{left} and {} have no common ancestor", right.id()),
+            });
+        }
+        Err(err) => return Err(GitError::Git2(err)),
+    };
+    Ok(repo.find_commit(base)?)
+}
+
+/// Builds the `git diff -c` view of a merge commit. libgit2 has no combined diff support,
+/// so this shells out to `git diff-tree`. Returns `None` when `right` is not a merge.
+fn combined_merge_diff(
+    repo: &git2::Repository,
+    req: &DiffRequestDto,
+    paths: &[String],
+    context_lines: u32,
+    max_output_bytes: usize,
+) -> Result<Option<DiffResponseDto>, GitError> {
+    let Some(right) = req.right.as_deref() else {
+        return Ok(None);
+    };
+    let Ok(commit) = repo.revparse_single(right)?.peel_to_commit() else {
+        return Ok(None);
+    };
+    if commit.parent_count() < 2 {
+        return Ok(None);
+    }
+
+    let options = req.options.as_ref();
+    let (_find_opts, rename_threshold) = build_find_options(options);
+    let mut cmd = Command::new("git");
+    cmd.current_dir(repo.workdir().unwrap_or_else(|| repo.path()))
+        .args(["diff-tree", "-p", "-c", "--no-commit-id"])
+        .arg(format!("-U{context_lines}"))
+        .arg(format!("-M{rename_threshold}%"));
+    if options.and_then(|opts| opts.find_copies).unwrap_or(false) {
+        cmd.arg(format!("-C{rename_threshold}%"));
+    }
+    cmd.arg(commit.id().to_string());
+    if !paths.is_empty() {
+        cmd.arg("--").args(paths);
+    }
+    let (mut stdout, truncated) = read_capped_output(&mut cmd, max_output_bytes)?;
+    let cut = if truncated {
+        stdout[..max_output_bytes]
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |idx| idx + 1)
+    } else {
+        stdout.len()
+    };
+    stdout.truncate(cut);
+    let diff_text = String::from_utf8(stdout)?;
+    let diff_hash = hash_bytes(diff_text.as_bytes());
+    let file_summaries = combined_file_summaries(&diff_text);
+
+    Ok(Some(DiffResponseDto {
+        diff_text,
+        diff_hash,
+        meta: DiffMetaDto {
+            compare_kind: DiffCompareKind::RefRef,
+            left: req.left.clone(),
+            right: req.right.clone(),
+            paths: paths.to_vec(),
+            context_lines,
+            file_summaries,
+            conflicted_paths: Vec::new(),
+            truncated,
+            rename_threshold,
+        },
+    }))
+}
+
+fn combined_file_summaries(diff_text: &str) -> Vec<DiffFileSummaryDto> {
+    let mut summaries: Vec<DiffFileSummaryDto> = Vec::new();
+    for line in diff_text.lines() {
+        if let Some(path) = line.strip_prefix("diff --combined ") {
+            summaries.push(DiffFileSummaryDto {
+                path: path.to_string(),
+                status: DiffDeltaStatus::Modified,
+                is_binary: false,
+                eol_only: false,
+                insertions: None,
+                deletions: None,
+            });
+        } else if let Some(summary) = summaries.last_mut() {
+            if line.starts_with("new file mode") {
+                summary.status = DiffDeltaStatus::Added;
+            } else if line.starts_with("deleted file mode") {
+                summary.status = DiffDeltaStatus::Deleted;
+            } else if line.starts_with("Binary files") {
+                summary.is_binary = true;
+            }
+        }
+    }
+    summaries
+}
+
+pub fn stash_diff(cwd: &Path, index: i32) -> Result<DiffResponseDto, GitError> {
+    if index < 0 {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: "stash index must be >= 0".to_string(),
+        });
+    }
+    let mut repo = open_repo(cwd)?;
+    let mut stash_oid = None;
+    repo.stash_foreach(|stash_index, _message, oid| {
+        if stash_index == index as usize {
+            stash_oid = Some(*oid);
+            false
+        } else {
+            true
+        }
+    })?;
+    let stash_oid = stash_oid.ok_or_else(|| GitError::GitFailed {
+        code: None,
+        stderr: format!("stash@{{{index}}} does not exist"),
+    })?;
+
+    let stash_commit = repo.find_commit(stash_oid)?;
+    let base_commit = stash_commit.parent(0)?;
+    let stash_tree = stash_commit.tree()?;
+    let base_tree = base_commit.tree()?;
+
+    let (mut opts, context_lines, _include_untracked) = build_diff_options(&[], None);
+    let mut diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&stash_tree), Some(&mut opts))?;
+
+    // Stashes saved with INCLUDE_UNTRACKED keep untracked files in a third parent
+    if stash_commit.parent_count() > 2 {
+        let untracked_tree = stash_commit.parent(2)?.tree()?;
+        let untracked_diff =
+            repo.diff_tree_to_tree(None, Some(&untracked_tree), Some(&mut opts))?;
+        diff.merge(&untracked_diff)?;
+    }
+
+    let (mut find_opts, rename_threshold) = build_find_options(None);
+    diff.find_similar(Some(&mut find_opts))?;
+
+    let (diff_text, truncated) = diff_to_unified_string(&diff, DEFAULT_MAX_DIFF_BYTES, &[])?;
+    let diff_hash = hash_bytes(diff_text.as_bytes());
+    let file_summaries = diff_file_summaries(&diff)?;
+
+    Ok(DiffResponseDto {
+        diff_text,
+        diff_hash,
+        meta: DiffMetaDto {
+            compare_kind: DiffCompareKind::RefRef,
+            left: Some(base_commit.id().to_string()),
+            right: Some(stash_oid.to_string()),
+            paths: Vec::new(),
+            context_lines,
+            file_summaries,
+            conflicted_paths: Vec::new(),
+            truncated,
+            rename_threshold,
         },
     })
 }
 
+/// Diffs the working state of two worktrees of the repository at `repo_root`.
+///
+/// Each side is the tree of that worktree's index, so unstaged edits and untracked files
+/// are not compared; a worktree with unresolved conflicts contributes its `HEAD` instead.
+/// `meta.left` and `meta.right` are the compared tree ids, which a `RefRef` request
+/// accepts to reproduce the same diff.
+pub fn diff_worktrees(
+    repo_root: &Path,
+    path_a: &Path,
+    path_b: &Path,
+) -> Result<DiffResponseDto, GitError> {
+    let repo = open_repo(repo_root)?;
+    let left_tree = worktree_state_tree(&repo, path_a)?;
+    let right_tree = worktree_state_tree(&repo, path_b)?;
+
+    let (mut opts, context_lines, _include_untracked) = build_diff_options(&[], None);
+    let mut diff = repo.diff_tree_to_tree(Some(&left_tree), Some(&right_tree), Some(&mut opts))?;
+    let (mut find_opts, rename_threshold) = build_find_options(None);
+    diff.find_similar(Some(&mut find_opts))?;
+
+    let (diff_text, truncated) = diff_to_unified_string(&diff, DEFAULT_MAX_DIFF_BYTES, &[])?;
+    let diff_hash = hash_bytes(diff_text.as_bytes());
+    let file_summaries = diff_file_summaries(&diff)?;
+
+    Ok(DiffResponseDto {
+        diff_text,
+        diff_hash,
+        meta: DiffMetaDto {
+            compare_kind: DiffCompareKind::RefRef,
+            left: Some(left_tree.id().to_string()),
+            right: Some(right_tree.id().to_string()),
+            paths: Vec::new(),
+            context_lines,
+            file_summaries,
+            conflicted_paths: Vec::new(),
+            truncated,
+            rename_threshold,
+        },
+    })
+}
+
+/// Writes the index of the worktree at `path` as a tree and looks it up in `repo`, which
+/// must share its object database.
+fn worktree_state_tree<'repo>(
+    repo: &'repo Repository,
+    path: &Path,
+) -> Result<Tree<'repo>, GitError> {
+    let worktree = open_repo(path)?;
+    if canonicalize_path(worktree.commondir()) != canonicalize_path(repo.commondir()) {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: format!("{} is not a worktree of this repository", path.display()),
+        });
+    }
+
+    let mut index = worktree.index()?;
+    let tree_id = if index.has_conflicts() {
+        worktree.head()?.peel_to_tree()?.id()
+    } else {
+        index.write_tree()?
+    };
+    Ok(repo.find_tree(tree_id)?)
+}
+
 fn build_diff_options(
     paths: &[String],
     options: Option<&DiffRequestOptionsDto>,
@@ -98,33 +431,194 @@ fn build_diff_options(
     (opts, context_lines, include_untracked)
 }
 
-fn diff_to_unified_string(diff: &Diff<'_>) -> Result<String, GitError> {
+/// Rename and copy detection for `find_similar`, along with the rename threshold in effect.
+fn build_find_options(options: Option<&DiffRequestOptionsDto>) -> (DiffFindOptions, u16) {
+    let rename_threshold = options
+        .and_then(|opts| opts.rename_threshold)
+        .unwrap_or(DEFAULT_RENAME_THRESHOLD)
+        .min(100);
+
+    let mut find_opts = DiffFindOptions::new();
+    find_opts.rename_threshold(rename_threshold);
+    if options.and_then(|opts| opts.find_copies).unwrap_or(false) {
+        find_opts.renames(true).copies(true).copy_threshold(rename_threshold);
+    }
+    (find_opts, rename_threshold)
+}
+
+/// Renders `diff` as patch text, stopping at the last whole line that fits in
+/// `max_bytes` and leaving out the files in `skip_paths`. The flag reports whether output
+/// was cut short.
+fn diff_to_unified_string(
+    diff: &Diff<'_>,
+    max_bytes: usize,
+    skip_paths: &[&str],
+) -> Result<(String, bool), GitError> {
     let mut buf = Vec::new();
-    diff.print(DiffFormat::Patch, |_delta, _hunk, line| {
-        buf.extend_from_slice(line.content());
+    let mut truncated = false;
+    let result = diff.print(DiffFormat::Patch, |delta, _hunk, line| {
+        if !skip_paths.is_empty()
+            && delta_path(&delta).is_some_and(|path| skip_paths.contains(&path.as_str()))
+        {
+            return true;
+        }
+        let content = line.content();
+        if buf.len() + content.len() > max_bytes {
+            truncated = true;
+            return false;
+        }
+        buf.extend_from_slice(content);
         true
-    })?;
-    Ok(String::from_utf8(buf)?)
+    });
+    match result {
+        Ok(()) => {}
+        // Returning false from the callback aborts the print with a user error.
+        Err(err) if truncated && err.code() == ErrorCode::User => {}
+        Err(err) => return Err(GitError::Git2(err)),
+    }
+    Ok((String::from_utf8(buf)?, truncated))
 }
 
-fn diff_file_summaries(diff: &Diff<'_>) -> Result<Vec<DiffFileSummaryDto>, GitError> {
+pub(crate) fn diff_file_summaries(diff: &Diff<'_>) -> Result<Vec<DiffFileSummaryDto>, GitError> {
     let mut summaries = Vec::new();
     for delta in diff.deltas() {
-        let path = delta
-            .new_file()
-            .path()
-            .or_else(|| delta.old_file().path())
-            .map(|p| p.to_string_lossy().to_string());
-        let Some(path) = path else { continue };
+        let Some(path) = delta_path(&delta) else { continue };
+        let is_binary = delta.new_file().is_binary() || delta.old_file().is_binary();
         summaries.push(DiffFileSummaryDto {
             path,
             status: map_delta_status(delta.status()),
-            is_binary: delta.new_file().is_binary() || delta.old_file().is_binary(),
+            is_binary,
+            eol_only: false,
+            insertions: None,
+            deletions: None,
         });
     }
     Ok(summaries)
 }
 
+/// Sets `eol_only` on the summaries from `diff_file_summaries` of the same diff. Contents are
+/// only read for modified files whose size change could be explained by line endings alone.
+fn mark_eol_only(
+    repo: &Repository,
+    diff: &Diff<'_>,
+    summaries: &mut [DiffFileSummaryDto],
+) -> Result<(), GitError> {
+    let deltas = diff.deltas().filter(|delta| delta_path(delta).is_some());
+    for (delta, summary) in deltas.zip(summaries.iter_mut()) {
+        summary.eol_only = !summary.is_binary && is_eol_only_change(repo, &delta)?;
+    }
+    Ok(())
+}
+
+/// Fills in `insertions`/`deletions` for each summary from `diff_file_summaries` of the
+/// same diff. Binary files keep `None`; loading the patch is also what finally tells
+/// whether a file is binary, so `is_binary` is corrected along the way.
+fn count_lines(diff: &Diff<'_>, summaries: &mut [DiffFileSummaryDto]) -> Result<(), GitError> {
+    let deltas = diff
+        .deltas()
+        .enumerate()
+        .filter(|(_, delta)| delta_path(delta).is_some());
+    for ((idx, _), summary) in deltas.zip(summaries.iter_mut()) {
+        if let Some(patch) = Patch::from_diff(diff, idx)? {
+            let delta = patch.delta();
+            if delta.new_file().is_binary() || delta.old_file().is_binary() {
+                summary.is_binary = true;
+                continue;
+            }
+            let (_, insertions, deletions) = patch.line_stats()?;
+            summary.insertions = Some(insertions as u32);
+            summary.deletions = Some(deletions as u32);
+        }
+    }
+    Ok(())
+}
+
+fn delta_path(delta: &DiffDelta<'_>) -> Option<String> {
+    delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+/// Whether both sides of a modification hold the same text once CRLF is read as LF.
+///
+/// Sizes are checked first: an EOL-only change always changes the size, by at most the
+/// number of CRs in the larger side, so most edits are ruled out without reading content.
+fn is_eol_only_change(repo: &Repository, delta: &DiffDelta<'_>) -> Result<bool, GitError> {
+    if !matches!(delta.status(), Delta::Modified | Delta::Renamed | Delta::Copied) {
+        return Ok(false);
+    }
+    let (Some(old_size), Some(new_size)) = (
+        diff_file_size(repo, &delta.old_file())?,
+        diff_file_size(repo, &delta.new_file())?,
+    ) else {
+        return Ok(false);
+    };
+    if old_size == new_size {
+        return Ok(false);
+    }
+    let (Some(old), Some(new)) = (
+        diff_file_content(repo, &delta.old_file())?,
+        diff_file_content(repo, &delta.new_file())?,
+    ) else {
+        return Ok(false);
+    };
+    let larger = if old.len() > new.len() { &old } else { &new };
+    let crs = larger.iter().filter(|&&byte| byte == b'\r').count();
+    if crs < old.len().abs_diff(new.len()) {
+        return Ok(false);
+    }
+    Ok(normalize_eol(&old) == normalize_eol(&new))
+}
+
+/// Size of one side of a delta, from the object header or the working tree file's metadata.
+fn diff_file_size(repo: &Repository, file: &DiffFile<'_>) -> Result<Option<u64>, GitError> {
+    if !file.id().is_zero() {
+        if let Ok((size, _)) = repo.odb().and_then(|odb| odb.read_header(file.id())) {
+            return Ok(Some(size as u64));
+        }
+    }
+    match (repo.workdir(), file.path()) {
+        (Some(workdir), Some(path)) => match std::fs::metadata(workdir.join(path)) {
+            Ok(metadata) => Ok(Some(metadata.len())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(GitError::Io(err)),
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Reads one side of a delta from the object database, or from the working tree when the
+/// blob was never written (unstaged edits).
+fn diff_file_content(repo: &Repository, file: &DiffFile<'_>) -> Result<Option<Vec<u8>>, GitError> {
+    if !file.id().is_zero() {
+        if let Ok(blob) = repo.find_blob(file.id()) {
+            return Ok(Some(blob.content().to_vec()));
+        }
+    }
+    match (repo.workdir(), file.path()) {
+        (Some(workdir), Some(path)) => match std::fs::read(workdir.join(path)) {
+            Ok(content) => Ok(Some(content)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(GitError::Io(err)),
+        },
+        _ => Ok(None),
+    }
+}
+
+fn normalize_eol(content: &[u8]) -> Vec<u8> {
+    let mut normalized = Vec::with_capacity(content.len());
+    let mut bytes = content.iter().peekable();
+    while let Some(&byte) = bytes.next() {
+        if byte == b'\r' && bytes.peek() == Some(&&b'\n') {
+            continue;
+        }
+        normalized.push(byte);
+    }
+    normalized
+}
+
 fn map_delta_status(status: Delta) -> DiffDeltaStatus {
     match status {
         Delta::Unmodified => DiffDeltaStatus::Unmodified,
@@ -141,15 +635,207 @@ fn map_delta_status(status: Delta) -> DiffDeltaStatus {
     }
 }
 
-fn index_conflicted_paths(repo: &git2::Repository) -> Result<Vec<String>, GitError> {
+/// Lists the files a commit changed relative to its first parent, without building diff text.
+pub fn commit_files(cwd: &Path, commit: &str) -> Result<Vec<DiffFileSummaryDto>, GitError> {
+    let repo = open_repo(cwd)?;
+    let commit = repo.revparse_single(commit)?.peel_to_commit()?;
+    let parent_tree = if commit.parent_count() > 0 {
+        Some(commit.parent(0)?.tree()?)
+    } else {
+        None
+    };
+    let tree = commit.tree()?;
+
+    let (mut opts, _context_lines, _include_untracked) = build_diff_options(&[], None);
+    let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut opts))?;
+    let (mut find_opts, _rename_threshold) = build_find_options(None);
+    diff.find_similar(Some(&mut find_opts))?;
+    diff_file_summaries(&diff)
+}
+
+/// Returns `count` lines of `path` as of `rev`, starting at the 1-based `start_line`, so a
+/// diff view can reveal context around a hunk without recomputing the diff. An empty `rev`
+/// reads the working tree copy. Fewer lines come back when the file ends first.
+pub fn expand_context(
+    cwd: &Path,
+    path: &str,
+    rev: &str,
+    start_line: usize,
+    count: usize,
+) -> Result<Vec<String>, GitError> {
+    if start_line == 0 {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: "start_line is 1-based".to_string(),
+        });
+    }
+    let repo = open_repo(cwd)?;
+    let content = if rev.is_empty() {
+        let workdir = repo.workdir().ok_or_else(|| GitError::GitFailed {
+            code: None,
+            stderr: "repository has no working tree".to_string(),
+        })?;
+        let full_path = workdir_file(workdir, path)?;
+        std::fs::read(full_path).map_err(|err| GitError::GitFailed {
+            code: None,
+            stderr: format!("failed to read {path}: {err}"),
+        })?
+    } else {
+        let blob = repo.revparse_single(&format!("{rev}:{path}"))?.peel_to_blob()?;
+        blob.content().to_vec()
+    };
+    if content.contains(&0) {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: format!("{path} is binary"),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&content)
+        .lines()
+        .skip(start_line - 1)
+        .take(count)
+        .map(str::to_string)
+        .collect())
+}
+
+/// Runs `cmd` and reads at most `max_bytes + 1` bytes of its stdout, killing the process once
+/// the cap is passed so huge outputs are never buffered. Returns the bytes read and whether
+/// the output went over the cap.
+fn read_capped_output(cmd: &mut Command, max_bytes: usize) -> Result<(Vec<u8>, bool), GitError> {
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(GitError::Io)?;
+    let mut stderr_pipe = child.stderr.take();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut stderr = Vec::new();
+        if let Some(pipe) = stderr_pipe.as_mut() {
+            let _ = pipe.read_to_end(&mut stderr);
+        }
+        stderr
+    });
+
+    let mut stdout = Vec::new();
+    if let Some(pipe) = child.stdout.take() {
+        pipe.take(max_bytes as u64 + 1)
+            .read_to_end(&mut stdout)
+            .map_err(GitError::Io)?;
+    }
+    let truncated = stdout.len() > max_bytes;
+    if truncated {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Ok((stdout, true));
+    }
+
+    let status = child.wait().map_err(GitError::Io)?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+    if !status.success() {
+        return Err(GitError::GitFailed {
+            code: status.code(),
+            stderr: String::from_utf8_lossy(&stderr).to_string(),
+        });
+    }
+    Ok((stdout, false))
+}
+
+/// Resolves a repo-relative `path` inside `workdir`, refusing absolute paths, `..` components
+/// and symlinks that lead outside the working tree.
+fn workdir_file(workdir: &Path, path: &str) -> Result<PathBuf, GitError> {
+    let outside = || GitError::GitFailed {
+        code: None,
+        stderr: format!("{path} is outside the working tree"),
+    };
+    let relative = Path::new(path);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(outside());
+    }
+    let read_err = |err: std::io::Error| GitError::GitFailed {
+        code: None,
+        stderr: format!("failed to read {path}: {err}"),
+    };
+    let root = workdir.canonicalize().map_err(read_err)?;
+    let full_path = workdir.join(relative).canonicalize().map_err(read_err)?;
+    if !full_path.starts_with(&root) {
+        return Err(outside());
+    }
+    Ok(full_path)
+}
+
+/// Returns the base/ours/theirs content of a conflicted path, read from the index stages.
+///
+/// Stages that are missing (the file was added or deleted on one side) or binary come back as
+/// `None`; `is_binary` tells the two apart.
+pub fn conflict_versions(cwd: &Path, path: &str) -> Result<ConflictVersionsDto, GitError> {
+    let repo = open_repo(cwd)?;
     let index = repo.index()?;
-    let mut paths = std::collections::HashSet::new();
+    let (_, conflict) = index_conflicts(&index)?
+        .into_iter()
+        .find(|(conflict_path, _)| conflict_path == path)
+        .ok_or_else(|| GitError::GitFailed {
+            code: None,
+            stderr: format!("{path} is not conflicted"),
+        })?;
+
+    let mut is_binary = false;
+    let mut read_stage = |entry: Option<git2::IndexEntry>| -> Result<Option<String>, GitError> {
+        let Some(entry) = entry else {
+            return Ok(None);
+        };
+        let blob = repo.find_blob(entry.id)?;
+        match std::str::from_utf8(blob.content()) {
+            Ok(text) if !blob.is_binary() => Ok(Some(text.to_string())),
+            _ => {
+                is_binary = true;
+                Ok(None)
+            }
+        }
+    };
+    let base = read_stage(conflict.ancestor)?;
+    let ours = read_stage(conflict.our)?;
+    let theirs = read_stage(conflict.their)?;
+
+    Ok(ConflictVersionsDto {
+        path: path.to_string(),
+        base,
+        ours,
+        theirs,
+        is_binary,
+    })
+}
+
+fn index_conflicted_paths(repo: &git2::Repository) -> Result<Vec<String>, GitError> {
+    conflicted_paths(&repo.index()?)
+}
+
+/// Sorted, de-duplicated paths with conflict entries in `index`.
+pub(crate) fn conflicted_paths(index: &git2::Index) -> Result<Vec<String>, GitError> {
+    let paths: std::collections::HashSet<String> = index_conflicts(index)?
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+
+    let mut sorted: Vec<String> = paths.into_iter().collect();
+    sorted.sort();
+    Ok(sorted)
+}
+
+/// Lists the index's conflict entries together with the path they belong to.
+pub(crate) fn index_conflicts(
+    index: &git2::Index,
+) -> Result<Vec<(String, git2::IndexConflict)>, GitError> {
     let conflicts = match index.conflicts() {
         Ok(conflicts) => conflicts,
         Err(err) if err.code() == ErrorCode::NotFound => return Ok(Vec::new()),
         Err(err) => return Err(GitError::Git2(err)),
     };
 
+    let mut entries = Vec::new();
     for conflict in conflicts {
         let conflict = conflict?;
         let path = conflict
@@ -160,13 +846,10 @@ fn index_conflicted_paths(repo: &git2::Repository) -> Result<Vec<String>, GitErr
             .map(|entry| String::from_utf8(entry.path.clone()))
             .transpose()?;
         if let Some(path) = path {
-            paths.insert(path);
+            entries.push((path, conflict));
         }
     }
-
-    let mut sorted: Vec<String> = paths.into_iter().collect();
-    sorted.sort();
-    Ok(sorted)
+    Ok(entries)
 }
 
 fn hash_bytes(bytes: &[u8]) -> String {
@@ -0,0 +1,118 @@
+use crate::git::error::GitError;
+use crate::git::status::open_repo;
+use crate::git::types::RepoStatsDto;
+use git2::{BranchType, Repository};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Upper bound on commits walked by `repo_stats`. Repositories with more reachable
+/// commits report this value instead of walking their entire history.
+const COMMIT_COUNT_CAP: usize = 100_000;
+
+/// Loose object count above which running `gc` is recommended. Matches git's
+/// default `gc.auto` threshold.
+const GC_LOOSE_OBJECT_THRESHOLD: usize = 6700;
+
+/// Collects size and object statistics for the repository containing `cwd`.
+///
+/// `commit_count` stops at 100,000 commits so huge histories don't stall the call.
+pub fn repo_stats(cwd: &Path) -> Result<RepoStatsDto, GitError> {
+    let repo = open_repo(cwd)?;
+
+    let mut branch_count = 0;
+    for branch in repo.branches(Some(BranchType::Local))? {
+        branch?;
+        branch_count += 1;
+    }
+    let tag_count = repo.tag_names(None)?.len();
+
+    let mut object_count = 0;
+    repo.odb()?.foreach(|_| {
+        object_count += 1;
+        true
+    })?;
+
+    let objects_dir = repo.commondir().join("objects");
+    let (loose_object_count, pack_size_bytes) = scan_objects_dir(&objects_dir)?;
+
+    Ok(RepoStatsDto {
+        commit_count: count_commits(&repo)?,
+        branch_count,
+        tag_count,
+        object_count,
+        pack_size_bytes,
+        loose_object_count,
+        gc_recommended: loose_object_count > GC_LOOSE_OBJECT_THRESHOLD,
+    })
+}
+
+/// Runs `git gc` to pack loose objects and prune unreachable ones.
+///
+/// This can take a long time on large repositories; callers must run it off the
+/// async event loop.
+pub fn gc(cwd: &Path, aggressive: bool) -> Result<(), GitError> {
+    let mut args = vec!["gc", "--quiet"];
+    if aggressive {
+        args.push("--aggressive");
+    }
+    run_git_command(cwd, args)?;
+    Ok(())
+}
+
+fn count_commits(repo: &Repository) -> Result<usize, GitError> {
+    let mut revwalk = repo.revwalk()?;
+    for reference in repo.references()? {
+        let reference = reference?;
+        // Tags can point at trees or blobs; only commit tips seed the walk.
+        if let Ok(commit) = reference.peel_to_commit() {
+            revwalk.push(commit.id())?;
+        }
+    }
+    Ok(revwalk.take(COMMIT_COUNT_CAP).filter(Result::is_ok).count())
+}
+
+fn scan_objects_dir(objects_dir: &Path) -> Result<(usize, u64), GitError> {
+    let mut loose_object_count = 0;
+    for entry in fs::read_dir(objects_dir).map_err(GitError::Io)? {
+        let entry = entry.map_err(GitError::Io)?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        // Loose objects live in two-hex-digit fan-out directories.
+        if name.len() == 2 && name.chars().all(|c| c.is_ascii_hexdigit()) {
+            loose_object_count += fs::read_dir(entry.path()).map_err(GitError::Io)?.count();
+        }
+    }
+
+    let mut pack_size_bytes = 0;
+    let pack_dir = objects_dir.join("pack");
+    if pack_dir.is_dir() {
+        for entry in fs::read_dir(&pack_dir).map_err(GitError::Io)? {
+            let entry = entry.map_err(GitError::Io)?;
+            if entry.path().extension().is_some_and(|ext| ext == "pack") {
+                pack_size_bytes += entry.metadata().map_err(GitError::Io)?.len();
+            }
+        }
+    }
+
+    Ok((loose_object_count, pack_size_bytes))
+}
+
+fn run_git_command<I, S>(cwd: &Path, args: I) -> Result<std::process::Output, GitError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .map_err(GitError::Io)?;
+    if !output.status.success() {
+        return Err(GitError::GitFailed {
+            code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+    Ok(output)
+}
@@ -7,12 +7,20 @@
 // - scanner: Repository scanning and detection
 // - status: Git status operations
 // - branches: Branch listing and management
-// - remotes: Remote operations (pull, push)
+// - remotes: Remote operations (fetch, pull, push)
+// - credentials: Authentication for git2 network operations
 // - worktrees: Worktree management
 // - stashes: Stash operations
 // - tags: Tag operations
+// - reflog: Reflog listing and restore
+// - commit_lint: Conventional commit message validation
+// - patches: Applying and exporting patches
+// - maintenance: Repository statistics and housekeeping
 // - diff: Diff generation
-// - operations: High-level operations (commit, merge, rebase, reset, revert, squash)
+// - tree: Browsing the tree of a revision
+// - watcher: File watching for change notifications
+// - operations: High-level operations (commit, merge, rebase, reset, revert, squash, drop, reorder,
+//   archive)
 
 mod types;
 mod error;
@@ -21,10 +29,17 @@ mod scanner;
 mod status;
 mod branches;
 mod remotes;
+mod credentials;
 mod worktrees;
 mod stashes;
 mod tags;
+mod reflog;
+mod commit_lint;
+mod patches;
+mod maintenance;
 mod diff;
+mod tree;
+mod watcher;
 mod operations;
 
 // Re-export all public types
@@ -33,40 +48,84 @@ pub use types::*;
 // Re-export error types
 pub use error::{GitError, is_missing_ref_error};
 
+// Re-export proxy functions
+pub use proxy::invalidate_proxy_cache;
+
 // Re-export scanner functions
-pub use scanner::{detect_repo, scan_repos, canonicalize_path};
+pub use scanner::{
+    detect_repo, scan_repos, has_any_repo, canonicalize_path, ActiveScan,
+    DEFAULT_REPO_PROBE_CHECKS,
+};
 
 // Re-export status functions
 pub use status::{
-    status, diff, diff_stats_worktree, diff_stats_against_branch,
-    stage_paths, unstage_paths, discard_paths, stage_all, unstage_all,
-    list_submodules,
+    status, repo_overview, is_clean, operation_state, diff, diff_stats_worktree,
+    diff_stats_against_branch, stage_paths, unstage_paths, discard_paths, resolve_conflict,
+    stage_all, unstage_all, list_submodules,
 };
 
 // Re-export branch functions
 pub use branches::{
-    list_branches, list_remote_branches, default_branch, current_branch,
+    list_branches, list_remote_branches, branch_sync_status, default_branch, current_branch,
     branch_exists, create_branch, delete_branch, checkout_local_branch,
     smart_checkout_branch,
 };
 
 // Re-export remote functions
-pub use remotes::{list_remotes, pull, push};
+pub use remotes::{list_remotes, set_remote_url, add_remote, remove_remote, fetch, pull, push};
+
+// Re-export credential handling
+pub use credentials::{
+    remote_callbacks, credential_host, CredentialBroker, CredentialPrompt, CredentialProvider,
+    GitCredential, NoCredentials,
+};
 
 // Re-export worktree functions
-pub use worktrees::{list_worktrees, add_worktree, remove_worktree, detach_worktree_head};
+pub use worktrees::{
+    list_worktrees, worktrees_with_stats, add_worktree, validate_add_worktree, remove_worktree,
+    move_worktree, repair_worktrees, detach_worktree_head, worktree_disk_usage,
+    suggest_worktree_name, get_sparse_checkout, set_sparse_checkout,
+};
+
+// Re-export patch functions
+pub use patches::{apply_patch, format_patch};
+
+// Re-export maintenance functions
+pub use maintenance::{repo_stats, gc};
 
 // Re-export stash functions
-pub use stashes::{list_stashes, apply_stash, drop_stash, stash_save};
+pub use stashes::{list_stashes, apply_stash, drop_stash, rename_stash, stash_save};
 
 // Re-export tag functions
 pub use tags::{list_tags};
 
+// Re-export reflog functions
+pub use reflog::{list_reflog, restore_to_reflog};
+
+// Re-export commit lint functions
+pub use commit_lint::{
+    validate_commit_message, validate_commit_message_with_limit, DEFAULT_MAX_SUBJECT_LENGTH,
+};
+
 // Re-export diff functions
-pub use diff::{get_unified_diff};
+pub use diff::{
+    get_unified_diff, stash_diff, diff_worktrees, commit_files, conflict_versions, expand_context,
+};
+
+// Re-export tree functions
+pub use tree::{list_tree, read_object};
+
+// Re-export watcher functions
+pub use watcher::{watch_repo, watch_scan_root, RepoWatcher, ScanRootWatcher};
 
 // Re-export operation functions
 pub use operations::{
-    list_commits, list_commits_range, commit, merge_into_branch, rebase_branch, reset, revert,
-    squash_commits, commits_in_remote,
+    list_commits, list_commits_paged, list_commits_range, last_commit_for_path, log_for_path,
+    unpushed_commits, author_stats,
+    commit, amend_preview,
+    can_merge_cleanly, merge_into_branch, merge_into_branch_with_mode,
+    rebase_branch, rebase_abort, rebase_continue,
+    reset, revert,
+    revert_abort, cherry_pick_abort, clear_pending_message,
+    squash_commits, drop_commits, reorder_commits, commits_in_remote, archive,
 };
@@ -1,10 +1,18 @@
 use crate::git::branches::checkout_branch;
+use crate::git::diff::{conflicted_paths, diff_file_summaries};
 use crate::git::error::{GitError, is_missing_ref_error};
 use crate::git::proxy::configure_proxy;
 use crate::git::status::open_repo;
-use crate::git::types::CommitInfoDto;
-use git2::{build, ErrorCode, MergeOptions, Oid, RevertOptions, ResetType, Repository, Sort, StashFlags, BranchType};
+use crate::git::types::{
+    AmendPreviewDto, ArchiveFormat, AuthorStatDto, CommitInfoDto, CommitPageDto, DiffDeltaStatus,
+    DiffFileSummaryDto, FastForwardMode, GitNetworkOptions, MergePreviewDto, SquashPreviewDto,
+};
+use git2::{
+    build, BranchType, DiffFindOptions, DiffOptions, ErrorCode, MergeOptions, Oid,
+    RepositoryState, ResetType, Repository, RevertOptions, Sort, StashFlags,
+};
 use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
 use std::path::Path;
 use std::process::Command;
 
@@ -51,6 +59,19 @@ pub fn list_commits(
     Ok(commits)
 }
 
+/// Like `list_commits`, but also reports whether more commits follow the returned page.
+pub fn list_commits_paged(
+    cwd: &Path,
+    limit: usize,
+    skip: Option<usize>,
+) -> Result<CommitPageDto, GitError> {
+    // Fetch one extra commit to learn whether another page exists
+    let mut commits = list_commits(cwd, limit.saturating_add(1), skip)?;
+    let has_more = commits.len() > limit;
+    commits.truncate(limit);
+    Ok(CommitPageDto { commits, has_more })
+}
+
 pub fn list_commits_range(
     cwd: &Path,
     include_branch: &str,
@@ -94,7 +115,204 @@ pub fn list_commits_range(
     Ok(commits)
 }
 
-pub fn commit(cwd: &Path, message: &str, stage_all: bool, amend: bool) -> Result<(), GitError> {
+/// Returns the newest commit reachable from `rev` (HEAD by default) that changed `path`,
+/// or `None` if no commit did. `path` may name a file or a directory.
+pub fn last_commit_for_path(
+    cwd: &Path,
+    path: &str,
+    rev: Option<String>,
+) -> Result<Option<CommitInfoDto>, GitError> {
+    let repo = open_repo(cwd)?;
+    let start = match repo.revparse_single(rev.as_deref().unwrap_or("HEAD")) {
+        Ok(object) => object.peel_to_commit()?,
+        Err(err) if rev.is_none() && err.code() == ErrorCode::NotFound => return Ok(None),
+        Err(err) => return Err(GitError::Git2(err)),
+    };
+    let path = Path::new(path.trim_matches('/'));
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(start.id())?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit_touches_path(&commit, path)? {
+            return Ok(Some(commit_info(&commit)));
+        }
+    }
+    Ok(None)
+}
+
+/// Lists up to `limit` commits reachable from HEAD that changed `path`, newest first, like
+/// `git log -- <path>`. With `follow`, history continues across renames of a single file
+/// (`git log --follow`), which libgit2 can't do, so that case shells out to git.
+pub fn log_for_path(
+    cwd: &Path,
+    path: &str,
+    limit: usize,
+    follow: bool,
+) -> Result<Vec<CommitInfoDto>, GitError> {
+    let repo = open_repo(cwd)?;
+    let path = path.trim_matches('/');
+    if follow {
+        let workdir = repo.workdir().unwrap_or_else(|| repo.path());
+        let limit = format!("--max-count={limit}");
+        let output = run_git_command(
+            workdir,
+            ["log", "--follow", "--format=%H", limit.as_str(), "--", path],
+        )?;
+        let mut commits = Vec::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let commit = repo.find_commit(Oid::from_str(line.trim())?)?;
+            commits.push(commit_info(&commit));
+        }
+        return Ok(commits);
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    if let Err(err) = revwalk.push_head() {
+        if err.code() == ErrorCode::UnbornBranch || is_missing_ref_error(&err) {
+            return Ok(Vec::new());
+        }
+        return Err(GitError::Git2(err));
+    }
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        if commits.len() >= limit {
+            break;
+        }
+        let commit = repo.find_commit(oid?)?;
+        if commit_touches_path(&commit, Path::new(path))? {
+            commits.push(commit_info(&commit));
+        }
+    }
+    Ok(commits)
+}
+
+/// Lists commits reachable from `branch` (HEAD by default) that are neither on its upstream
+/// nor on any remote-tracking ref, newest first.
+pub fn unpushed_commits(
+    cwd: &Path,
+    branch: Option<String>,
+) -> Result<Vec<CommitInfoDto>, GitError> {
+    let repo = open_repo(cwd)?;
+    let (tip, branch) = match branch {
+        Some(name) => {
+            let branch = repo.find_branch(&name, BranchType::Local)?;
+            (branch.get().peel_to_commit()?, Some(branch))
+        }
+        None => {
+            let head = match repo.head() {
+                Ok(head) => head,
+                Err(err) if err.code() == ErrorCode::UnbornBranch || is_missing_ref_error(&err) => {
+                    return Ok(Vec::new());
+                }
+                Err(err) => return Err(GitError::Git2(err)),
+            };
+            let tip = head.peel_to_commit()?;
+            (tip, head.is_branch().then(|| git2::Branch::wrap(head)))
+        }
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(tip.id())?;
+    // The upstream may be a local branch, which the remote glob below doesn't cover.
+    if let Some(upstream) = branch.and_then(|branch| branch.upstream().ok()) {
+        revwalk.hide(upstream.get().peel_to_commit()?.id())?;
+    }
+    revwalk.hide_glob("refs/remotes/*")?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        commits.push(commit_info(&repo.find_commit(oid?)?));
+    }
+    Ok(commits)
+}
+
+/// Counts commits per author over the newest `limit` commits reachable from HEAD, most
+/// prolific first. Identities go through the repository's mailmap, like `git shortlog`.
+pub fn author_stats(
+    cwd: &Path,
+    limit: usize,
+    exclude_merges: bool,
+) -> Result<Vec<AuthorStatDto>, GitError> {
+    let repo = open_repo(cwd)?;
+    let mut revwalk = repo.revwalk()?;
+    if let Err(err) = revwalk.push_head() {
+        if err.code() == ErrorCode::UnbornBranch || is_missing_ref_error(&err) {
+            return Ok(Vec::new());
+        }
+        return Err(GitError::Git2(err));
+    }
+    revwalk.set_sorting(Sort::TIME)?;
+    let mailmap = repo.mailmap()?;
+
+    let mut counts: HashMap<(String, String), usize> = HashMap::new();
+    for oid in revwalk.take(limit) {
+        let commit = repo.find_commit(oid?)?;
+        if exclude_merges && commit.parent_count() > 1 {
+            continue;
+        }
+        let author = commit.author_with_mailmap(&mailmap)?;
+        let key = (
+            author.name().unwrap_or_default().to_string(),
+            author.email().unwrap_or_default().to_string(),
+        );
+        *counts.entry(key).or_default() += 1;
+    }
+
+    let mut stats: Vec<AuthorStatDto> = counts
+        .into_iter()
+        .map(|((name, email), commit_count)| AuthorStatDto {
+            name,
+            email,
+            commit_count,
+        })
+        .collect();
+    stats.sort_by(|a, b| {
+        b.commit_count
+            .cmp(&a.commit_count)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.email.cmp(&b.email))
+    });
+    Ok(stats)
+}
+
+/// Whether `commit` changed the file or directory at `path`. A merge only counts when
+/// `path` differs from every parent, matching git's default history simplification.
+fn commit_touches_path(commit: &git2::Commit, path: &Path) -> Result<bool, GitError> {
+    let entry_id = |tree: &git2::Tree| tree.get_path(path).ok().map(|entry| entry.id());
+    let id = entry_id(&commit.tree()?);
+    if commit.parent_count() == 0 {
+        return Ok(id.is_some());
+    }
+    for parent in commit.parents() {
+        if entry_id(&parent.tree()?) == id {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn commit_info(commit: &git2::Commit) -> CommitInfoDto {
+    CommitInfoDto {
+        id: commit.id().to_string(),
+        summary: commit.summary().unwrap_or_default().to_string(),
+        author: commit.author().name().unwrap_or_default().to_string(),
+        relative_time: format_relative_time(commit.time()),
+    }
+}
+
+pub fn commit(
+    cwd: &Path,
+    message: &str,
+    stage_all: bool,
+    amend: bool,
+    co_authors: &[String],
+) -> Result<(), GitError> {
+    let message = append_co_author_trailers(message, co_authors)?;
+    let message = message.as_str();
     let repo = open_repo(cwd)?;
     let mut index = repo.index()?;
 
@@ -107,6 +325,25 @@ pub fn commit(cwd: &Path, message: &str, stage_all: bool, amend: bool) -> Result
     let tree = repo.find_tree(tree_id)?;
     let sig = repo.signature()?;
 
+    if !amend {
+        let head_tree_id = match repo.head() {
+            Ok(head) => Some(head.peel_to_tree()?.id()),
+            Err(err) if err.code() == ErrorCode::UnbornBranch => None,
+            Err(err) => return Err(GitError::Git2(err)),
+        };
+        let matches_head = match head_tree_id {
+            Some(head_tree_id) => head_tree_id == tree_id,
+            None => index.is_empty(),
+        };
+        if matches_head {
+            // With stage_all the index already holds every change, so it can't be unstaged work
+            if !stage_all && has_worktree_changes(&repo)? {
+                return Err(GitError::NothingStaged);
+            }
+            return Err(GitError::NothingToCommit);
+        }
+    }
+
     if amend {
         let head = repo.head().map_err(|err| {
             if err.code() == ErrorCode::UnbornBranch {
@@ -135,14 +372,7 @@ pub fn commit(cwd: &Path, message: &str, stage_all: bool, amend: bool) -> Result
     let mut parents = Vec::new();
     if let Ok(head) = repo.head() {
         if let Some(head_id) = head.target() {
-            let head_commit = repo.find_commit(head_id)?;
-            if head_commit.tree_id() == tree.id() {
-                return Err(GitError::GitFailed {
-                    code: None,
-                    stderr: "nothing to commit".to_string(),
-                });
-            }
-            parents.push(head_commit);
+            parents.push(repo.find_commit(head_id)?);
         }
     }
     let parent_refs: Vec<&git2::Commit<'_>> = parents.iter().collect();
@@ -150,10 +380,152 @@ pub fn commit(cwd: &Path, message: &str, stage_all: bool, amend: bool) -> Result
     Ok(())
 }
 
+/// Shows what `commit` with `amend` would create: HEAD's message and parents, and the
+/// changes the new tree brings. With `stage_all`, unstaged and untracked changes are
+/// included just as `commit` would stage them. Nothing is written to the index or refs.
+pub fn amend_preview(cwd: &Path, stage_all: bool) -> Result<AmendPreviewDto, GitError> {
+    let repo = open_repo(cwd)?;
+    let head = match repo.head() {
+        Ok(head) => head.peel_to_commit()?,
+        Err(err) if err.code() == ErrorCode::UnbornBranch => {
+            return Err(GitError::GitFailed {
+                code: None,
+                stderr: "cannot amend without any commits".to_string(),
+            });
+        }
+        Err(err) => return Err(GitError::Git2(err)),
+    };
+    let head_tree = head.tree()?;
+    let parent_tree = match head.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+
+    let index = repo.index()?;
+    let changes_from = |tree: Option<&git2::Tree>| -> Result<Vec<DiffFileSummaryDto>, GitError> {
+        let mut opts = DiffOptions::new();
+        let mut diff = if stage_all {
+            opts.include_untracked(true).recurse_untracked_dirs(true);
+            repo.diff_tree_to_workdir_with_index(tree, Some(&mut opts))?
+        } else {
+            repo.diff_tree_to_index(tree, Some(&index), Some(&mut opts))?
+        };
+        diff.find_similar(Some(&mut DiffFindOptions::new()))?;
+        let mut files = diff_file_summaries(&diff)?;
+        for file in &mut files {
+            // Untracked files would be added by the commit's stage-all
+            if file.status == DiffDeltaStatus::Untracked {
+                file.status = DiffDeltaStatus::Added;
+            }
+        }
+        Ok(files)
+    };
+
+    Ok(AmendPreviewDto {
+        head_id: head.id().to_string(),
+        message: head.message().unwrap_or_default().to_string(),
+        parent_ids: head.parent_ids().map(|id| id.to_string()).collect(),
+        staged_files: changes_from(Some(&head_tree))?,
+        files: changes_from(parent_tree.as_ref())?,
+    })
+}
+
+/// Whether the working tree has unstaged or untracked changes, ignoring ignored files.
+fn has_worktree_changes(repo: &Repository) -> Result<bool, GitError> {
+    let mut opts = git2::StatusOptions::new();
+    opts.include_untracked(true).include_ignored(false);
+    let statuses = repo.statuses(Some(&mut opts))?;
+    Ok(statuses.iter().any(|entry| {
+        entry.status().intersects(
+            git2::Status::WT_NEW
+                | git2::Status::WT_MODIFIED
+                | git2::Status::WT_DELETED
+                | git2::Status::WT_RENAMED
+                | git2::Status::WT_TYPECHANGE,
+        )
+    }))
+}
+
+fn append_co_author_trailers(message: &str, co_authors: &[String]) -> Result<String, GitError> {
+    if co_authors.is_empty() {
+        return Ok(message.to_string());
+    }
+
+    let mut trailers = Vec::with_capacity(co_authors.len());
+    for co_author in co_authors {
+        let co_author = co_author.trim();
+        let valid = match (co_author.find('<'), co_author.strip_suffix('>')) {
+            (Some(open), Some(rest)) => {
+                !co_author[..open].trim().is_empty() && rest.len() > open + 1
+            }
+            _ => false,
+        };
+        if !valid {
+            return Err(GitError::GitFailed {
+                code: None,
+                stderr: format!("invalid co-author \"{co_author}\": expected \"Name <email>\""),
+            });
+        }
+        trailers.push(format!("Co-authored-by: {co_author}"));
+    }
+
+    Ok(format!("{}\n\n{}", message.trim_end(), trailers.join("\n")))
+}
+
+/// Merges `source` into `target` in memory to report whether the merge would conflict, and
+/// where, without changing any ref, the index or the worktree. Both accept any revision.
+pub fn can_merge_cleanly(
+    cwd: &Path,
+    target: &str,
+    source: &str,
+) -> Result<MergePreviewDto, GitError> {
+    let repo = open_repo(cwd)?;
+    let ours = repo.revparse_single(target)?.peel_to_commit()?;
+    let theirs = repo.revparse_single(source)?.peel_to_commit()?;
+
+    let up_to_date = ours.id() == theirs.id() || repo.graph_descendant_of(ours.id(), theirs.id())?;
+    let fast_forward = !up_to_date && repo.graph_descendant_of(theirs.id(), ours.id())?;
+    let conflicted_paths = if up_to_date || fast_forward {
+        Vec::new()
+    } else {
+        conflicted_paths(&repo.merge_commits(&ours, &theirs, None)?)?
+    };
+
+    Ok(MergePreviewDto {
+        up_to_date,
+        fast_forward,
+        has_conflicts: !conflicted_paths.is_empty(),
+        conflicted_paths,
+    })
+}
+
+/// Merges `source_branch` into `target_branch`, checking the target out first if needed,
+/// always creating a merge commit. With `auto_stash`, local changes are stashed around the
+/// merge; without it, any uncommitted change refuses the merge, even one the merge would
+/// not touch.
 pub fn merge_into_branch(
     repo_root: &Path,
     target_branch: &str,
     source_branch: &str,
+    auto_stash: bool,
+) -> Result<(), GitError> {
+    merge_into_branch_with_mode(
+        repo_root,
+        target_branch,
+        source_branch,
+        auto_stash,
+        FastForwardMode::default(),
+    )
+}
+
+/// Like `merge_into_branch`, with `fast_forward` choosing whether the target branch may
+/// simply be moved forward instead of getting a merge commit.
+pub fn merge_into_branch_with_mode(
+    repo_root: &Path,
+    target_branch: &str,
+    source_branch: &str,
+    auto_stash: bool,
+    fast_forward: FastForwardMode,
 ) -> Result<(), GitError> {
     let mut repo = open_repo(repo_root)?;
     if target_branch.trim().is_empty() || source_branch.trim().is_empty() {
@@ -186,6 +558,28 @@ pub fn merge_into_branch(
             .to_string()
     };
 
+    let target_oid = repo.refname_to_id(&target_refname)?;
+    let source_oid = repo.refname_to_id(&source_refname)?;
+    if target_oid == source_oid || repo.graph_descendant_of(target_oid, source_oid)? {
+        return Ok(());
+    }
+    let fast_forward = match fast_forward {
+        FastForwardMode::Never => false,
+        FastForwardMode::Auto => repo.graph_descendant_of(source_oid, target_oid)?,
+        FastForwardMode::Only => {
+            if !repo.graph_descendant_of(source_oid, target_oid)? {
+                return Err(GitError::GitFailed {
+                    code: None,
+                    stderr: format!(
+                        "not possible to fast-forward {target_branch} to {source_branch}; \
+                         the branches have diverged"
+                    ),
+                });
+            }
+            true
+        }
+    };
+
     let original_head = repo
         .head()
         .ok()
@@ -197,6 +591,14 @@ pub fn merge_into_branch(
 
     let mut created_stash = false;
     if is_repo_dirty(&repo)? {
+        if !auto_stash {
+            return Err(GitError::GitFailed {
+                code: None,
+                stderr: "working tree has uncommitted changes; commit or stash them before \
+                         merging"
+                    .to_string(),
+            });
+        }
         let msg = "parallel-cli-runner: auto-stash before merge";
         let sig = repo.signature()?;
         repo.stash_save(&sig, msg, Some(StashFlags::INCLUDE_UNTRACKED))?;
@@ -212,7 +614,31 @@ pub fn merge_into_branch(
         }
     }
 
-    {
+    if fast_forward {
+        let result = (|| -> Result<(), GitError> {
+            let source_commit = repo.find_commit(source_oid)?;
+            let mut checkout_opts = build::CheckoutBuilder::new();
+            repo.checkout_tree(source_commit.as_object(), Some(&mut checkout_opts))?;
+            let message = format!("merge {source_branch}: Fast-forward");
+            repo.find_reference(&target_refname)?.set_target(source_oid, &message)?;
+            Ok(())
+        })();
+        if let Err(err) = result {
+            // Put back the branch and files the user had before the merge started
+            let restore_to = if switched {
+                original_head.as_deref()
+            } else {
+                Some(target_refname.as_str())
+            };
+            if let Some(refname) = restore_to {
+                let _ = checkout_branch(&repo, refname);
+            }
+            if created_stash {
+                let _ = repo.stash_pop(0, None);
+            }
+            return Err(err);
+        }
+    } else {
         let annotated = {
             let source_ref = repo.find_reference(&source_refname)?;
             repo.reference_to_annotated_commit(&source_ref)?
@@ -332,7 +758,15 @@ pub fn rebase_branch(
         checkout_branch(&repo, &target_refname)?;
     }
 
-    run_git_command(repo_root, ["rebase", "--autostash", onto_branch])?;
+    let result = match run_git_command(repo_root, ["rebase", "--autostash", onto_branch]) {
+        Ok(_) => Ok(()),
+        // A conflicted rebase stays on the target branch so it can be resolved and continued.
+        // The in-progress state is checked rather than git's (translated) output.
+        Err(GitError::GitFailed { stderr, .. }) if rebase_in_progress(&repo) => {
+            return Err(GitError::RebaseConflict(stderr));
+        }
+        Err(err) => Err(err),
+    };
 
     if switched {
         if let Some(original_head) = original_head {
@@ -340,7 +774,17 @@ pub fn rebase_branch(
         }
     }
 
-    Ok(())
+    result
+}
+
+fn rebase_in_progress(repo: &Repository) -> bool {
+    matches!(
+        repo.state(),
+        RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge
+            | RepositoryState::ApplyMailboxOrRebase
+    )
 }
 
 pub fn reset(repo_root: &Path, target: &str, mode: &str) -> Result<(), GitError> {
@@ -407,6 +851,89 @@ pub fn revert(repo_root: &Path, commit_str: &str) -> Result<(), GitError> {
     Ok(())
 }
 
+/// Abandons a rebase that stopped on conflicts, putting the branch back where it started.
+pub fn rebase_abort(cwd: &Path) -> Result<(), GitError> {
+    require_rebase_in_progress(cwd)?;
+    run_git_command(cwd, ["rebase", "--abort"])?;
+    Ok(())
+}
+
+/// Resumes a rebase once its conflicts are resolved and staged. Commits keep their original
+/// messages instead of opening an editor.
+pub fn rebase_continue(cwd: &Path) -> Result<(), GitError> {
+    require_rebase_in_progress(cwd)?;
+    run_git_command(cwd, ["-c", "core.editor=true", "rebase", "--continue"])?;
+    Ok(())
+}
+
+fn require_rebase_in_progress(cwd: &Path) -> Result<(), GitError> {
+    if !rebase_in_progress(&open_repo(cwd)?) {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: "no rebase in progress".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Abandons a conflicted revert, resetting the worktree and index to HEAD.
+pub fn revert_abort(cwd: &Path) -> Result<(), GitError> {
+    abort_operation(
+        cwd,
+        "revert",
+        &[RepositoryState::Revert, RepositoryState::RevertSequence],
+    )
+}
+
+/// Abandons a conflicted cherry-pick, resetting the worktree and index to HEAD.
+pub fn cherry_pick_abort(cwd: &Path) -> Result<(), GitError> {
+    abort_operation(
+        cwd,
+        "cherry-pick",
+        &[RepositoryState::CherryPick, RepositoryState::CherryPickSequence],
+    )
+}
+
+fn abort_operation(
+    cwd: &Path,
+    operation: &str,
+    states: &[RepositoryState],
+) -> Result<(), GitError> {
+    let repo = open_repo(cwd)?;
+    if !states.contains(&repo.state()) {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: format!("no {operation} in progress"),
+        });
+    }
+
+    let head = repo.head()?.peel_to_commit()?;
+    let mut checkout = build::CheckoutBuilder::new();
+    checkout.force();
+    repo.reset(head.as_object(), ResetType::Hard, Some(&mut checkout))?;
+    repo.cleanup_state()?;
+    Ok(())
+}
+
+/// Removes leftover commit message drafts (`MERGE_MSG`, `SQUASH_MSG` and `COMMIT_EDITMSG`)
+/// so they no longer pre-fill the next commit. Stale operation state is cleaned up too,
+/// unless a merge, revert or cherry-pick is still in progress.
+pub fn clear_pending_message(cwd: &Path) -> Result<(), GitError> {
+    let repo = open_repo(cwd)?;
+    for name in ["MERGE_MSG", "SQUASH_MSG", "COMMIT_EDITMSG"] {
+        match std::fs::remove_file(repo.path().join(name)) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                return Err(GitError::Io(err));
+            }
+            _ => {}
+        }
+    }
+    if repo.state() == RepositoryState::Clean {
+        repo.cleanup_state()?;
+    }
+    Ok(())
+}
+
 /// Helper struct for building and validating commit graphs during squash operations.
 struct CommitGraph {
     /// Set of selected commit OIDs
@@ -524,52 +1051,40 @@ impl CommitGraph {
     }
 }
 
+/// Result of replaying a branch with a range of commits squashed together.
+struct SquashReplay {
+    /// Tip of the rewritten history (not yet referenced by any branch)
+    head_oid: Oid,
+    /// Message of the squashed commit
+    squash_message: String,
+    /// Number of commits between the base and HEAD before squashing
+    replayed_count: usize,
+}
+
 /// Squash commits by replaying them onto the base commit.
 ///
 /// This function cherry-picks each commit in order, squashing selected commits
-/// together into a single commit with combined messages.
+/// together into a single commit with combined messages, or `message_override` when given.
 fn replay_commits_squashed(
     repo: &mut Repository,
     graph: &CommitGraph,
     committer: &git2::Signature,
-) -> Result<Oid, GitError> {
-    let mut revwalk = repo.revwalk()?;
-    revwalk.push_head()?;
-    revwalk.hide(graph.base())?;
-    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
-    let commits_to_replay: Vec<Oid> = revwalk.filter_map(Result::ok).collect();
-
-    let commits_to_replay_set: HashSet<Oid> = commits_to_replay.iter().copied().collect();
+    message_override: Option<&str>,
+) -> Result<SquashReplay, GitError> {
+    let commits_to_replay = commits_since_base(repo, graph)?;
+    let replayed_count = commits_to_replay.len();
     let selected = graph.selected();
 
-    // Verify all selected commits are on current branch
-    if !selected.is_subset(&commits_to_replay_set) {
-        return Err(GitError::GitFailed {
-            code: None,
-            stderr: "selected commits are not on the current branch".to_string(),
-        });
-    }
-
     let mut current_oid = graph.base();
     let mut squashing = false;
     let mut squash_parent = graph.base();
     let mut squash_messages: Vec<String> = Vec::new();
     let mut squash_author: Option<git2::Signature<'static>> = None;
+    let mut squash_message = String::new();
 
     for oid in commits_to_replay {
         let commit = repo.find_commit(oid)?;
-        let current_commit = repo.find_commit(current_oid)?;
-        let mut index = repo.cherrypick_commit(&commit, &current_commit, 0, None)?;
-
-        if index.has_conflicts() {
-            return Err(GitError::GitFailed {
-                code: None,
-                stderr: "squash resulted in conflicts; resolve them manually".to_string(),
-            });
-        }
-
-        let tree_id = index.write_tree_to(repo)?;
-        let tree = repo.find_tree(tree_id)?;
+        let tree = replay_tree(repo, &commit, current_oid, "squash")?;
 
         if selected.contains(&oid) {
             // Squash mode: combine commit messages
@@ -583,7 +1098,10 @@ fn replay_commits_squashed(
                 squash_messages.push(commit.message().unwrap_or("").trim_end().to_string());
             }
 
-            let message = squash_messages.join("\n\n");
+            squash_message = match message_override {
+                Some(message) => message.to_string(),
+                None => squash_messages.join("\n\n"),
+            };
             let author = squash_author.as_ref().ok_or_else(|| GitError::GitFailed {
                 code: None,
                 stderr: "failed to resolve squash author".to_string(),
@@ -593,7 +1111,7 @@ fn replay_commits_squashed(
                 None,
                 author,
                 committer,
-                &message,
+                &squash_message,
                 &tree,
                 &[&parent_commit],
             )?;
@@ -619,63 +1137,309 @@ fn replay_commits_squashed(
         }
     }
 
+    Ok(SquashReplay {
+        head_oid: current_oid,
+        squash_message,
+        replayed_count,
+    })
+}
+
+/// Replays the branch onto the base commit, leaving out the selected commits.
+fn replay_commits_dropped(
+    repo: &Repository,
+    graph: &CommitGraph,
+    committer: &git2::Signature,
+) -> Result<Oid, GitError> {
+    let mut current_oid = graph.base();
+    for oid in commits_since_base(repo, graph)? {
+        if graph.selected().contains(&oid) {
+            continue;
+        }
+        current_oid = replay_commit(repo, oid, current_oid, committer, "drop")?;
+    }
+    Ok(current_oid)
+}
+
+/// Replays the selected range in `new_order` onto the base, followed by the rest of the branch.
+fn replay_commits_reordered(
+    repo: &Repository,
+    graph: &CommitGraph,
+    new_order: &[Oid],
+    committer: &git2::Signature,
+) -> Result<Oid, GitError> {
+    let remaining: Vec<Oid> = commits_since_base(repo, graph)?
+        .into_iter()
+        .filter(|oid| !graph.selected().contains(oid))
+        .collect();
+
+    let mut current_oid = graph.base();
+    for &oid in new_order.iter().chain(remaining.iter()) {
+        current_oid = replay_commit(repo, oid, current_oid, committer, "reorder")?;
+    }
     Ok(current_oid)
 }
 
-pub fn squash_commits(repo_root: &Path, commit_ids: &[String]) -> Result<(), GitError> {
+/// Replays a single commit onto `onto`, keeping its author and message.
+fn replay_commit(
+    repo: &Repository,
+    oid: Oid,
+    onto: Oid,
+    committer: &git2::Signature,
+    operation: &str,
+) -> Result<Oid, GitError> {
+    let commit = repo.find_commit(oid)?;
+    let tree = replay_tree(repo, &commit, onto, operation)?;
+    let author = signature_from_commit(&commit)?;
+    let message = commit.message().unwrap_or("").to_string();
+    let parent_commit = repo.find_commit(onto)?;
+    Ok(repo.commit(
+        None,
+        &author,
+        committer,
+        &message,
+        &tree,
+        &[&parent_commit],
+    )?)
+}
+
+/// Lists the commits between the graph's base and HEAD, oldest first.
+///
+/// Fails if any selected commit is not part of that range.
+fn commits_since_base(repo: &Repository, graph: &CommitGraph) -> Result<Vec<Oid>, GitError> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.hide(graph.base())?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    let commits: Vec<Oid> = revwalk.filter_map(Result::ok).collect();
+
+    // Verify all selected commits are on current branch
+    let commit_set: HashSet<Oid> = commits.iter().copied().collect();
+    if !graph.selected().is_subset(&commit_set) {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: "selected commits are not on the current branch".to_string(),
+        });
+    }
+    Ok(commits)
+}
+
+/// Cherry-picks `commit` onto `onto` in memory and returns the resulting tree.
+fn replay_tree<'repo>(
+    repo: &'repo Repository,
+    commit: &git2::Commit,
+    onto: Oid,
+    operation: &str,
+) -> Result<git2::Tree<'repo>, GitError> {
+    let onto_commit = repo.find_commit(onto)?;
+    let mut index = repo.cherrypick_commit(commit, &onto_commit, 0, None)?;
+    if index.has_conflicts() {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: format!("{operation} resulted in conflicts; resolve them manually"),
+        });
+    }
+    let tree_id = index.write_tree_to(repo)?;
+    Ok(repo.find_tree(tree_id)?)
+}
+
+/// Points the current branch at `new_oid` and checks it out.
+fn update_current_branch(
+    repo: &Repository,
+    new_oid: Oid,
+    operation: &str,
+    reflog_message: &str,
+) -> Result<(), GitError> {
+    let head = repo.head()?;
+    let head_name = head.name().ok_or_else(|| GitError::GitFailed {
+        code: None,
+        stderr: "HEAD is detached".to_string(),
+    })?;
+    if !head.is_branch() {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: format!("{operation} requires an attached branch"),
+        });
+    }
+
+    let mut reference = repo.find_reference(head_name)?;
+    reference.set_target(new_oid, reflog_message)?;
+    repo.set_head(head_name)?;
+
+    let mut checkout = build::CheckoutBuilder::new();
+    checkout.force();
+    repo.checkout_head(Some(&mut checkout))?;
+    Ok(())
+}
+
+/// Squashes the selected commits into one and returns what the squash does.
+///
+/// The squashed commit uses `message` verbatim when given, otherwise the selected commits'
+/// messages joined by blank lines. With `dry_run` the history is replayed into unreferenced
+/// commits only: the branch ref, worktree and stash are left untouched, so the returned
+/// preview can be shown before the user confirms.
+pub fn squash_commits(
+    repo_root: &Path,
+    commit_ids: &[String],
+    message: Option<&str>,
+    dry_run: bool,
+) -> Result<SquashPreviewDto, GitError> {
     if commit_ids.len() < 2 {
         return Err(GitError::GitFailed {
             code: None,
             stderr: "select at least two commits to squash".to_string(),
         });
     }
+    if message.is_some_and(|message| message.trim().is_empty()) {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: "squash message cannot be empty".to_string(),
+        });
+    }
 
     let mut repo = open_repo(repo_root)?;
+    if dry_run {
+        if !repo.head()?.is_branch() {
+            return Err(GitError::GitFailed {
+                code: None,
+                stderr: "squash requires an attached branch".to_string(),
+            });
+        }
+        let committer = repo.signature()?;
+        let graph = CommitGraph::build(&repo, commit_ids)?;
+        let replay = replay_commits_squashed(&mut repo, &graph, &committer, message)?;
+        return Ok(squash_preview(&graph, replay));
+    }
+
     let created_stash =
         maybe_create_auto_stash(&mut repo, "parallel-cli-runner: auto-stash before squash")?;
 
-    let result = (|| -> Result<(), GitError> {
+    let result = (|| -> Result<SquashPreviewDto, GitError> {
         let committer = repo.signature()?;
 
         // Build and validate commit graph
         let graph = CommitGraph::build(&repo, commit_ids)?;
 
         // Replay commits with squashing
-        let current_oid = replay_commits_squashed(&mut repo, &graph, &committer)?;
+        let replay = replay_commits_squashed(&mut repo, &graph, &committer, message)?;
 
         // Update branch reference
-        let head = repo.head()?;
-        let head_name = head.name().ok_or_else(|| GitError::GitFailed {
+        update_current_branch(&repo, replay.head_oid, "squash", "squash commits")?;
+
+        Ok(squash_preview(&graph, replay))
+    })();
+
+    let preview = match result {
+        Ok(preview) => preview,
+        Err(err) => {
+            if created_stash {
+                let _ = repo.stash_pop(0, None);
+            }
+            return Err(err);
+        }
+    };
+
+    if created_stash {
+        restore_auto_stash(&mut repo, "Squash succeeded, but failed to restore stashed changes")?;
+    }
+
+    Ok(preview)
+}
+
+fn squash_preview(graph: &CommitGraph, replay: SquashReplay) -> SquashPreviewDto {
+    SquashPreviewDto {
+        resulting_message: replay.squash_message,
+        commit_count_before: replay.replayed_count,
+        commit_count_after: replay.replayed_count + 1 - graph.selected().len(),
+    }
+}
+
+/// Removes the selected commits from the current branch, replaying the commits after them.
+///
+/// The selection must be a contiguous, linear range that does not include the root commit.
+pub fn drop_commits(repo_root: &Path, commit_ids: &[String]) -> Result<(), GitError> {
+    if commit_ids.is_empty() {
+        return Err(GitError::GitFailed {
             code: None,
-            stderr: "HEAD is detached".to_string(),
-        })?;
-        if !head.is_branch() {
-            return Err(GitError::GitFailed {
-                code: None,
-                stderr: "squash requires an attached branch".to_string(),
-            });
+            stderr: "select at least one commit to drop".to_string(),
+        });
+    }
+
+    let mut repo = open_repo(repo_root)?;
+    let created_stash =
+        maybe_create_auto_stash(&mut repo, "parallel-cli-runner: auto-stash before drop")?;
+
+    let result = (|| -> Result<(), GitError> {
+        let committer = repo.signature()?;
+        let graph = CommitGraph::build(&repo, commit_ids)?;
+        let new_oid = replay_commits_dropped(&repo, &graph, &committer)?;
+        update_current_branch(&repo, new_oid, "drop", "drop commits")
+    })();
+
+    if let Err(err) = result {
+        if created_stash {
+            let _ = repo.stash_pop(0, None);
         }
+        return Err(err);
+    }
 
-        let mut reference = repo.find_reference(head_name)?;
-        reference.set_target(current_oid, "squash commits")?;
-        repo.set_head(head_name)?;
+    if created_stash {
+        restore_auto_stash(&mut repo, "Drop succeeded, but failed to restore stashed changes")?;
+    }
 
-        let mut checkout = build::CheckoutBuilder::new();
-        checkout.force();
-        repo.checkout_head(Some(&mut checkout))?;
+    Ok(())
+}
 
-        Ok(())
+/// Rewrites the current branch so the given commits appear in `new_order` (oldest first).
+///
+/// `new_order` must be a permutation of a contiguous, linear range of the branch; commits
+/// after the range are replayed on top unchanged.
+pub fn reorder_commits(repo_root: &Path, new_order: &[String]) -> Result<(), GitError> {
+    if new_order.len() < 2 {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: "select at least two commits to reorder".to_string(),
+        });
+    }
+
+    let mut repo = open_repo(repo_root)?;
+    let graph = CommitGraph::build(&repo, new_order)?;
+    let order = new_order
+        .iter()
+        .map(|commit| resolve_commit_oid(&repo, commit))
+        .collect::<Result<Vec<_>, _>>()?;
+    if order.len() != graph.selected().len() {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: "reorder list contains duplicate commits".to_string(),
+        });
+    }
+
+    let original: Vec<Oid> = commits_since_base(&repo, &graph)?
+        .into_iter()
+        .filter(|oid| graph.selected().contains(oid))
+        .collect();
+    if original == order {
+        return Ok(());
+    }
+
+    let created_stash =
+        maybe_create_auto_stash(&mut repo, "parallel-cli-runner: auto-stash before reorder")?;
+
+    let result = (|| -> Result<(), GitError> {
+        let committer = repo.signature()?;
+        let new_oid = replay_commits_reordered(&repo, &graph, &order, &committer)?;
+        update_current_branch(&repo, new_oid, "reorder", "reorder commits")
     })();
 
-    if result.is_err() {
+    if let Err(err) = result {
         if created_stash {
             let _ = repo.stash_pop(0, None);
         }
-        return result;
+        return Err(err);
     }
 
     if created_stash {
-        restore_auto_stash(&mut repo, "Squash succeeded, but failed to restore stashed changes")?;
+        restore_auto_stash(&mut repo, "Reorder succeeded, but failed to restore stashed changes")?;
     }
 
     Ok(())
@@ -713,6 +1477,32 @@ pub fn commits_in_remote(repo_root: &Path, commit_ids: &[String]) -> Result<bool
     Ok(false)
 }
 
+/// Writes a snapshot of `rev` to `out_path` using `git archive`.
+pub fn archive(
+    cwd: &Path,
+    rev: &str,
+    format: ArchiveFormat,
+    out_path: &Path,
+) -> Result<(), GitError> {
+    let repo = open_repo(cwd)?;
+    // Resolve up front so a bad rev gets a git2 error instead of being parsed as an option.
+    let object = repo.revparse_single(rev)?;
+    object.peel_to_tree()?;
+    let object_id = object.id().to_string();
+    let format_arg = format!("--format={}", format.as_str());
+    run_git_command(
+        cwd,
+        [
+            OsStr::new("archive"),
+            OsStr::new(&format_arg),
+            OsStr::new("-o"),
+            out_path.as_os_str(),
+            OsStr::new(&object_id),
+        ],
+    )?;
+    Ok(())
+}
+
 fn signature_from_commit(commit: &git2::Commit<'_>) -> Result<git2::Signature<'static>, GitError> {
     let author = commit.author();
     let name = author.name().unwrap_or("unknown");
@@ -727,7 +1517,7 @@ fn resolve_commit_oid(repo: &Repository, commit_str: &str) -> Result<Oid, GitErr
     Ok(commit.id())
 }
 
-fn is_repo_dirty(repo: &Repository) -> Result<bool, GitError> {
+pub fn is_repo_dirty(repo: &Repository) -> Result<bool, GitError> {
     let mut opts = git2::StatusOptions::new();
     opts.show(git2::StatusShow::IndexAndWorkdir)
         .include_untracked(true)
@@ -769,7 +1559,7 @@ where
     let mut cmd = Command::new("git");
     cmd.args(args).current_dir(cwd);
 
-    let proxy_url = configure_proxy(&mut cmd);
+    let proxy_url = configure_proxy(&mut cmd, &GitNetworkOptions::default());
     let output = cmd.output().map_err(GitError::Io)?;
 
     if !output.status.success() {
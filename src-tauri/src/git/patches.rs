@@ -0,0 +1,109 @@
+use crate::git::error::GitError;
+use crate::git::status::open_repo;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Applies a unified diff to the working tree with `git apply`.
+///
+/// With `check_only` nothing is written; the call only reports whether the patch applies.
+/// Hunks that fail to apply are reported through git's stderr in the returned error.
+pub fn apply_patch(
+    cwd: &Path,
+    patch: &str,
+    check_only: bool,
+    three_way: bool,
+) -> Result<(), GitError> {
+    if patch.trim().is_empty() {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: "patch is empty".to_string(),
+        });
+    }
+
+    let workdir = repo_workdir(cwd)?;
+    let mut cmd = Command::new("git");
+    cmd.arg("apply").current_dir(&workdir);
+    if check_only {
+        cmd.arg("--check");
+    }
+    if three_way {
+        cmd.arg("--3way");
+    }
+    cmd.arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(GitError::Io)?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // git may exit before reading everything; its stderr explains why.
+        if let Err(err) = stdin.write_all(patch.as_bytes()) {
+            if err.kind() != std::io::ErrorKind::BrokenPipe {
+                return Err(GitError::Io(err));
+            }
+        }
+    }
+    let output = child.wait_with_output().map_err(GitError::Io)?;
+
+    if !output.status.success() {
+        return Err(GitError::GitFailed {
+            code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Writes one `.patch` file per commit in `range` to `out_dir` with `git format-patch`
+/// and returns the generated file paths in series order. A relative `out_dir` is taken
+/// relative to `cwd`, where git runs.
+pub fn format_patch(cwd: &Path, range: &str, out_dir: &Path) -> Result<Vec<String>, GitError> {
+    let repo = open_repo(cwd)?;
+    repo.revparse(range)?;
+    let out_dir = cwd.join(out_dir);
+    ensure_writable_dir(&out_dir)?;
+
+    let mut cmd = Command::new("git");
+    cmd.arg("format-patch")
+        .arg("-o")
+        .arg(&out_dir)
+        .arg(range)
+        .current_dir(cwd);
+    let output = cmd.output().map_err(GitError::Io)?;
+    if !output.status.success() {
+        return Err(GitError::GitFailed {
+            code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+fn ensure_writable_dir(dir: &Path) -> Result<(), GitError> {
+    fs::create_dir_all(dir).map_err(GitError::Io)?;
+    let probe = dir.join(".parallel-cli-runner-write-check");
+    fs::write(&probe, b"").map_err(|err| GitError::GitFailed {
+        code: None,
+        stderr: format!("output directory {} is not writable: {err}", dir.display()),
+    })?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+fn repo_workdir(cwd: &Path) -> Result<PathBuf, GitError> {
+    let repo = open_repo(cwd)?;
+    repo.workdir()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| GitError::GitFailed {
+            code: None,
+            stderr: "repository has no working directory".to_string(),
+        })
+}
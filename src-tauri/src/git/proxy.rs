@@ -1,66 +1,85 @@
+use crate::git::types::GitNetworkOptions;
 use std::process::Command;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// How long a detected system proxy is reused before probing the system again.
+const PROXY_CACHE_TTL: Duration = Duration::from_secs(30);
+
+struct CachedProxy {
+    detected_at: Instant,
+    proxy: Option<(String, String)>,
+}
+
+static PROXY_CACHE: Mutex<Option<CachedProxy>> = Mutex::new(None);
 
 #[cfg(target_os = "macos")]
-fn get_proxy_url() -> Option<(String, String)> {
-    if let Ok(output) = Command::new("scutil").arg("--proxy").output() {
-        let s = String::from_utf8_lossy(&output.stdout);
+fn detect_proxy_url() -> Option<(String, String)> {
+    let output = Command::new("scutil").arg("--proxy").output().ok()?;
+    parse_scutil_proxy(&String::from_utf8_lossy(&output.stdout))
+}
 
-        let mut http_enabled = false;
-        let mut http_host = String::new();
-        let mut http_port = String::new();
+/// Parses `scutil --proxy` output into a proxy URL and a comma-separated bypass list.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_scutil_proxy(s: &str) -> Option<(String, String)> {
+    let mut http_enabled = false;
+    let mut http_host = String::new();
+    let mut http_port = String::new();
 
-        let mut socks_enabled = false;
-        let mut socks_host = String::new();
-        let mut socks_port = String::new();
+    let mut socks_enabled = false;
+    let mut socks_host = String::new();
+    let mut socks_port = String::new();
 
-        let bypass = String::new();
+    let mut exceptions = Vec::new();
+    let mut in_exceptions = false;
 
-        for line in s.lines() {
-            let line = line.trim();
-            if let Some((key, value)) = line.split_once(':') {
-                let key = key.trim();
+    for line in s.lines() {
+        let line = line.trim();
+        if in_exceptions {
+            // Array entries are indented as `<index> : <host>` until the closing brace
+            if line == "}" {
+                in_exceptions = false;
+            } else if let Some((_, value)) = line.split_once(':') {
                 let value = value.trim();
-                match key {
-                    "HTTPEnable" => {
-                        if value == "1" {
-                            http_enabled = true;
-                        }
-                    }
-                    "HTTPProxy" => http_host = value.to_string(),
-                    "HTTPPort" => http_port = value.to_string(),
-                    "SOCKSEnable" => {
-                        if value == "1" {
-                            socks_enabled = true;
-                        }
-                    }
-                    "SOCKSProxy" => socks_host = value.to_string(),
-                    "SOCKSPort" => socks_port = value.to_string(),
-                    "ExceptionsList" => {
-                        // scutil output for list is complex, usually spans lines.
-                        // For simplicity, we might skip parsing complex bypass list from scutil
-                        // and rely on sysproxy if needed, or just ignore for now as the issue is CONNECT.
-                    }
-                    _ => {}
+                if !value.is_empty() {
+                    exceptions.push(value.to_string());
                 }
             }
+            continue;
         }
-
-        // Prefer HTTP
-        if http_enabled && !http_host.is_empty() && !http_port.is_empty() {
-            return Some((format!("http://{}:{}", http_host, http_port), bypass));
-        }
-        if socks_enabled && !socks_host.is_empty() && !socks_port.is_empty() {
-            return Some((
-                format!("socks5://{}:{}", socks_host, socks_port),
-                bypass,
-            ));
+        if let Some((key, value)) = line.split_once(':') {
+            let key = key.trim();
+            let value = value.trim();
+            match key {
+                "HTTPEnable" => http_enabled = value == "1",
+                "HTTPProxy" => http_host = value.to_string(),
+                "HTTPPort" => http_port = value.to_string(),
+                "SOCKSEnable" => socks_enabled = value == "1",
+                "SOCKSProxy" => socks_host = value.to_string(),
+                "SOCKSPort" => socks_port = value.to_string(),
+                "ExceptionsList" => in_exceptions = value.ends_with('{'),
+                _ => {}
+            }
         }
     }
+
+    let bypass = exceptions.join(",");
+
+    // Prefer HTTP
+    if http_enabled && !http_host.is_empty() && !http_port.is_empty() {
+        return Some((format!("http://{}:{}", http_host, http_port), bypass));
+    }
+    if socks_enabled && !socks_host.is_empty() && !socks_port.is_empty() {
+        return Some((
+            format!("socks5://{}:{}", socks_host, socks_port),
+            bypass,
+        ));
+    }
     None
 }
 
 #[cfg(not(target_os = "macos"))]
-fn get_proxy_url() -> Option<(String, String)> {
+fn detect_proxy_url() -> Option<(String, String)> {
     if let Ok(proxy) = sysproxy::Sysproxy::get_system_proxy() {
         if proxy.enable {
             let host = proxy.host;
@@ -76,13 +95,42 @@ fn get_proxy_url() -> Option<(String, String)> {
     None
 }
 
-pub fn configure_proxy(cmd: &mut Command) -> Option<String> {
+/// Returns the system proxy, probing at most once per `PROXY_CACHE_TTL`.
+fn get_proxy_url() -> Option<(String, String)> {
+    // Holding the lock while probing keeps concurrent commands from spawning duplicate probes
+    let mut cache = PROXY_CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(cached) = cache
+        .as_ref()
+        .filter(|cached| cached.detected_at.elapsed() < PROXY_CACHE_TTL)
+    {
+        return cached.proxy.clone();
+    }
+    let proxy = detect_proxy_url();
+    *cache = Some(CachedProxy {
+        detected_at: Instant::now(),
+        proxy: proxy.clone(),
+    });
+    proxy
+}
+
+/// Forces the next network command to re-detect the system proxy.
+pub fn invalidate_proxy_cache() {
+    *PROXY_CACHE.lock().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+pub fn configure_proxy(cmd: &mut Command, options: &GitNetworkOptions) -> Option<String> {
+    if let Some(proxy_url) = options.proxy.as_deref().filter(|url| !url.trim().is_empty()) {
+        set_proxy_env(cmd, proxy_url);
+        return Some(proxy_url.to_string());
+    }
+    if !options.use_system_proxy {
+        clear_proxy_env(cmd);
+        return None;
+    }
+
     let detected_proxy = get_proxy_url();
     if let Some((proxy_url, bypass)) = &detected_proxy {
-        cmd.env("http_proxy", proxy_url);
-        cmd.env("https_proxy", proxy_url);
-        cmd.env("HTTP_PROXY", proxy_url);
-        cmd.env("HTTPS_PROXY", proxy_url);
+        set_proxy_env(cmd, proxy_url);
 
         if !bypass.is_empty() {
             cmd.env("no_proxy", bypass);
@@ -92,3 +140,80 @@ pub fn configure_proxy(cmd: &mut Command) -> Option<String> {
     }
     None
 }
+
+fn set_proxy_env(cmd: &mut Command, proxy_url: &str) {
+    cmd.env("http_proxy", proxy_url);
+    cmd.env("https_proxy", proxy_url);
+    cmd.env("HTTP_PROXY", proxy_url);
+    cmd.env("HTTPS_PROXY", proxy_url);
+}
+
+/// Proxy variables git and curl read from the environment.
+const PROXY_ENV_VARS: [&str; 6] = [
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+];
+
+/// Keeps a proxy inherited from the app's environment from applying when the system proxy is off.
+fn clear_proxy_env(cmd: &mut Command) {
+    for var in PROXY_ENV_VARS {
+        cmd.env_remove(var);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_scutil_proxy_collects_exceptions() {
+        let output = "<dictionary> {
+  ExceptionsList : <array> {
+    0 : *.local
+    1 : 169.254/16
+    2 : fe80::/10
+  }
+  FTPPassive : 1
+  HTTPEnable : 1
+  HTTPPort : 8080
+  HTTPProxy : proxy.example.com
+}
+";
+        let (url, bypass) = parse_scutil_proxy(output).expect("proxy");
+        assert_eq!(url, "http://proxy.example.com:8080");
+        assert_eq!(bypass, "*.local,169.254/16,fe80::/10");
+    }
+
+    #[test]
+    fn parse_scutil_proxy_without_enabled_proxy() {
+        let output = "<dictionary> {
+  ExceptionsList : <array> {
+    0 : *.local
+  }
+  HTTPEnable : 0
+}
+";
+        assert!(parse_scutil_proxy(output).is_none());
+    }
+
+    #[test]
+    fn configure_proxy_clears_inherited_proxy_when_system_proxy_is_off() {
+        let mut cmd = Command::new("git");
+        let options = GitNetworkOptions {
+            proxy: None,
+            use_system_proxy: false,
+        };
+        assert!(configure_proxy(&mut cmd, &options).is_none());
+        let removed: Vec<_> = cmd
+            .get_envs()
+            .filter(|(_, value)| value.is_none())
+            .map(|(key, _)| key.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(removed.len(), PROXY_ENV_VARS.len());
+        assert!(PROXY_ENV_VARS.iter().all(|var| removed.iter().any(|key| key == var)));
+    }
+}
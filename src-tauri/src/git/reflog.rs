@@ -0,0 +1,47 @@
+use crate::git::error::GitError;
+use crate::git::operations::reset;
+use crate::git::status::{format_relative_time, open_repo};
+use crate::git::types::ReflogEntryDto;
+use std::path::Path;
+
+pub fn list_reflog(
+    cwd: &Path,
+    reference: Option<String>,
+    limit: usize,
+) -> Result<Vec<ReflogEntryDto>, GitError> {
+    let repo = open_repo(cwd)?;
+    let reference = reference.unwrap_or_else(|| "HEAD".to_string());
+    let reflog = repo.reflog(&reference)?;
+
+    let entries = reflog
+        .iter()
+        .take(limit)
+        .map(|entry| {
+            let committer = entry.committer();
+            ReflogEntryDto {
+                old_id: entry.id_old().to_string(),
+                new_id: entry.id_new().to_string(),
+                message: entry.message().unwrap_or_default().to_string(),
+                committer: committer.name().unwrap_or_default().to_string(),
+                relative_time: format_relative_time(committer.when()),
+            }
+        })
+        .collect();
+    Ok(entries)
+}
+
+pub fn restore_to_reflog(cwd: &Path, reference: &str, index: usize) -> Result<(), GitError> {
+    let repo = open_repo(cwd)?;
+    let reflog = repo.reflog(reference)?;
+    if index >= reflog.len() {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: format!(
+                "reflog index {index} is out of range for {reference} ({} entries)",
+                reflog.len()
+            ),
+        });
+    }
+
+    reset(cwd, &format!("{reference}@{{{index}}}"), "hard")
+}
@@ -1,7 +1,9 @@
+use crate::git::credentials::{remote_callbacks, CredentialProvider, GitCredential};
 use crate::git::error::GitError;
 use crate::git::proxy::configure_proxy;
 use crate::git::status::open_repo;
-use crate::git::types::RemoteInfoDto;
+use crate::git::types::{GitNetworkOptions, GitPushOptions, RemoteInfoDto};
+use git2::{ErrorCode, FetchOptions, ProxyOptions};
 use std::path::Path;
 use std::process::Command;
 
@@ -22,40 +24,223 @@ pub fn list_remotes(cwd: &Path) -> Result<Vec<RemoteInfoDto>, GitError> {
     Ok(remotes)
 }
 
-pub fn pull(cwd: &Path) -> Result<(), GitError> {
-    let _ = run_git_command(cwd, ["pull"])?;
+/// Rewrites the fetch URL of an existing remote, or its push URL when `push` is set.
+pub fn set_remote_url(cwd: &Path, name: &str, url: &str, push: bool) -> Result<(), GitError> {
+    require_non_empty("remote url", url)?;
+    let repo = open_repo(cwd)?;
+    // remote_set_url happily writes config for unknown names; make a typo an error instead.
+    repo.find_remote(name)?;
+    if push {
+        repo.remote_set_pushurl(name, Some(url))?;
+    } else {
+        repo.remote_set_url(name, url)?;
+    }
+    Ok(())
+}
+
+pub fn add_remote(cwd: &Path, name: &str, url: &str) -> Result<(), GitError> {
+    require_non_empty("remote name", name)?;
+    require_non_empty("remote url", url)?;
+    let repo = open_repo(cwd)?;
+    repo.remote(name, url)?;
     Ok(())
 }
 
-pub fn push(cwd: &Path, force: bool) -> Result<(), GitError> {
-    let mut args = vec!["push"];
-    if force {
-        args.push("--force");
+pub fn remove_remote(cwd: &Path, name: &str) -> Result<(), GitError> {
+    let repo = open_repo(cwd)?;
+    repo.remote_delete(name)?;
+    Ok(())
+}
+
+fn require_non_empty(label: &str, value: &str) -> Result<(), GitError> {
+    if value.trim().is_empty() {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: format!("{label} cannot be empty"),
+        });
     }
-    let _ = run_git_command(cwd, args)?;
     Ok(())
 }
 
-fn run_git_command<I, S>(cwd: &Path, args: I) -> Result<std::process::Output, GitError>
+/// Env vars carrying credentials to the temporary credential helper, so secrets never
+/// appear on the git command line.
+const CREDENTIAL_USERNAME_ENV: &str = "PARALLEL_CLI_RUNNER_GIT_USERNAME";
+const CREDENTIAL_PASSWORD_ENV: &str = "PARALLEL_CLI_RUNNER_GIT_PASSWORD";
+
+/// Fetches `remote_name` through libgit2 rather than the git CLI. Authentication goes
+/// through `remote_callbacks`, with `credentials` asked for a username and password last.
+pub fn fetch(
+    cwd: &Path,
+    remote_name: &str,
+    network: &GitNetworkOptions,
+    credentials: &dyn CredentialProvider,
+) -> Result<(), GitError> {
+    let repo = open_repo(cwd)?;
+    let mut remote = repo.find_remote(remote_name)?;
+
+    let mut proxy = ProxyOptions::new();
+    if let Some(url) = network.proxy.as_deref() {
+        proxy.url(url);
+    } else if network.use_system_proxy {
+        proxy.auto();
+    }
+    let mut options = FetchOptions::new();
+    options
+        .remote_callbacks(remote_callbacks(credentials))
+        .proxy_options(proxy);
+
+    // An empty refspec list uses the remote's configured fetch refspecs.
+    match remote.fetch::<&str>(&[], Some(&mut options), None) {
+        Err(err) if err.code() == ErrorCode::Auth => {
+            credentials.rejected(remote.url().unwrap_or_default());
+            Err(GitError::Git2(err))
+        }
+        result => Ok(result?),
+    }
+}
+
+pub fn pull(
+    cwd: &Path,
+    network: &GitNetworkOptions,
+    credentials: &dyn CredentialProvider,
+) -> Result<(), GitError> {
+    let _ = run_network_command(cwd, &["pull"], network, credentials)?;
+    Ok(())
+}
+
+/// Pushes according to `options`. `force_with_lease` wins over `force` when both are set.
+/// Without a remote, git pushes the current branch to its configured upstream, so naming a
+/// branch then is an error.
+pub fn push(
+    cwd: &Path,
+    options: &GitPushOptions,
+    network: &GitNetworkOptions,
+    credentials: &dyn CredentialProvider,
+) -> Result<(), GitError> {
+    let args = push_args(cwd, options)?;
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let _ = run_network_command(cwd, &args, network, credentials)?;
+    Ok(())
+}
+
+fn push_args(cwd: &Path, options: &GitPushOptions) -> Result<Vec<String>, GitError> {
+    let mut args = vec!["push".to_string()];
+    if options.force_with_lease {
+        args.push("--force-with-lease".to_string());
+    } else if options.force {
+        args.push("--force".to_string());
+    }
+    if options.set_upstream {
+        args.push("--set-upstream".to_string());
+    }
+
+    let Some(remote) = options.remote.as_deref() else {
+        if options.local_branch.is_some() || options.remote_branch.is_some() {
+            return Err(GitError::GitFailed {
+                code: None,
+                stderr: "a remote is required to push a specific branch".to_string(),
+            });
+        }
+        return Ok(args);
+    };
+    require_non_empty("remote name", remote)?;
+    // Rejects typos, and names that git would parse as an option.
+    open_repo(cwd)?.find_remote(remote)?;
+    args.push(remote.to_string());
+
+    let local = options.local_branch.as_deref().map(str::trim);
+    let Some(destination) = options.remote_branch.as_deref().map(str::trim).or(local) else {
+        return Ok(args);
+    };
+    require_non_empty("branch name", destination)?;
+    let source = match local {
+        Some(branch) => {
+            require_non_empty("local branch", branch)?;
+            format!("refs/heads/{branch}")
+        }
+        None => "HEAD".to_string(),
+    };
+    args.push(format!("{source}:refs/heads/{destination}"));
+    Ok(args)
+}
+
+/// Runs a git command that talks to a remote. Terminal prompts are disabled, so when
+/// git needs HTTPS credentials it fails fast; the command is then retried once with
+/// credentials from `credentials`, served through a temporary credential helper.
+fn run_network_command(
+    cwd: &Path,
+    args: &[&str],
+    network: &GitNetworkOptions,
+    credentials: &dyn CredentialProvider,
+) -> Result<std::process::Output, GitError> {
+    let (code, stderr) = match run_git_command(cwd, args, network, None) {
+        Err(GitError::GitFailed { code, stderr }) => (code, stderr),
+        result => return result,
+    };
+    let retry = auth_failure_url(&stderr)
+        .and_then(|url| credentials.credentials(&url, None).map(|credential| (url, credential)));
+    let Some((url, credential)) = retry else {
+        return Err(GitError::GitFailed { code, stderr });
+    };
+
+    let result = run_git_command(cwd, args, network, Some(&credential));
+    if let Err(GitError::GitFailed { stderr, .. }) = &result {
+        if auth_failure_url(stderr).is_some() {
+            credentials.rejected(&url);
+        }
+    }
+    result
+}
+
+/// Returns the remote URL from git's "could not read Username" / "Authentication
+/// failed" errors, which is how the CLI reports that it needed credentials.
+fn auth_failure_url(stderr: &str) -> Option<String> {
+    const MARKERS: &[&str] = &["could not read Username for '", "Authentication failed for '"];
+    MARKERS.iter().find_map(|marker| {
+        let start = stderr.find(marker)? + marker.len();
+        let end = stderr[start..].find('\'')?;
+        Some(stderr[start..start + end].to_string())
+    })
+}
+
+fn run_git_command<I, S>(
+    cwd: &Path,
+    args: I,
+    network: &GitNetworkOptions,
+    credential: Option<&GitCredential>,
+) -> Result<std::process::Output, GitError>
 where
     I: IntoIterator<Item = S>,
     S: AsRef<std::ffi::OsStr>,
 {
     let mut cmd = Command::new("git");
-    cmd.args(args).current_dir(cwd);
+    cmd.current_dir(cwd).env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(credential) = credential {
+        // Clear inherited helpers, then answer `get` from the env vars set below.
+        let helper = format!(
+            "credential.helper=!f() {{ test \"$1\" = get && \
+             echo username=\"${CREDENTIAL_USERNAME_ENV}\" && \
+             echo password=\"${CREDENTIAL_PASSWORD_ENV}\"; }}; f"
+        );
+        cmd.args(["-c", "credential.helper=", "-c", &helper])
+            .env(CREDENTIAL_USERNAME_ENV, &credential.username)
+            .env(CREDENTIAL_PASSWORD_ENV, &credential.password);
+    }
+    cmd.args(args);
 
-    let proxy_url = configure_proxy(&mut cmd);
+    let proxy_url = configure_proxy(&mut cmd, network);
     let output = cmd.output().map_err(GitError::Io)?;
 
     if !output.status.success() {
         let mut stderr = String::from_utf8_lossy(&output.stderr).to_string();
         if let Some(url) = proxy_url {
             use std::fmt::Write;
-            let _ = write!(
-                stderr,
-                "\n[parallel-cli-runner] System proxy detected and used: {}",
-                url
-            );
+            let source = if network.proxy.is_some() {
+                "Configured proxy used"
+            } else {
+                "System proxy detected and used"
+            };
+            let _ = write!(stderr, "\n[parallel-cli-runner] {}: {}", source, url);
         }
 
         return Err(GitError::GitFailed {
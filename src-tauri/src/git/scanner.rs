@@ -1,10 +1,33 @@
 use crate::git::error::GitError;
-use crate::git::types::RepoInfoDto;
+use crate::git::types::{RepoInfoDto, ScanProgress};
 use git2::ErrorCode;
 use git2::Repository;
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Tracks the cancellation flag of the scan currently running for one client, so a later
+/// request can stop it.
+#[derive(Clone, Default)]
+pub struct ActiveScan(Arc<Mutex<Option<Arc<AtomicBool>>>>);
+
+impl ActiveScan {
+    /// Starts tracking a new scan and returns the flag to pass to [`scan_repos`].
+    pub fn begin(&self) -> Arc<AtomicBool> {
+        let cancel = Arc::new(AtomicBool::new(false));
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(cancel.clone());
+        cancel
+    }
+
+    /// Asks the tracked scan to stop. Does nothing when no scan has been started.
+    pub fn cancel(&self) {
+        if let Some(cancel) = self.0.lock().unwrap_or_else(PoisonError::into_inner).as_ref() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+    }
+}
 
 pub fn detect_repo(cwd: &Path) -> Result<Option<PathBuf>, GitError> {
     match Repository::discover(cwd) {
@@ -51,25 +74,50 @@ fn register_repo(
     }
 }
 
-pub fn scan_repos<F>(root: &Path, progress_cb: F) -> Result<Vec<RepoInfoDto>, GitError>
+/// Finds repositories under `root`, plus the repository containing it.
+///
+/// Symlinked directories are skipped unless `follow_symlinks` is set, in which case each
+/// directory is visited once by its canonical path so link cycles end the walk.
+///
+/// `cancel` is checked between directories; once it is set the walk stops and only the
+/// repositories found so far are returned.
+pub fn scan_repos<F>(
+    root: &Path,
+    follow_symlinks: bool,
+    cancel: &AtomicBool,
+    progress_cb: F,
+) -> Result<Vec<RepoInfoDto>, GitError>
 where
-    F: Fn(String),
+    F: Fn(ScanProgress),
 {
+    let mut dirs_scanned = 0;
     let mut seen = HashSet::new();
     let mut scanned_entries = Vec::new();
     let mut pending = Vec::new();
     let mut queued = HashSet::new();
+    let mut visited = HashSet::new();
 
     if queued.insert(root.to_path_buf()) {
         pending.push(root.to_path_buf());
     }
 
-    if let Ok(repo) = Repository::discover(root) {
+    let root_repo = if looks_like_git_dir(root) {
+        open_git_dir(root)
+    } else {
+        Repository::discover(root)
+    };
+    if let Ok(repo) = root_repo {
         register_repo(&repo, &mut scanned_entries, &mut seen, &mut pending, &mut queued);
     }
 
     while let Some(dir) = pending.pop() {
-        progress_cb(dir.to_string_lossy().to_string());
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        if follow_symlinks && !visited.insert(canonicalize_path(&dir)) {
+            continue;
+        }
+        dirs_scanned += 1;
         let entries = match fs::read_dir(&dir) {
             Ok(entries) => entries,
             Err(_) => continue,
@@ -88,36 +136,45 @@ where
                 );
                 is_repo_dir = true;
             }
-        } else {
-            let head = dir.join("HEAD");
-            let objects = dir.join("objects");
-            if head.is_file() && objects.is_dir() {
-                if let Ok(repo) = Repository::open(&dir) {
-                    register_repo(
-                        &repo,
-                        &mut scanned_entries,
-                        &mut seen,
-                        &mut pending,
-                        &mut queued,
-                    );
-                    is_repo_dir = true;
-                }
+        } else if looks_like_git_dir(&dir) {
+            if let Ok(repo) = open_git_dir(&dir) {
+                register_repo(
+                    &repo,
+                    &mut scanned_entries,
+                    &mut seen,
+                    &mut pending,
+                    &mut queued,
+                );
+                is_repo_dir = true;
             }
         }
 
+        // Reported after detection so a repo directory is already counted in `repos_found`.
+        progress_cb(ScanProgress {
+            current_path: dir.to_string_lossy().to_string(),
+            dirs_scanned,
+            repos_found: scanned_entries.len(),
+        });
+
         if is_repo_dir {
             continue;
         }
 
         for entry in entries.flatten() {
             let path = entry.path();
-            let metadata = match entry.metadata() {
+            let mut metadata = match entry.metadata() {
                 Ok(metadata) => metadata,
                 Err(_) => continue,
             };
 
             if metadata.file_type().is_symlink() {
-                continue;
+                if !follow_symlinks {
+                    continue;
+                }
+                metadata = match fs::metadata(&path) {
+                    Ok(metadata) => metadata,
+                    Err(_) => continue,
+                };
             }
             if !metadata.is_dir() {
                 continue;
@@ -134,7 +191,8 @@ where
 
     let mut repos = Vec::new();
     for (info, git_path) in &scanned_entries {
-        let is_worktree = scanned_entries.iter().any(|(_, other_git_path)| {
+        // Linked worktrees always have a working directory, so a bare repo is never one.
+        let is_worktree = !info.is_bare && scanned_entries.iter().any(|(_, other_git_path)| {
             if git_path == other_git_path {
                 return false;
             }
@@ -157,6 +215,58 @@ where
     Ok(repos)
 }
 
+/// Directories [`has_any_repo`] inspects when the caller doesn't pick a limit.
+pub const DEFAULT_REPO_PROBE_CHECKS: usize = 1000;
+
+/// Cheaply checks whether any repository lives under `root`, before a full
+/// [`scan_repos`]. Directories are visited breadth first, skipping symlinks, and the probe
+/// gives up with `false` after inspecting `max_checks` of them.
+pub fn has_any_repo(root: &Path, max_checks: usize) -> Result<bool, GitError> {
+    if !root.is_dir() {
+        return Err(GitError::InvalidPath(root.to_string_lossy().to_string()));
+    }
+
+    let mut pending = VecDeque::from([root.to_path_buf()]);
+    let mut checks = 0;
+    while let Some(dir) = pending.pop_front() {
+        if checks == max_checks {
+            break;
+        }
+        checks += 1;
+        if fs::symlink_metadata(dir.join(".git")).is_ok() || looks_like_git_dir(&dir) {
+            return Ok(true);
+        }
+
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                pending.push_back(entry.path());
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Mirrors git's own check for a git dir: a `HEAD` file plus `objects` and `refs`.
+fn looks_like_git_dir(dir: &Path) -> bool {
+    dir.join("HEAD").is_file() && dir.join("objects").is_dir() && dir.join("refs").is_dir()
+}
+
+/// Opens a directory that is itself a git dir. A `*.git` directory is treated as bare
+/// unless its config names a worktree, even when `core.bare` is unset or false (as in a
+/// `.git` directory copied out of a clone); otherwise libgit2 would report its parent
+/// directory as the working tree.
+fn open_git_dir(dir: &Path) -> Result<Repository, git2::Error> {
+    let repo = Repository::open(dir)?;
+    let named_bare = dir.extension().is_some_and(|ext| ext == "git");
+    if repo.is_bare() || !named_bare || repo.config()?.get_path("core.worktree").is_ok() {
+        return Ok(repo);
+    }
+    Repository::open_bare(dir)
+}
+
 pub fn canonicalize_path(path: &Path) -> PathBuf {
     fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
 }
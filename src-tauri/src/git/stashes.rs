@@ -53,6 +53,70 @@ pub fn drop_stash(cwd: &Path, index: i32) -> Result<(), GitError> {
     Ok(())
 }
 
+pub fn rename_stash(cwd: &Path, index: i32, message: &str) -> Result<(), GitError> {
+    if index < 0 {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: "stash index must be >= 0".to_string(),
+        });
+    }
+    let index = index as usize;
+    let repo = open_repo(cwd)?;
+    let mut reflog = repo.reflog("refs/stash")?;
+    let Some((stash_oid, committer)) = reflog
+        .get(index)
+        .map(|entry| (entry.id_new(), entry.committer().to_owned()))
+    else {
+        return Err(GitError::GitFailed {
+            code: None,
+            stderr: format!("stash@{{{index}}} does not exist"),
+        });
+    };
+
+    // Build the replacement commit before touching the stash so a failure leaves it intact
+    let stash_commit = repo.find_commit(stash_oid)?;
+    let parents = stash_commit.parents().collect::<Vec<_>>();
+    let parent_refs = parents.iter().collect::<Vec<_>>();
+    let new_oid = repo.commit(
+        None,
+        &stash_commit.author(),
+        &stash_commit.committer(),
+        message,
+        &stash_commit.tree()?,
+        &parent_refs,
+    )?;
+
+    if index == 0 {
+        // Moving refs/stash logs the new entry on top; the old one then sits at index 1
+        repo.find_reference("refs/stash")?.set_target(new_oid, message)?;
+        let mut reflog = repo.reflog("refs/stash")?;
+        reflog.remove(1, true)?;
+        reflog.write()?;
+        return Ok(());
+    }
+
+    // Reflog entries can only be appended, so pop the newer ones and replay them afterwards
+    let newer = (0..index)
+        .filter_map(|i| reflog.get(i))
+        .map(|entry| {
+            (
+                entry.id_new(),
+                entry.committer().to_owned(),
+                entry.message().map(str::to_string),
+            )
+        })
+        .collect::<Vec<_>>();
+    for _ in 0..=index {
+        reflog.remove(0, false)?;
+    }
+    reflog.append(new_oid, &committer, Some(message))?;
+    for (oid, sig, msg) in newer.iter().rev() {
+        reflog.append(*oid, sig, msg.as_deref())?;
+    }
+    reflog.write()?;
+    Ok(())
+}
+
 pub fn stash_save(
     cwd: &Path,
     message: Option<String>,
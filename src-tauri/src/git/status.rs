@@ -1,7 +1,17 @@
+use crate::git::branches::default_branch;
+use crate::git::diff::index_conflicts;
 use crate::git::error::GitError;
+use crate::git::operations::is_repo_dirty;
+use crate::git::remotes::list_remotes;
 use crate::git::scanner::canonicalize_path;
-use crate::git::types::{CommitInfoDto, FileChangeType, FileStats, FileStatusDto, RepoStatusDto, SubmoduleInfoDto};
-use git2::{Diff, DiffOptions, ErrorCode, IndexAddOption, Repository, Status, StatusOptions, StatusShow};
+use crate::git::types::{
+    CommitInfoDto, ConflictSide, FileChangeType, FileStats, FileStatusDto, RepoOperationState,
+    RepoOverviewDto, RepoStatusDto, SubmoduleInfoDto,
+};
+use git2::{
+    BranchType, Diff, DiffOptions, ErrorCode, IndexAddOption, Repository, RepositoryState, Status,
+    StatusOptions, StatusShow,
+};
 use std::io::BufRead;
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -9,7 +19,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 pub fn status(cwd: &std::path::Path) -> Result<RepoStatusDto, GitError> {
     let repo = open_repo(cwd)?;
     let repo_root = repo_root_path(&repo);
-    let (branch, ahead, behind) = branch_status(&repo)?;
+    let (branch, ahead, behind, head_commit) = branch_status(&repo)?;
 
     let mut opts = StatusOptions::new();
     opts.show(StatusShow::IndexAndWorkdir)
@@ -92,6 +102,8 @@ pub fn status(cwd: &std::path::Path) -> Result<RepoStatusDto, GitError> {
         repo_id: repo_root.to_string_lossy().to_string(),
         root_path: repo_root.to_string_lossy().to_string(),
         branch,
+        detached: head_commit.is_some(),
+        head_commit,
         ahead,
         behind,
         has_untracked,
@@ -103,6 +115,44 @@ pub fn status(cwd: &std::path::Path) -> Result<RepoStatusDto, GitError> {
     })
 }
 
+/// Bundles status, the default branch, the local branch count and the remotes, so opening
+/// a repository takes one request instead of several.
+pub fn repo_overview(cwd: &Path) -> Result<RepoOverviewDto, GitError> {
+    let status = status(cwd)?;
+    let branch_count = open_repo(cwd)?.branches(Some(BranchType::Local))?.count();
+    Ok(RepoOverviewDto {
+        status,
+        default_branch: default_branch(cwd)?,
+        branch_count,
+        remotes: list_remotes(cwd)?,
+    })
+}
+
+pub fn is_clean(cwd: &Path) -> Result<bool, GitError> {
+    let repo = open_repo(cwd)?;
+    Ok(!is_repo_dirty(&repo)?)
+}
+
+/// Reports which merge, rebase, cherry-pick or revert (if any) is waiting to be continued or
+/// aborted.
+pub fn operation_state(cwd: &Path) -> Result<RepoOperationState, GitError> {
+    let repo = open_repo(cwd)?;
+    Ok(match repo.state() {
+        RepositoryState::Merge => RepoOperationState::Merge,
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge
+        | RepositoryState::ApplyMailboxOrRebase => RepoOperationState::Rebase,
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+            RepoOperationState::CherryPick
+        }
+        RepositoryState::Revert | RepositoryState::RevertSequence => RepoOperationState::Revert,
+        RepositoryState::Clean | RepositoryState::Bisect | RepositoryState::ApplyMailbox => {
+            RepoOperationState::None
+        }
+    })
+}
+
 pub fn diff(cwd: &std::path::Path, pathspecs: &[String]) -> Result<String, GitError> {
     let repo = open_repo(cwd)?;
     let mut opts = DiffOptions::new();
@@ -244,6 +294,48 @@ pub fn discard_paths(cwd: &std::path::Path, paths: &[String]) -> Result<(), GitE
     Ok(())
 }
 
+/// Resolves a conflicted path by taking one side of the conflict and marking it resolved.
+///
+/// If the chosen side deleted the file, the file is removed from the worktree and index.
+pub fn resolve_conflict(cwd: &Path, path: &str, side: ConflictSide) -> Result<(), GitError> {
+    let repo = open_repo(cwd)?;
+    let mut index = repo.index()?;
+    let (_, conflict) = index_conflicts(&index)?
+        .into_iter()
+        .find(|(conflict_path, _)| conflict_path == path)
+        .ok_or_else(|| GitError::GitFailed {
+            code: None,
+            stderr: format!("{path} is not conflicted"),
+        })?;
+    let chosen = match side {
+        ConflictSide::Ours => conflict.our,
+        ConflictSide::Theirs => conflict.their,
+    };
+
+    if chosen.is_some() {
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout.force().path(path);
+        match side {
+            ConflictSide::Ours => checkout.use_ours(true),
+            ConflictSide::Theirs => checkout.use_theirs(true),
+        };
+        repo.checkout_index(Some(&mut index), Some(&mut checkout))?;
+        index.add_path(Path::new(path))?;
+    } else {
+        let workdir = repo.workdir().ok_or_else(|| GitError::GitFailed {
+            code: None,
+            stderr: "cannot resolve conflicts in bare repo".to_string(),
+        })?;
+        let full_path = workdir.join(path);
+        if full_path.exists() {
+            std::fs::remove_file(&full_path)?;
+        }
+        index.remove_path(Path::new(path))?;
+    }
+    index.write()?;
+    Ok(())
+}
+
 pub fn stage_all(cwd: &std::path::Path) -> Result<(), GitError> {
     let repo = open_repo(cwd)?;
     let mut index = repo.index()?;
@@ -276,18 +368,27 @@ fn repo_root_path(repo: &Repository) -> std::path::PathBuf {
     }
 }
 
-fn branch_status(repo: &Repository) -> Result<(String, i32, i32), GitError> {
+/// Returns the branch name with its ahead/behind counts. On a detached `HEAD` the name is
+/// the abbreviated commit id and the full id comes back as the fourth element.
+fn branch_status(repo: &Repository) -> Result<(String, i32, i32, Option<String>), GitError> {
     let head = match repo.head() {
         Ok(head) => head,
         Err(err) if err.code() == ErrorCode::UnbornBranch => {
-            return Ok(("HEAD".to_string(), 0, 0));
+            return Ok(("HEAD".to_string(), 0, 0, None));
         }
         Err(err) if err.code() == ErrorCode::NotFound => {
-            return Ok(("HEAD".to_string(), 0, 0));
+            return Ok(("HEAD".to_string(), 0, 0, None));
         }
         Err(err) => return Err(GitError::Git2(err)),
     };
 
+    if repo.head_detached()? {
+        let commit = head.peel_to_commit()?;
+        let short_id = commit.as_object().short_id()?;
+        let short_id = short_id.as_str().unwrap_or("HEAD").to_string();
+        return Ok((short_id, 0, 0, Some(commit.id().to_string())));
+    }
+
     let branch = head.shorthand().unwrap_or("HEAD").to_string();
     let mut ahead = 0i32;
     let mut behind = 0i32;
@@ -310,7 +411,7 @@ fn branch_status(repo: &Repository) -> Result<(String, i32, i32), GitError> {
         }
     }
 
-    Ok((branch, ahead, behind))
+    Ok((branch, ahead, behind, None))
 }
 
 fn map_index_status(status: Status) -> Option<FileChangeType> {
@@ -399,7 +500,7 @@ fn staged_paths(cwd: &std::path::Path) -> Result<Vec<String>, GitError> {
     Ok(paths)
 }
 
-fn format_relative_time(time: git2::Time) -> String {
+pub fn format_relative_time(time: git2::Time) -> String {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
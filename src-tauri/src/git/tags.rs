@@ -1,6 +1,7 @@
 use crate::git::error::GitError;
-use crate::git::status::open_repo;
+use crate::git::status::{format_relative_time, open_repo};
 use crate::git::types::TagInfoDto;
+use git2::{ObjectType, Repository};
 use std::path::Path;
 
 pub fn list_tags(
@@ -16,11 +17,35 @@ pub fn list_tags(
     }
     tag_names.sort();
     let skip = skip.unwrap_or(0);
-    let tags = tag_names
+    tag_names
         .into_iter()
         .skip(skip)
         .take(limit)
-        .map(|name| TagInfoDto { name })
-        .collect();
-    Ok(tags)
+        .map(|name| tag_info(&repo, name))
+        .collect()
+}
+
+fn tag_info(repo: &Repository, name: String) -> Result<TagInfoDto, GitError> {
+    let object = repo.revparse_single(&format!("refs/tags/{name}"))?;
+    let target = object.peel(ObjectType::Commit).unwrap_or_else(|_| object.clone());
+    let commit_time = target.as_commit().map(|commit| commit.time());
+
+    let (message, tagger, time) = match object.as_tag() {
+        Some(tag) => {
+            let tagger = tag.tagger();
+            let time = tagger.as_ref().map(|sig| sig.when()).or(commit_time);
+            let name = tagger.as_ref().and_then(|sig| sig.name().map(str::to_string));
+            (tag.message().map(|msg| msg.trim_end().to_string()), name, time)
+        }
+        None => (None, None, commit_time),
+    };
+
+    Ok(TagInfoDto {
+        name,
+        target_commit: target.id().to_string(),
+        is_annotated: object.kind() == Some(ObjectType::Tag),
+        message,
+        tagger,
+        relative_time: time.map(format_relative_time).unwrap_or_default(),
+    })
 }
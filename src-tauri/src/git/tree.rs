@@ -0,0 +1,109 @@
+use crate::git::error::GitError;
+use crate::git::status::open_repo;
+use crate::git::types::{ObjectDto, ObjectKind, TreeEntryDto};
+use git2::{ObjectType, Oid};
+use std::path::Path;
+
+/// Lists the entries directly under `path` in the tree of `rev`, directories first and
+/// then by name. An empty `path` lists the root of the tree.
+pub fn list_tree(cwd: &Path, rev: &str, path: &str) -> Result<Vec<TreeEntryDto>, GitError> {
+    let repo = open_repo(cwd)?;
+    let root = repo.revparse_single(rev)?.peel_to_tree()?;
+    let path = path.trim_matches('/');
+    let tree = if path.is_empty() {
+        root
+    } else {
+        let entry = root.get_path(Path::new(path)).map_err(|_| GitError::GitFailed {
+            code: None,
+            stderr: format!("{path} does not exist at {rev}"),
+        })?;
+        entry
+            .to_object(&repo)?
+            .into_tree()
+            .map_err(|_| GitError::GitFailed {
+                code: None,
+                stderr: format!("{path} is not a directory at {rev}"),
+            })?
+    };
+
+    let odb = repo.odb()?;
+    let mut entries = Vec::with_capacity(tree.len());
+    for entry in tree.iter() {
+        let is_dir = entry.kind() == Some(ObjectType::Tree);
+        // Submodule entries point at commits in another repository, so they have no size here
+        let size = match entry.kind() {
+            Some(ObjectType::Blob) => Some(odb.read_header(entry.id())?.0 as u64),
+            _ => None,
+        };
+        entries.push(TreeEntryDto {
+            name: String::from_utf8_lossy(entry.name_bytes()).to_string(),
+            is_dir,
+            size,
+            object_id: entry.id().to_string(),
+        });
+    }
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
+
+/// Reads an object straight from the object database. `oid` may be abbreviated.
+///
+/// Commits and tags come back as their raw text, trees as one `<mode> <kind> <oid>\t<name>`
+/// line per entry, and blobs as their content unless they look binary.
+pub fn read_object(cwd: &Path, oid: &str) -> Result<ObjectDto, GitError> {
+    let repo = open_repo(cwd)?;
+    let oid = oid.trim();
+    let invalid = || GitError::GitFailed {
+        code: None,
+        stderr: format!("{oid} is not a valid object id"),
+    };
+    if oid.is_empty() || oid.len() > 40 || !oid.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let odb = repo.odb()?;
+    let id = Oid::from_str(oid).map_err(|_| invalid())?;
+    let id = if oid.len() < 40 { odb.exists_prefix(id, oid.len())? } else { id };
+    let object = odb.read(id)?;
+    let data = object.data();
+    let text = |data: &[u8]| String::from_utf8_lossy(data).to_string();
+
+    let (kind, content, is_binary) = match object.kind() {
+        ObjectType::Blob => {
+            // Same heuristic as git: a NUL in the first 8000 bytes means binary
+            let looks_binary = data.iter().take(8000).any(|&byte| byte == 0);
+            match std::str::from_utf8(data) {
+                Ok(text) if !looks_binary => (ObjectKind::Blob, Some(text.to_string()), false),
+                _ => (ObjectKind::Blob, None, true),
+            }
+        }
+        ObjectType::Tree => {
+            let tree = repo.find_tree(id)?;
+            let mut lines = String::new();
+            for entry in tree.iter() {
+                let kind = entry.kind().map_or("unknown", |kind| kind.str());
+                lines.push_str(&format!(
+                    "{:06o} {kind} {}\t{}\n",
+                    entry.filemode(),
+                    entry.id(),
+                    String::from_utf8_lossy(entry.name_bytes())
+                ));
+            }
+            (ObjectKind::Tree, Some(lines), false)
+        }
+        ObjectType::Commit => (ObjectKind::Commit, Some(text(data)), false),
+        ObjectType::Tag => (ObjectKind::Tag, Some(text(data)), false),
+        _ => {
+            return Err(GitError::GitFailed {
+                code: None,
+                stderr: format!("{id} has an unsupported object type"),
+            })
+        }
+    };
+
+    Ok(ObjectDto {
+        kind,
+        size: object.len() as u64,
+        content,
+        is_binary,
+    })
+}
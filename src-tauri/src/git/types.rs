@@ -11,6 +11,56 @@ pub enum FileChangeType {
     Unmerged,
 }
 
+/// Multi-step operation a repository is in the middle of, e.g. after a conflicting merge.
+#[derive(Clone, Copy, Debug, Serialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoOperationState {
+    None,
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+/// Which side of a conflicted path to keep.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictSide {
+    Ours,
+    Theirs,
+}
+
+/// Output formats supported by `git archive`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+}
+
+impl ArchiveFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::Tar => "tar",
+        }
+    }
+}
+
+impl std::str::FromStr for ArchiveFormat {
+    type Err = crate::git::GitError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "zip" => Ok(ArchiveFormat::Zip),
+            "tar" => Ok(ArchiveFormat::Tar),
+            other => Err(crate::git::GitError::GitFailed {
+                code: None,
+                stderr: format!("unsupported archive format '{other}' (expected zip or tar)"),
+            }),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, TS)]
 pub struct FileStats {
     pub insertions: i32,
@@ -34,11 +84,40 @@ pub struct CommitInfoDto {
     pub relative_time: String,
 }
 
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct CommitPageDto {
+    pub commits: Vec<CommitInfoDto>,
+    pub has_more: bool,
+}
+
+/// Outcome of merging `source` into `target`, worked out without touching the worktree.
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct MergePreviewDto {
+    /// `target` already contains `source`, so there is nothing to merge.
+    pub up_to_date: bool,
+    /// `target` can simply move forward to `source`.
+    pub fast_forward: bool,
+    pub has_conflicts: bool,
+    pub conflicted_paths: Vec<String>,
+}
+
+/// Commits by one author, as in `git shortlog -se`.
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct AuthorStatDto {
+    pub name: String,
+    pub email: String,
+    pub commit_count: usize,
+}
+
 #[derive(Clone, Debug, Serialize, TS)]
 pub struct RepoStatusDto {
     pub repo_id: String,
     pub root_path: String,
+    /// The checked-out branch, or the abbreviated commit id when `HEAD` is detached.
     pub branch: String,
+    pub detached: bool,
+    /// Full id of the checked-out commit when `HEAD` is detached.
+    pub head_commit: Option<String>,
     pub ahead: i32,
     pub behind: i32,
     pub has_untracked: bool,
@@ -49,6 +128,17 @@ pub struct RepoStatusDto {
     pub latest_commit: Option<CommitInfoDto>,
 }
 
+/// What the UI shows when a repository is first opened, gathered in one call.
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct RepoOverviewDto {
+    /// Also carries the current branch, its ahead/behind counts and the latest commit.
+    pub status: RepoStatusDto,
+    pub default_branch: String,
+    /// Number of local branches.
+    pub branch_count: usize,
+    pub remotes: Vec<RemoteInfoDto>,
+}
+
 #[derive(Clone, Debug, Serialize, TS)]
 pub struct RepoInfoDto {
     pub repo_id: String,
@@ -57,6 +147,14 @@ pub struct RepoInfoDto {
     pub is_bare: bool,
 }
 
+/// Payload of the `scan-progress` event emitted as `scan_repos` visits each directory.
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct ScanProgress {
+    pub current_path: String,
+    pub dirs_scanned: usize,
+    pub repos_found: usize,
+}
+
 #[derive(Clone, Debug, Serialize, TS)]
 pub struct DiffStatDto {
     pub files_changed: usize,
@@ -73,6 +171,14 @@ pub struct BranchInfoDto {
     pub behind: i32,
 }
 
+/// A branch name and worktree path that are both free, from `suggest_worktree_name`.
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct WorktreeSuggestionDto {
+    pub branch: String,
+    /// Relative to the repository root, e.g. `../repo-feature-2`
+    pub path: String,
+}
+
 #[derive(Clone, Debug, Serialize, TS)]
 pub struct RemoteInfoDto {
     pub name: String,
@@ -95,6 +201,16 @@ pub struct WorktreeInfoDto {
     pub behind: i32,
 }
 
+/// A worktree and its uncommitted changes. `error` is set instead of `stats` when the
+/// worktree can't be read, e.g. because its directory was deleted.
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct WorktreeStatusDto {
+    pub branch: String,
+    pub path: String,
+    pub stats: Option<DiffStatDto>,
+    pub error: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, TS)]
 pub struct StashInfoDto {
     pub index: i32,
@@ -103,9 +219,89 @@ pub struct StashInfoDto {
     pub relative_time: String,
 }
 
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct ReflogEntryDto {
+    pub old_id: String,
+    pub new_id: String,
+    pub message: String,
+    pub committer: String,
+    pub relative_time: String,
+}
+
 #[derive(Clone, Debug, Serialize, TS)]
 pub struct TagInfoDto {
     pub name: String,
+    /// Commit the tag points at, after peeling annotated tags; the object id itself for
+    /// tags of trees or blobs.
+    pub target_commit: String,
+    pub is_annotated: bool,
+    /// Annotation message of an annotated tag
+    pub message: Option<String>,
+    /// Tagger name of an annotated tag
+    pub tagger: Option<String>,
+    /// When the tag was made for annotated tags, otherwise when the target was committed
+    pub relative_time: String,
+}
+
+/// One entry of a directory listing from `list_tree`.
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct TreeEntryDto {
+    pub name: String,
+    pub is_dir: bool,
+    /// Blob size in bytes; `None` for directories and submodules
+    #[ts(type = "number | null")]
+    pub size: Option<u64>,
+    pub object_id: String,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectKind {
+    Blob,
+    Tree,
+    Commit,
+    Tag,
+}
+
+/// A raw object read from the object database by `read_object`.
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct ObjectDto {
+    pub kind: ObjectKind,
+    /// Size of the raw object data in bytes
+    #[ts(type = "number")]
+    pub size: u64,
+    /// Pretty-printed object, as `git cat-file -p` shows it; `None` for binary blobs
+    pub content: Option<String>,
+    pub is_binary: bool,
+}
+
+/// What amending HEAD would produce, as computed by `amend_preview`.
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct AmendPreviewDto {
+    pub head_id: String,
+    /// Message the amended commit keeps unless a new one is given
+    pub message: String,
+    /// Parents of the amended commit, which are those of the current HEAD
+    pub parent_ids: Vec<String>,
+    /// Changes folded into the commit, relative to the current HEAD
+    pub staged_files: Vec<DiffFileSummaryDto>,
+    /// Everything the amended commit changes relative to its first parent
+    pub files: Vec<DiffFileSummaryDto>,
+}
+
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct SquashPreviewDto {
+    pub resulting_message: String,
+    pub commit_count_before: usize,
+    pub commit_count_after: usize,
+}
+
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct CommitLintDto {
+    pub valid: bool,
+    pub errors: Vec<String>,
+    pub parsed_type: Option<String>,
+    pub scope: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
@@ -116,11 +312,66 @@ pub enum DiffCompareKind {
     IndexHead,
 }
 
+/// How a `RefRef` diff presents a merge commit on the right side.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeDiffMode {
+    /// Changes relative to the first parent (`git diff -m --first-parent`).
+    FirstParent,
+    /// Only hunks that differ from every parent (`git diff -c`).
+    Combined,
+}
+
+/// Whether a merge may move the target branch forward instead of creating a merge commit.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FastForwardMode {
+    /// Fast-forward when possible, otherwise create a merge commit.
+    Auto,
+    /// Only fast-forward, failing when the branches have diverged (`--ff-only`).
+    Only,
+    /// Always create a merge commit (`--no-ff`), which is what merges did before the mode
+    /// existed.
+    #[default]
+    Never,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct DiffRequestOptionsDto {
     pub context_lines: Option<u32>,
     pub show_binary: Option<bool>,
     pub include_untracked: Option<bool>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub merge_diff_mode: Option<MergeDiffMode>,
+    /// Upper bound on `diff_text` in bytes; longer diffs are cut at a line boundary.
+    #[serde(default)]
+    #[ts(optional)]
+    pub max_output_bytes: Option<usize>,
+    /// For `RefRef` with a `left`, diff `right` against the merge base of the two refs
+    /// (`git diff left...right`) instead of against `left` itself.
+    #[serde(default)]
+    #[ts(optional)]
+    pub use_merge_base: Option<bool>,
+    /// Similarity percentage (0-100) at which a delete/add pair counts as a rename;
+    /// defaults to 50 like `git diff -M`.
+    #[serde(default)]
+    #[ts(optional)]
+    pub rename_threshold: Option<u16>,
+    /// Also detect copies of modified files (`git diff -C`), using the same threshold.
+    #[serde(default)]
+    #[ts(optional)]
+    pub find_copies: Option<bool>,
+    /// Leave files whose only change is line endings out of `diff_text`. They are flagged
+    /// with `eol_only` in `file_summaries` either way.
+    #[serde(default)]
+    #[ts(optional)]
+    pub ignore_eol: Option<bool>,
+    /// Skip the patch text and only count added/deleted lines per file (`git diff
+    /// --numstat`); `diff_text` comes back empty. Ignored for combined merge diffs.
+    #[serde(default)]
+    #[ts(optional)]
+    pub stat_only: Option<bool>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
@@ -138,6 +389,13 @@ pub struct DiffFileSummaryDto {
     pub path: String,
     pub status: DiffDeltaStatus,
     pub is_binary: bool,
+    /// The file differs only in line endings (CRLF vs LF). Always detected, whatever the
+    /// request's `ignore_eol`.
+    pub eol_only: bool,
+    /// Added lines; only counted for `stat_only` diffs, and `None` for binary files.
+    pub insertions: Option<u32>,
+    /// Deleted lines, counted alongside `insertions`.
+    pub deletions: Option<u32>,
 }
 
 #[derive(Clone, Debug, Serialize, TS)]
@@ -149,6 +407,30 @@ pub struct DiffMetaDto {
     pub context_lines: u32,
     pub file_summaries: Vec<DiffFileSummaryDto>,
     pub conflicted_paths: Vec<String>,
+    pub truncated: bool,
+    /// Rename similarity threshold the diff was computed with.
+    pub rename_threshold: u16,
+}
+
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct ConflictVersionsDto {
+    pub path: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    pub is_binary: bool,
+}
+
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct RepoStatsDto {
+    pub commit_count: usize,
+    pub branch_count: usize,
+    pub tag_count: usize,
+    pub object_count: usize,
+    #[ts(type = "number")]
+    pub pack_size_bytes: u64,
+    pub loose_object_count: usize,
+    pub gc_recommended: bool,
 }
 
 #[derive(Clone, Debug, Serialize, TS)]
@@ -158,7 +440,7 @@ pub struct DiffResponseDto {
     pub meta: DiffMetaDto,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, TS, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum DiffDeltaStatus {
     Unmodified,
@@ -173,3 +455,53 @@ pub enum DiffDeltaStatus {
     Unreadable,
     Conflicted,
 }
+
+/// Payload of the `git-credential-request` event, asking the client for credentials.
+#[derive(Clone, Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCredentialRequestDto {
+    pub request_id: String,
+    pub url: String,
+    /// Host the answer is cached under for the rest of the session
+    pub host: String,
+    pub username: Option<String>,
+}
+
+/// What `push` sends and where. With no remote, git pushes the current branch to its
+/// configured upstream.
+#[derive(Clone, Debug, Default)]
+pub struct GitPushOptions {
+    pub force: bool,
+    /// Only overwrite the remote branch if it still matches our remote-tracking ref; takes
+    /// precedence over `force`
+    pub force_with_lease: bool,
+    pub remote: Option<String>,
+    /// Local branch to push; defaults to the current branch when `remote_branch` is set
+    pub local_branch: Option<String>,
+    /// Name of the branch on the remote; defaults to `local_branch`
+    pub remote_branch: Option<String>,
+    /// Record the pushed branch as the local branch's upstream
+    pub set_upstream: bool,
+}
+
+/// Proxy settings for git commands that talk to a remote.
+#[derive(Clone, Debug, Deserialize, TS)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GitNetworkOptions {
+    /// Explicit proxy URL; takes precedence over the system proxy
+    #[ts(optional)]
+    pub proxy: Option<String>,
+    /// Apply the auto-detected system proxy when no explicit proxy is set. When off, proxy
+    /// variables inherited from the environment are cleared too
+    #[ts(as = "Option<bool>", optional)]
+    pub use_system_proxy: bool,
+}
+
+impl Default for GitNetworkOptions {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            use_system_proxy: true,
+        }
+    }
+}
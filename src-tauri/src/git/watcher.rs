@@ -0,0 +1,157 @@
+use crate::git::error::GitError;
+use crate::git::scanner::canonicalize_path;
+use crate::git::status::open_repo;
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// Quiet period after the last relevant file event before the change callback fires.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Longest a burst of relevant events can hold the change callback back.
+const MAX_DEBOUNCE: Duration = Duration::from_secs(5);
+
+type WatchEvent = notify::Result<notify::Event>;
+type EventReceiver = Receiver<WatchEvent>;
+
+/// Keeps a repository watch alive; dropping it stops watching.
+pub struct RepoWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Watches the worktree and git directory, calling `callback` once per burst of changes.
+pub fn watch_repo<F>(cwd: &Path, callback: F) -> Result<RepoWatcher, GitError>
+where
+    F: Fn() + Send + 'static,
+{
+    let repo = open_repo(cwd)?;
+    let git_dir = canonicalize_path(repo.path());
+    let root = repo
+        .workdir()
+        .map(canonicalize_path)
+        .unwrap_or_else(|| git_dir.clone());
+    // Linked worktrees keep their objects in the main repository's git dir
+    let objects_dir = canonicalize_path(repo.commondir()).join("objects");
+
+    let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher = notify::recommended_watcher(tx).map_err(watch_error)?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(watch_error)?;
+    if !git_dir.starts_with(&root) {
+        watcher
+            .watch(&git_dir, RecursiveMode::Recursive)
+            .map_err(watch_error)?;
+    }
+
+    // The thread exits once the watcher, and with it the sender, is dropped
+    std::thread::spawn(move || {
+        let is_relevant = |event: &WatchEvent| is_relevant_event(event, &objects_dir);
+        while let Ok(event) = rx.recv() {
+            if !is_relevant(&event) || !settle(&rx, is_relevant) {
+                continue;
+            }
+            callback();
+        }
+    });
+
+    Ok(RepoWatcher { _watcher: watcher })
+}
+
+/// Keeps a scan root watch alive; dropping it stops watching.
+pub struct ScanRootWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Watches everything under `root`, calling `callback` once per burst of changes that
+/// add or remove a repository, i.e. a `.git` entry at any depth.
+///
+/// Events are filtered by path alone, so the tree is never re-walked. A repository moved
+/// out of `root` as a whole is only seen as its directory disappearing and isn't reported.
+pub fn watch_scan_root<F>(root: &Path, callback: F) -> Result<ScanRootWatcher, GitError>
+where
+    F: Fn() + Send + 'static,
+{
+    let root = canonicalize_path(root);
+    if !root.is_dir() {
+        return Err(GitError::InvalidPath(root.to_string_lossy().to_string()));
+    }
+
+    let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher = notify::recommended_watcher(tx).map_err(watch_error)?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(watch_error)?;
+
+    // The thread exits once the watcher, and with it the sender, is dropped
+    std::thread::spawn(move || {
+        while let Ok(event) = rx.recv() {
+            // A clone writes thousands of files, but only its `.git` entries restart the wait
+            if !is_repo_set_change(&event) || !settle(&rx, is_repo_set_change) {
+                continue;
+            }
+            callback();
+        }
+    });
+
+    Ok(ScanRootWatcher { _watcher: watcher })
+}
+
+/// Waits until no `is_relevant` event has arrived for `DEBOUNCE`, or `MAX_DEBOUNCE` has
+/// passed, so unrelated churn (or an endless burst) can't hold the callback back forever.
+/// Returns `false` once the watcher is gone.
+fn settle(rx: &EventReceiver, is_relevant: impl Fn(&WatchEvent) -> bool) -> bool {
+    let deadline = Instant::now() + MAX_DEBOUNCE;
+    let mut quiet_until = Instant::now() + DEBOUNCE;
+    loop {
+        let wait_until = quiet_until.min(deadline);
+        let now = Instant::now();
+        if now >= wait_until {
+            return true;
+        }
+        match rx.recv_timeout(wait_until - now) {
+            Ok(event) if is_relevant(&event) => quiet_until = Instant::now() + DEBOUNCE,
+            Ok(_) => {}
+            Err(RecvTimeoutError::Timeout) => return true,
+            Err(RecvTimeoutError::Disconnected) => return false,
+        }
+    }
+}
+
+/// Whether `event` creates, removes or renames a `.git` entry. A directory created or
+/// moved in also counts when it already holds one, since the recursive watch may only
+/// be added after a clone has written its `.git`.
+fn is_repo_set_change(event: &notify::Result<notify::Event>) -> bool {
+    let Ok(event) = event else {
+        return false;
+    };
+    let adds_or_removes = matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))
+    );
+    adds_or_removes
+        && event.paths.iter().any(|path| {
+            if path.file_name().is_some_and(|name| name == ".git") {
+                return true;
+            }
+            let inside_git_dir = path.components().any(|part| part.as_os_str() == ".git");
+            !inside_git_dir && path.join(".git").exists()
+        })
+}
+
+/// Object writes churn constantly during fetches and gc without changing status.
+fn is_relevant_event(event: &notify::Result<notify::Event>, objects_dir: &Path) -> bool {
+    let Ok(event) = event else {
+        return false;
+    };
+    if event.kind.is_access() {
+        return false;
+    }
+    event.paths.iter().any(|path| !path.starts_with(objects_dir))
+}
+
+fn watch_error(err: notify::Error) -> GitError {
+    GitError::Internal(format!("failed to watch repository: {err}"))
+}
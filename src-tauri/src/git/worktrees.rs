@@ -2,12 +2,16 @@ use crate::git::branches::current_branch_from_repo;
 use crate::git::error::GitError;
 use crate::git::proxy::configure_proxy;
 use crate::git::scanner::canonicalize_path;
-use crate::git::status::open_repo;
-use crate::git::types::WorktreeInfoDto;
+use crate::git::status::{diff_stats_worktree, open_repo};
+use crate::git::types::{
+    GitNetworkOptions, WorktreeInfoDto, WorktreeStatusDto, WorktreeSuggestionDto,
+};
 use git2::ErrorCode;
+use std::ffi::OsStr;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
+use walkdir::WalkDir;
 
 pub fn list_worktrees(cwd: &Path) -> Result<Vec<WorktreeInfoDto>, GitError> {
     let repo = open_repo(cwd)?;
@@ -35,16 +39,7 @@ pub fn list_worktrees(cwd: &Path) -> Result<Vec<WorktreeInfoDto>, GitError> {
 
         if !path.exists() {
             // Try to find the branch name before pruning
-            let mut branch_to_delete = None;
-            let worktree_git_dir = repo.path().join("worktrees").join(name);
-            let head_file = worktree_git_dir.join("HEAD");
-
-            if let Ok(content) = fs::read_to_string(&head_file) {
-                let content = content.trim();
-                if let Some(ref_name) = content.strip_prefix("ref: refs/heads/") {
-                    branch_to_delete = Some(ref_name.to_string());
-                }
-            }
+            let branch_to_delete = missing_worktree_branch(&repo, name);
 
             let mut opts = git2::WorktreePruneOptions::new();
             opts.valid(true);
@@ -85,12 +80,79 @@ pub fn list_worktrees(cwd: &Path) -> Result<Vec<WorktreeInfoDto>, GitError> {
     Ok(worktrees)
 }
 
+/// Lists every worktree of the repository at `repo_root` with its changes against its
+/// `HEAD`, as counted by `diff_stats_worktree`.
+///
+/// Unlike `list_worktrees` this never prunes: a worktree whose directory is missing is
+/// reported with `error` set so the caller can decide what to do with it.
+pub fn worktrees_with_stats(repo_root: &Path) -> Result<Vec<WorktreeStatusDto>, GitError> {
+    let repo = open_repo(repo_root)?;
+    let mut worktrees = Vec::new();
+
+    if let Some(workdir) = repo.workdir() {
+        worktrees.push(worktree_status(workdir, current_branch_from_repo(&repo)?));
+    }
+
+    let names = repo.worktrees()?;
+    for name in names.iter().flatten() {
+        let worktree = match repo.find_worktree(name) {
+            Ok(worktree) => worktree,
+            Err(err) if err.code() == ErrorCode::NotFound => continue,
+            Err(err) => return Err(GitError::Git2(err)),
+        };
+        let path = worktree.path();
+
+        if !path.exists() {
+            worktrees.push(WorktreeStatusDto {
+                branch: missing_worktree_branch(&repo, name).unwrap_or_else(|| "HEAD".to_string()),
+                path: path.to_string_lossy().to_string(),
+                stats: None,
+                error: Some(format!("worktree path {} does not exist", path.display())),
+            });
+            continue;
+        }
+
+        let branch = git2::Repository::open(path)
+            .ok()
+            .and_then(|worktree_repo| current_branch_from_repo(&worktree_repo).ok())
+            .unwrap_or_else(|| "HEAD".to_string());
+        worktrees.push(worktree_status(path, branch));
+    }
+
+    Ok(worktrees)
+}
+
+fn worktree_status(path: &Path, branch: String) -> WorktreeStatusDto {
+    let (stats, error) = match diff_stats_worktree(path) {
+        Ok(stats) => (Some(stats), None),
+        Err(err) => (None, Some(err.to_string())),
+    };
+    WorktreeStatusDto {
+        branch,
+        path: canonicalize_path(path).to_string_lossy().to_string(),
+        stats,
+        error,
+    }
+}
+
+/// Reads the branch a worktree had checked out from its admin dir, which outlives the
+/// worktree directory itself.
+fn missing_worktree_branch(repo: &git2::Repository, name: &str) -> Option<String> {
+    let head_file = repo.commondir().join("worktrees").join(name).join("HEAD");
+    let content = fs::read_to_string(head_file).ok()?;
+    content
+        .trim()
+        .strip_prefix("ref: refs/heads/")
+        .map(str::to_string)
+}
+
 pub fn add_worktree(
     repo_root: &Path,
     worktree_path: &Path,
     branch: &str,
     start_point: &str,
 ) -> Result<(), GitError> {
+    validate_add_worktree(repo_root, worktree_path, branch, start_point)?;
     let repo = open_repo(repo_root)?;
 
     // Try to find the existing branch first, or create it from start_point
@@ -124,6 +186,123 @@ pub fn add_worktree(
     Ok(())
 }
 
+/// Checks the arguments of `add_worktree` without creating anything, so a form can report
+/// problems as they are typed.
+///
+/// An existing branch is reused by `add_worktree`, so it only fails validation while it is
+/// checked out somewhere else; `start_point` is only checked when the branch would be
+/// created from it. Fails with `InvalidBranch`, `PathOccupied`, `InvalidPath` (the parent
+/// directory is missing) or `InvalidStartPoint`.
+pub fn validate_add_worktree(
+    repo_root: &Path,
+    worktree_path: &Path,
+    branch: &str,
+    start_point: &str,
+) -> Result<(), GitError> {
+    let repo = open_repo(repo_root)?;
+
+    if branch.trim().is_empty() || !git2::Branch::name_is_valid(branch)? {
+        return Err(GitError::InvalidBranch(format!("'{branch}' is not a valid branch name")));
+    }
+    let branch_exists = repo.find_branch(branch, git2::BranchType::Local).is_ok();
+    if branch_exists {
+        if let Some(checked_out_at) = branch_checkout_path(&repo, branch)? {
+            return Err(GitError::InvalidBranch(format!(
+                "'{branch}' is already checked out at {}",
+                checked_out_at.display()
+            )));
+        }
+    }
+
+    let full_path = if worktree_path.is_absolute() {
+        worktree_path.to_path_buf()
+    } else {
+        repo_root.join(worktree_path)
+    };
+    // Unlike `git worktree add`, libgit2 refuses even an empty directory
+    if full_path.exists() {
+        return Err(GitError::PathOccupied(format!("{} already exists", full_path.display())));
+    }
+    // Nor does it create missing parent directories
+    if !full_path.parent().is_some_and(Path::is_dir) {
+        return Err(GitError::InvalidPath(full_path.display().to_string()));
+    }
+    let worktree_name = full_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(branch);
+    if repo.find_worktree(worktree_name).is_ok() {
+        return Err(GitError::PathOccupied(format!(
+            "a worktree named '{worktree_name}' is already registered"
+        )));
+    }
+
+    let start_commit = repo.revparse_single(start_point).and_then(|obj| obj.peel_to_commit());
+    if !branch_exists && start_commit.is_err() {
+        return Err(GitError::InvalidStartPoint(format!("'{start_point}' does not name a commit")));
+    }
+    Ok(())
+}
+
+/// Upper bound on the numeric suffixes `suggest_worktree_name` tries.
+const MAX_NAME_SUGGESTIONS: usize = 1000;
+
+/// Suggests a branch and a sibling worktree directory (`../<repo>-<branch>`, the layout the
+/// create dialog defaults to) derived from `base`, appending `-2`, `-3`, ... until neither
+/// the branch, the directory nor a registered worktree of that name exists.
+pub fn suggest_worktree_name(
+    repo_root: &Path,
+    base: &str,
+) -> Result<WorktreeSuggestionDto, GitError> {
+    let repo = open_repo(repo_root)?;
+    let base = base.trim();
+    if base.is_empty() || !git2::Branch::name_is_valid(base)? {
+        return Err(GitError::InvalidBranch(format!("'{base}' is not a valid branch name")));
+    }
+    let repo_name = canonicalize_path(repo_root)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "worktree".to_string());
+
+    for n in 1..=MAX_NAME_SUGGESTIONS {
+        let branch = if n == 1 { base.to_string() } else { format!("{base}-{n}") };
+        let dir_name = format!("{repo_name}-{}", branch.replace('/', "-"));
+        let path = format!("../{dir_name}");
+        let taken = repo.find_branch(&branch, git2::BranchType::Local).is_ok()
+            || repo_root.join(&path).exists()
+            || repo.find_worktree(&dir_name).is_ok();
+        if !taken {
+            return Ok(WorktreeSuggestionDto { branch, path });
+        }
+    }
+    Err(GitError::GitFailed {
+        code: None,
+        stderr: format!("no free worktree name found for '{base}'"),
+    })
+}
+
+/// Where `branch` is checked out, either in the main working tree or a linked worktree.
+fn branch_checkout_path(
+    repo: &git2::Repository,
+    branch: &str,
+) -> Result<Option<std::path::PathBuf>, GitError> {
+    // `repo` may itself be a linked worktree, so look at the main one through the common dir
+    let main = git2::Repository::open(repo.commondir())?;
+    if let (Ok(head), Some(workdir)) = (main.head(), main.workdir()) {
+        if head.is_branch() && head.shorthand() == Some(branch) {
+            return Ok(Some(workdir.to_path_buf()));
+        }
+    }
+    let names = repo.worktrees()?;
+    for name in names.iter().flatten() {
+        if missing_worktree_branch(repo, name).as_deref() == Some(branch) {
+            let worktree = repo.find_worktree(name)?;
+            return Ok(Some(worktree.path().to_path_buf()));
+        }
+    }
+    Ok(None)
+}
+
 pub fn remove_worktree(
     repo_root: &Path,
     worktree_path: &Path,
@@ -152,11 +331,88 @@ pub fn remove_worktree(
     })
 }
 
+/// Moves the linked worktree at `from` to `to` with `git worktree move`, which git2 has
+/// no equivalent for. Relative paths are resolved against `repo_root`.
+///
+/// `to` must not exist or be an empty directory.
+pub fn move_worktree(repo_root: &Path, from: &Path, to: &Path) -> Result<(), GitError> {
+    let from = repo_root.join(from);
+    let to = repo_root.join(to);
+    if to.exists() {
+        let is_empty_dir = to.is_dir() && fs::read_dir(&to).map_err(GitError::Io)?.next().is_none();
+        if !is_empty_dir {
+            return Err(GitError::GitFailed {
+                code: None,
+                stderr: format!("destination {} already exists and is not empty", to.display()),
+            });
+        }
+        // git would move the worktree inside an existing directory instead of onto it
+        fs::remove_dir(&to).map_err(GitError::Io)?;
+    }
+
+    run_git_command(
+        repo_root,
+        [OsStr::new("worktree"), OsStr::new("move"), from.as_os_str(), to.as_os_str()],
+    )?;
+    Ok(())
+}
+
+/// Runs `git worktree repair` to rewrite stale links between the repository at
+/// `repo_root` and worktrees that are still at their recorded paths. Does nothing when
+/// every link is already valid.
+pub fn repair_worktrees(repo_root: &Path) -> Result<(), GitError> {
+    run_git_command(repo_root, ["worktree", "repair"])?;
+    Ok(())
+}
+
+/// Lists the sparse-checkout patterns of the worktree at `cwd` (directories in cone mode).
+/// Fails with git's "not sparse" error when sparse checkout was never enabled.
+pub fn get_sparse_checkout(cwd: &Path) -> Result<Vec<String>, GitError> {
+    let output = run_git_command(cwd, ["sparse-checkout", "list"])?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Replaces the sparse-checkout patterns of the worktree at `cwd` with `git sparse-checkout
+/// set`, enabling sparse checkout if needed and updating the working tree to match. With
+/// `cone`, patterns are directories; otherwise they use gitignore syntax.
+pub fn set_sparse_checkout(cwd: &Path, patterns: &[String], cone: bool) -> Result<(), GitError> {
+    let mode = if cone { "--cone" } else { "--no-cone" };
+    let mut args = vec!["sparse-checkout", "set", mode, "--"];
+    args.extend(patterns.iter().map(String::as_str));
+    run_git_command(cwd, args)?;
+    Ok(())
+}
+
 pub fn detach_worktree_head(worktree_path: &Path) -> Result<(), GitError> {
     let _ = run_git_command(worktree_path, ["checkout", "--detach"])?;
     Ok(())
 }
 
+/// Total size in bytes of the files under a worktree, leaving out the `.git` entry at its
+/// root: repository storage shared with other worktrees rather than checkout content.
+/// Symlinks are not followed, so nothing is counted twice and link cycles can't recurse.
+/// Entries that can't be read are skipped.
+pub fn worktree_disk_usage(path: &Path) -> Result<u64, GitError> {
+    if !fs::metadata(path).map_err(GitError::Io)?.is_dir() {
+        return Err(GitError::InvalidPath(path.display().to_string()));
+    }
+    let walker = WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| !(entry.depth() == 1 && entry.file_name() == ".git"));
+    let mut total = 0;
+    for entry in walker.flatten() {
+        if entry.file_type().is_file() {
+            total += entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        }
+    }
+    Ok(total)
+}
+
 fn run_git_command<I, S>(cwd: &Path, args: I) -> Result<std::process::Output, GitError>
 where
     I: IntoIterator<Item = S>,
@@ -165,7 +421,7 @@ where
     let mut cmd = Command::new("git");
     cmd.args(args).current_dir(cwd);
 
-    let proxy_url = configure_proxy(&mut cmd);
+    let proxy_url = configure_proxy(&mut cmd, &GitNetworkOptions::default());
     let output = cmd.output().map_err(GitError::Io)?;
 
     if !output.status.success() {
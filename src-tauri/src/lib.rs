@@ -7,12 +7,15 @@ use uuid::Uuid;
 mod command_error;
 mod commands;
 mod error;
+pub mod logging;
+mod shell;
 mod utils;
 
 pub mod acp;
 use crate::acp::AcpManager;
 use crate::acp::types::AcpEvent;
 pub mod git;
+use crate::git::{ActiveScan, CredentialBroker};
 mod pty;
 use crate::pty::PtyManager;
 pub mod ws;
@@ -73,7 +76,13 @@ pub fn run() {
             }
             let token = auth_token.clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(err) = ws_server::run_ws_server_on_listener(listener, token).await {
+                if let Err(err) = ws_server::run_ws_server_on_listener(
+                    listener,
+                    token,
+                    ws_server::DEFAULT_EVENT_BUFFER,
+                )
+                .await
+                {
                     tracing::error!("ws server error: {err}");
                 }
             });
@@ -82,16 +91,25 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(PtyManager::default())
+        .manage(CredentialBroker::default())
+        .manage(ActiveScan::default())
         .invoke_handler(tauri::generate_handler![
             // PTY commands
             pty::create_session,
+            pty::create_sessions,
+            pty::group_status,
             pty::write_to_session,
+            pty::set_session_cwd,
             pty::resize_session,
             pty::kill_session,
+            pty::signal_session,
             pty::broadcast_line,
             // ACP commands
             commands::acp_connect,
             commands::acp_disconnect,
+            commands::acp_ping,
+            commands::acp_get_stderr,
+            commands::acp_validate_mcp,
             commands::acp_session_new,
             commands::acp_session_load,
             commands::acp_session_prompt,
@@ -102,28 +120,61 @@ pub fn run() {
             // Git commands
             commands::git_detect_repo,
             commands::git_scan_repos,
+            commands::git_has_any_repo,
+            commands::git_cancel_scan,
             commands::git_status,
+            commands::git_repo_overview,
+            commands::git_is_clean,
+            commands::git_operation_state,
             commands::git_diff,
             commands::git_unified_diff,
             commands::git_list_branches,
             commands::git_list_remote_branches,
+            commands::git_repo_stats,
+            commands::git_gc,
+            commands::git_branch_sync_status,
             commands::git_list_commits,
+            commands::git_list_commits_paged,
             commands::git_list_commits_range,
+            commands::git_last_commit_for_path,
+            commands::git_log_for_path,
+            commands::git_unpushed_commits,
+            commands::git_author_stats,
             commands::git_list_worktrees,
+            commands::git_worktrees_with_stats,
             commands::git_list_remotes,
+            commands::git_set_remote_url,
+            commands::git_add_remote,
+            commands::git_remove_remote,
             commands::git_list_submodules,
             commands::git_list_stashes,
             commands::git_list_tags,
+            commands::git_list_reflog,
+            commands::git_restore_to_reflog,
+            commands::git_validate_commit_message,
             commands::git_apply_stash,
             commands::git_drop_stash,
+            commands::git_rename_stash,
+            commands::git_stash_diff,
+            commands::git_commit_files,
+            commands::git_fetch,
+            commands::git_credential_reply,
             commands::git_pull,
+            commands::git_invalidate_proxy_cache,
             commands::git_push,
             commands::git_commit,
+            commands::git_amend_preview,
             commands::git_stage_files,
             commands::git_unstage_files,
             commands::git_discard_files,
+            commands::git_resolve_conflict,
+            commands::git_conflict_versions,
+            commands::git_expand_context,
+            commands::git_list_tree,
+            commands::git_read_object,
             commands::git_stage_all,
             commands::git_unstage_all,
+            commands::git_can_merge,
             commands::git_merge_into_branch,
             commands::git_rebase_branch,
             commands::git_create_branch,
@@ -132,10 +183,28 @@ pub fn run() {
             commands::git_smart_checkout_branch,
             commands::git_reset,
             commands::git_revert,
+            commands::git_rebase_abort,
+            commands::git_rebase_continue,
+            commands::git_revert_abort,
+            commands::git_cherry_pick_abort,
+            commands::git_clear_pending_message,
+            commands::git_archive,
+            commands::git_apply_patch,
+            commands::git_format_patch,
             commands::git_squash_commits,
+            commands::git_drop_commits,
+            commands::git_reorder_commits,
             commands::git_commits_in_remote,
+            commands::git_diff_worktrees,
             commands::git_add_worktree,
+            commands::git_validate_add_worktree,
+            commands::git_suggest_worktree_name,
             commands::git_remove_worktree,
+            commands::git_move_worktree,
+            commands::git_repair_worktrees,
+            commands::git_worktree_disk_usage,
+            commands::git_get_sparse_checkout,
+            commands::git_set_sparse_checkout,
             commands::git_delete_branch,
             commands::git_stash_save
         ])
@@ -2,21 +2,52 @@
 //!
 //! Provides dual output to both stdout and log files with configurable levels.
 
+use crate::error::AppError;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, PoisonError};
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+use tracing_subscriber::{
+    filter::filter_fn, fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter,
+    Layer, Registry,
+};
 
 /// Log directory name within the application data directory
 const LOG_DIR_NAME: &str = "logs";
 
+/// Base name of the log file; daily rotation appends the date
+const LOG_FILE_NAME: &str = "parallel-cli-runner.log";
+
 /// Default log level when RUST_LOG is not set
 const DEFAULT_LOG_LEVEL: &str = "info";
 
+/// Handle for swapping the active filter at runtime
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Directory the file layer writes to
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Receives each formatted log line while log streaming is enabled
+pub type LogLineSink = Box<dyn Fn(&str) + Send + Sync>;
+
+static LOG_LINE_SINK: Mutex<Option<LogLineSink>> = Mutex::new(None);
+static LOG_STREAMING: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Set while the sink runs so logging from inside it cannot feed back into itself
+    static IN_LOG_SINK: Cell<bool> = const { Cell::new(false) };
+}
+
 /// Initializes the logging system with both stdout and file output.
 ///
 /// # Arguments
 ///
 /// * `log_dir` - Optional path to a custom log directory. If `None`, uses a default location.
+/// * `log_level` - Optional filter directive (e.g. `debug`); overrides RUST_LOG when valid.
 ///
 /// # Returns
 ///
@@ -27,14 +58,22 @@ const DEFAULT_LOG_LEVEL: &str = "info";
 /// # Example
 ///
 /// ```ignore
-/// let guard = init_logging(None);
+/// let guard = init_logging(None, None);
 /// // ... application code ...
 /// // guard is dropped here, flushing any remaining logs
 /// ```
-pub fn init_logging(log_dir: Option<&Path>) -> Option<WorkerGuard> {
-    // Determine log level from environment or use default
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_LEVEL));
+pub fn init_logging(log_dir: Option<&Path>, log_level: Option<&str>) -> Option<WorkerGuard> {
+    // Determine log level from the argument, then the environment, then the default
+    let mut invalid_level = None;
+    let env_filter = match log_level.map(EnvFilter::try_new) {
+        Some(Ok(filter)) => filter,
+        requested => {
+            invalid_level = requested.and_then(Result::err);
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_LEVEL))
+        }
+    };
+    let (filter_layer, filter_handle) = reload::Layer::new(env_filter);
 
     // Determine log directory
     let log_dir = log_dir.unwrap_or_else(|| Path::new(LOG_DIR_NAME));
@@ -43,26 +82,38 @@ pub fn init_logging(log_dir: Option<&Path>) -> Option<WorkerGuard> {
     std::fs::create_dir_all(log_dir).expect("failed to create log directory");
 
     // Set up file appender with daily rotation
-    let file_appender = tracing_appender::rolling::daily(log_dir, "parallel-cli-runner.log");
+    let file_appender = tracing_appender::rolling::daily(log_dir, LOG_FILE_NAME);
+    let _ = LOG_DIR.set(log_dir.canonicalize().unwrap_or_else(|_| log_dir.to_path_buf()));
     let (non_blocking_file, guard) = tracing_appender::non_blocking(file_appender);
 
     // Set up stdout layer
     let stdout_layer = fmt::layer()
         .with_writer(std::io::stdout)
-        .with_ansi(true)
-        .with_filter(env_filter.clone());
+        .with_ansi(true);
 
     // Set up file layer
     let file_layer = fmt::layer()
         .with_writer(non_blocking_file)
+        .with_ansi(false);
+
+    // Set up streaming layer, only formatting lines while a sink is installed
+    let stream_layer = fmt::layer()
+        .with_writer(|| LogLineWriter)
         .with_ansi(false)
-        .with_filter(env_filter.clone());
+        .with_filter(filter_fn(|_| LOG_STREAMING.load(Ordering::Relaxed)));
 
     // Combine and initialize subscriber
     tracing_subscriber::registry()
+        .with(filter_layer)
         .with(stdout_layer)
         .with(file_layer)
+        .with(stream_layer)
         .init();
+    let _ = FILTER_HANDLE.set(filter_handle);
+
+    if let Some(err) = invalid_level {
+        tracing::warn!("ignoring invalid log level {:?}: {err}", log_level.unwrap_or_default());
+    }
 
     tracing::info!(
         log_dir = %log_dir.display(),
@@ -76,8 +127,8 @@ pub fn init_logging(log_dir: Option<&Path>) -> Option<WorkerGuard> {
 ///
 /// This is a convenience function that sets up logging in the current
 /// working directory for server mode operation.
-pub fn init_ws_server_logging() -> Option<WorkerGuard> {
-    init_logging(Some(Path::new(".")))
+pub fn init_ws_server_logging(log_level: Option<&str>) -> Option<WorkerGuard> {
+    init_logging(Some(Path::new(".")), log_level)
 }
 
 /// Initializes logging for Tauri desktop app mode.
@@ -85,10 +136,10 @@ pub fn init_ws_server_logging() -> Option<WorkerGuard> {
 /// This is a convenience function that uses a platform-appropriate
 /// log directory for desktop application operation.
 #[cfg(target_os = "macos")]
-pub fn init_desktop_logging() -> Option<WorkerGuard> {
+pub fn init_desktop_logging(log_level: Option<&str>) -> Option<WorkerGuard> {
     let log_dir = dirs::home_dir()
         .map(|p| p.join("Library").join("Logs").join("parallel-cli-runner"));
-    init_logging(log_dir.as_deref())
+    init_logging(log_dir.as_deref(), log_level)
 }
 
 /// Initializes logging for Tauri desktop app mode.
@@ -96,10 +147,10 @@ pub fn init_desktop_logging() -> Option<WorkerGuard> {
 /// This is a convenience function that uses a platform-appropriate
 /// log directory for desktop application operation.
 #[cfg(target_os = "windows")]
-pub fn init_desktop_logging() -> Option<WorkerGuard> {
+pub fn init_desktop_logging(log_level: Option<&str>) -> Option<WorkerGuard> {
     let log_dir = dirs::home_dir()
         .map(|p| p.join("parallel-cli-runner").join("logs"));
-    init_logging(log_dir.as_deref())
+    init_logging(log_dir.as_deref(), log_level)
 }
 
 /// Initializes logging for Tauri desktop app mode.
@@ -107,10 +158,10 @@ pub fn init_desktop_logging() -> Option<WorkerGuard> {
 /// This is a convenience function that uses a platform-appropriate
 /// log directory for desktop application operation.
 #[cfg(target_os = "linux")]
-pub fn init_desktop_logging() -> Option<WorkerGuard> {
+pub fn init_desktop_logging(log_level: Option<&str>) -> Option<WorkerGuard> {
     let log_dir = dirs::home_dir()
         .map(|p| p.join(".parallel-cli-runner").join("logs"));
-    init_logging(log_dir.as_deref())
+    init_logging(log_dir.as_deref(), log_level)
 }
 
 /// Initializes logging for Tauri desktop app mode.
@@ -118,21 +169,94 @@ pub fn init_desktop_logging() -> Option<WorkerGuard> {
 /// This is a convenience function that uses a platform-appropriate
 /// log directory for desktop application operation.
 #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
-pub fn init_desktop_logging() -> Option<WorkerGuard> {
+pub fn init_desktop_logging(log_level: Option<&str>) -> Option<WorkerGuard> {
     // Fallback to current directory for unknown platforms
-    init_logging(None)
+    init_logging(None, log_level)
 }
 
 /// Initializes logging for VSCode extension mode.
 ///
 /// Reads the log directory from the PARALLEL_CLI_RUNNER_LOG_DIR environment variable.
 /// If not set, falls back to logging in the current directory.
-pub fn init_extension_logging() -> Option<WorkerGuard> {
+pub fn init_extension_logging(log_level: Option<&str>) -> Option<WorkerGuard> {
     let log_dir = std::env::var("PARALLEL_CLI_RUNNER_LOG_DIR")
         .ok()
         .map(PathBuf::from);
 
-    init_logging(log_dir.as_deref())
+    init_logging(log_dir.as_deref(), log_level)
+}
+
+/// Replaces the active log filter, e.g. to switch to `trace` while debugging.
+pub fn set_log_level(level: &str) -> Result<(), AppError> {
+    let filter = EnvFilter::try_new(level).map_err(|err| {
+        AppError::parse(format!("invalid log level: {err}"), Some(level.to_string()))
+    })?;
+    let handle = FILTER_HANDLE
+        .get()
+        .ok_or_else(|| AppError::Internal("logging is not initialized".to_string()))?;
+    handle
+        .reload(filter)
+        .map_err(|err| AppError::Internal(err.to_string()))?;
+    tracing::info!(level, "Log level changed");
+    Ok(())
+}
+
+/// Returns the log file currently being written, i.e. the newest rotated file.
+pub fn log_file_path() -> Option<PathBuf> {
+    let log_dir = LOG_DIR.get()?;
+    fs::read_dir(log_dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(LOG_FILE_NAME))
+        .max_by_key(|entry| entry.file_name())
+        .map(|entry| entry.path())
+}
+
+/// Returns up to the last `lines` lines of the active log file.
+pub fn tail_log(lines: usize) -> Result<Vec<String>, AppError> {
+    let path = log_file_path()
+        .ok_or_else(|| AppError::Internal("no log file has been written yet".to_string()))?;
+    if lines == 0 {
+        return Ok(Vec::new());
+    }
+    let mut tail = VecDeque::with_capacity(lines);
+    for line in BufReader::new(File::open(path)?).split(b'\n') {
+        if tail.len() == lines {
+            tail.pop_front();
+        }
+        tail.push_back(String::from_utf8_lossy(&line?).trim_end_matches('\r').to_string());
+    }
+    Ok(tail.into())
+}
+
+/// Installs or removes the sink that receives every formatted log line.
+pub fn set_log_line_sink(sink: Option<LogLineSink>) {
+    let mut current = LOG_LINE_SINK.lock().unwrap_or_else(PoisonError::into_inner);
+    LOG_STREAMING.store(sink.is_some(), Ordering::Relaxed);
+    *current = sink;
+}
+
+struct LogLineWriter;
+
+impl Write for LogLineWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if IN_LOG_SINK.with(Cell::get) {
+            return Ok(buf.len());
+        }
+        let sink = LOG_LINE_SINK.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(sink) = sink.as_ref() {
+            IN_LOG_SINK.with(|flag| flag.set(true));
+            for line in String::from_utf8_lossy(buf).lines() {
+                sink(line);
+            }
+            IN_LOG_SINK.with(|flag| flag.set(false));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 /// Sets up a panic hook to capture panics to the log file.
@@ -1,45 +1,62 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod logging;
-
+use parallel_cli_runner_lib::logging;
 use std::env;
 
 struct WsArgs {
     port: u16,
     auth_token: String,
+    event_buffer: usize,
+    env: Vec<(String, String)>,
 }
 
 fn main() {
+    let ws_args = parse_ws_args();
+    if let Ok(Some(args)) = &ws_args {
+        // Set before logging starts its worker thread, while this is still the only thread,
+        // so every spawned `git` inherits them
+        for (key, value) in &args.env {
+            env::set_var(key, value);
+        }
+    }
+
     // Determine mode and initialize logging
-    let is_ws_mode = parse_ws_args().is_ok_and(|opt| opt.is_some());
+    let is_ws_mode = matches!(ws_args, Ok(Some(_)));
+    let args: Vec<String> = env::args().collect();
+    let log_level = find_arg_value(&args, "--log-level");
+    let log_level = log_level.as_deref();
 
     let _guard = if is_ws_mode {
         // Extension mode: check for custom log directory
-        logging::init_extension_logging().or_else(logging::init_ws_server_logging)
+        logging::init_extension_logging(log_level)
+            .or_else(|| logging::init_ws_server_logging(log_level))
     } else {
         // Desktop mode: use platform-specific log directory
-        logging::init_desktop_logging()
+        logging::init_desktop_logging(log_level)
     };
 
     // Set up panic hook to capture panics to log file
     logging::setup_panic_hook();
 
-    match parse_ws_args() {
+    match ws_args {
         Ok(Some(args)) => {
             let runtime = tokio::runtime::Builder::new_multi_thread()
                 .enable_all()
                 .build()
                 .expect("failed to start runtime");
-            if let Err(err) = runtime
-                .block_on(parallel_cli_runner_lib::ws_server::run_ws_server(
-                    args.port,
-                    args.auth_token,
-                ))
-            {
+            let result = runtime.block_on(parallel_cli_runner_lib::ws_server::run_ws_server(
+                args.port,
+                args.auth_token,
+                args.event_buffer,
+            ));
+            // Don't wait on connection tasks still blocked in git or pty work.
+            runtime.shutdown_timeout(std::time::Duration::from_secs(1));
+            if let Err(err) = result {
                 tracing::error!("ws server failed: {err}");
                 std::process::exit(1);
             }
+            tracing::info!("ws server stopped");
         }
         Ok(None) => {
             parallel_cli_runner_lib::run();
@@ -55,6 +72,7 @@ fn parse_ws_args() -> Result<Option<WsArgs>, String> {
     let args: Vec<String> = env::args().collect();
     let port = find_arg_value(&args, "--port");
     let token = find_arg_value(&args, "--auth-token");
+    let event_buffer = find_arg_value(&args, "--event-buffer");
 
     if port.is_none() && token.is_none() {
         return Ok(None);
@@ -68,9 +86,31 @@ fn parse_ws_args() -> Result<Option<WsArgs>, String> {
         .parse::<u16>()
         .map_err(|_| format!("invalid --port value: {port}"))?;
 
+    // Larger buffers use more memory but drop fewer events for slow clients
+    let event_buffer = match event_buffer {
+        Some(value) => value
+            .parse::<usize>()
+            .ok()
+            .filter(|size| *size > 0)
+            .ok_or_else(|| format!("invalid --event-buffer value: {value}"))?,
+        None => parallel_cli_runner_lib::ws_server::DEFAULT_EVENT_BUFFER,
+    };
+
+    let env = find_arg_values(&args, "--env")
+        .into_iter()
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) if parallel_cli_runner_lib::acp::is_valid_env_key(key) => {
+                Ok((key.to_string(), value.to_string()))
+            }
+            _ => Err(format!("invalid --env value (expected KEY=VALUE): {pair}")),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
     Ok(Some(WsArgs {
         port,
         auth_token: token,
+        event_buffer,
+        env,
     }))
 }
 
@@ -87,3 +127,18 @@ fn find_arg_value(args: &[String], name: &str) -> Option<String> {
             None
         })
 }
+
+/// Like `find_arg_value`, but collects every occurrence of a repeatable flag.
+fn find_arg_values(args: &[String], name: &str) -> Vec<String> {
+    let prefix = format!("{name}=");
+    let mut values = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == name {
+            values.extend(iter.next().cloned());
+        } else if let Some(value) = arg.strip_prefix(&prefix) {
+            values.push(value.to_string());
+        }
+    }
+    values
+}
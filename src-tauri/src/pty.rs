@@ -2,14 +2,15 @@ use std::{
     collections::HashMap,
     io::{Read, Write},
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
-#[cfg(not(target_os = "windows"))]
 use std::path::Path;
 
 use anyhow::Context;
 use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, State};
+use ts_rs::TS;
 use uuid::Uuid;
 
 use crate::command_error::CommandError;
@@ -18,6 +19,33 @@ use crate::utils::mutex_lock_or_panic;
 #[derive(Default, Clone)]
 pub struct PtyManager {
     pub sessions: Arc<Mutex<HashMap<Uuid, Arc<PtySession>>>>,
+    /// Number of clients attached to each session
+    attachments: Arc<Mutex<HashMap<Uuid, usize>>>,
+    /// Session groups by id. Finished groups are kept for `FINISHED_GROUP_TTL` so their status
+    /// can still be queried, then dropped so the id can be reused.
+    groups: Arc<Mutex<HashMap<String, SessionGroup>>>,
+}
+
+/// How long a completed group stays queryable through `group_status`.
+const FINISHED_GROUP_TTL: Duration = Duration::from_secs(10 * 60);
+
+struct SessionGroup {
+    members: Vec<GroupMemberStatus>,
+    /// Set once every member has been spawned; the group can't complete before that.
+    sealed: bool,
+    on_complete: GroupCompleteEmitter,
+    /// When the last member exited
+    completed_at: Option<Instant>,
+}
+
+impl SessionGroup {
+    fn status(&self, group_id: &str) -> SessionGroupStatus {
+        SessionGroupStatus {
+            group_id: group_id.to_string(),
+            complete: self.sealed && self.members.iter().all(|member| !member.running),
+            sessions: self.members.clone(),
+        }
+    }
 }
 
 impl PtyManager {
@@ -27,20 +55,160 @@ impl PtyManager {
     }
 
     pub fn remove(&self, id: &Uuid) -> Option<Arc<PtySession>> {
-        let mut guard = mutex_lock_or_panic(&self.sessions);
-        guard.remove(id)
+        let session = mutex_lock_or_panic(&self.sessions).remove(id);
+        mutex_lock_or_panic(&self.attachments).remove(id);
+        session
     }
 
     pub fn get(&self, id: &Uuid) -> Option<Arc<PtySession>> {
         let guard = mutex_lock_or_panic(&self.sessions);
         guard.get(id).cloned()
     }
+
+    /// Registers a client attachment and returns the new count, or `None` if the session is gone.
+    pub fn attach(&self, id: &Uuid) -> Option<usize> {
+        let mut attachments = mutex_lock_or_panic(&self.attachments);
+        self.get(id)?;
+        let count = attachments.entry(*id).or_insert(0);
+        *count += 1;
+        Some(*count)
+    }
+
+    /// Releases a client attachment and returns the remaining count, or `None` if untracked.
+    pub fn detach(&self, id: &Uuid) -> Option<usize> {
+        let mut attachments = mutex_lock_or_panic(&self.attachments);
+        let count = attachments.get_mut(id)?;
+        *count = count.saturating_sub(1);
+        Some(*count)
+    }
+
+    /// Kills the session if no client re-attached since it was last detached.
+    pub fn kill_if_detached(&self, id: &Uuid) -> bool {
+        let mut attachments = mutex_lock_or_panic(&self.attachments);
+        if attachments.get(id).copied().unwrap_or(0) > 0 {
+            return false;
+        }
+        attachments.remove(id);
+        let session = mutex_lock_or_panic(&self.sessions).remove(id);
+        drop(attachments);
+
+        match session {
+            Some(session) => {
+                let _ = session.kill();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Kills every session, attached or not, and returns how many were running.
+    pub fn kill_all(&self) -> usize {
+        let sessions: Vec<_> = mutex_lock_or_panic(&self.sessions).drain().collect();
+        mutex_lock_or_panic(&self.attachments).clear();
+        for (id, session) in &sessions {
+            if let Err(err) = session.kill() {
+                tracing::warn!("failed to kill session {id}: {err}");
+            }
+        }
+        sessions.len()
+    }
+
+    fn create_group(
+        &self,
+        group_id: &str,
+        on_complete: GroupCompleteEmitter,
+    ) -> Result<(), CommandError> {
+        let mut groups = mutex_lock_or_panic(&self.groups);
+        prune_finished_groups(&mut groups, Instant::now());
+        if groups.contains_key(group_id) {
+            return Err(CommandError::new(
+                "invalid_argument",
+                format!("session group {group_id} already exists"),
+            ));
+        }
+        groups.insert(
+            group_id.to_string(),
+            SessionGroup {
+                members: Vec::new(),
+                sealed: false,
+                on_complete,
+                completed_at: None,
+            },
+        );
+        Ok(())
+    }
+
+    fn add_group_member(&self, group_id: &str, id: Uuid) {
+        if let Some(group) = mutex_lock_or_panic(&self.groups).get_mut(group_id) {
+            group.members.push(GroupMemberStatus {
+                id: id.to_string(),
+                running: true,
+                exit_code: None,
+            });
+        }
+    }
+
+    fn remove_group(&self, group_id: &str) {
+        mutex_lock_or_panic(&self.groups).remove(group_id);
+    }
+
+    /// Marks the group as fully spawned, firing its completion if every member already exited.
+    fn seal_group(&self, group_id: &str) {
+        self.update_group(group_id, |group| group.sealed = true);
+    }
+
+    fn record_exit(&self, group_id: &str, id: Uuid, exit_code: Option<u32>) {
+        let id = id.to_string();
+        self.update_group(group_id, |group| {
+            if let Some(member) = group.members.iter_mut().find(|member| member.id == id) {
+                member.running = false;
+                member.exit_code = exit_code;
+            }
+        });
+    }
+
+    fn update_group(&self, group_id: &str, update: impl FnOnce(&mut SessionGroup)) {
+        let completed = {
+            let mut groups = mutex_lock_or_panic(&self.groups);
+            let Some(group) = groups.get_mut(group_id) else {
+                return;
+            };
+            let was_complete = group.status(group_id).complete;
+            update(group);
+            let status = group.status(group_id);
+            let just_completed = !was_complete && status.complete;
+            if just_completed {
+                group.completed_at = Some(Instant::now());
+            }
+            just_completed.then(|| (status, group.on_complete.clone()))
+        };
+        if let Some((status, on_complete)) = completed {
+            on_complete(status);
+        }
+    }
+
+    pub fn group_status(&self, group_id: &str) -> Option<SessionGroupStatus> {
+        let mut groups = mutex_lock_or_panic(&self.groups);
+        prune_finished_groups(&mut groups, Instant::now());
+        groups.get(group_id).map(|group| group.status(group_id))
+    }
+}
+
+/// Drops groups that completed more than `FINISHED_GROUP_TTL` before `now`.
+fn prune_finished_groups(groups: &mut HashMap<String, SessionGroup>, now: Instant) {
+    groups.retain(|_, group| {
+        group
+            .completed_at
+            .is_none_or(|at| now.duration_since(at) < FINISHED_GROUP_TTL)
+    });
 }
 
 pub struct PtySession {
     master: Mutex<Box<dyn MasterPty + Send>>,
     writer: Mutex<Box<dyn Write + Send>>,
     child: Mutex<Box<dyn Child + Send>>,
+    /// Program the session was started with, used to pick the quoting for `cd`
+    shell: String,
 }
 
 impl PtySession {
@@ -48,11 +216,13 @@ impl PtySession {
         master: Box<dyn MasterPty + Send>,
         writer: Box<dyn Write + Send>,
         child: Box<dyn Child + Send>,
+        shell: String,
     ) -> Self {
         Self {
             master: Mutex::new(master),
             writer: Mutex::new(writer),
             child: Mutex::new(child),
+            shell,
         }
     }
 
@@ -79,6 +249,61 @@ impl PtySession {
         let mut child = mutex_lock_or_panic(&self.child);
         child.kill().context("failed to kill child")
     }
+
+    /// Waits for the child to exit and returns its exit code. Polls rather than blocking
+    /// in `wait` so that `kill` and `signal` can still take the lock meanwhile.
+    pub fn wait(&self) -> anyhow::Result<u32> {
+        loop {
+            if let Some(status) = mutex_lock_or_panic(&self.child).try_wait()? {
+                return Ok(status.exit_code());
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    /// Sends `signal` to the terminal's foreground process group, i.e. the command
+    /// currently running in the shell, or the shell itself when it is at its prompt.
+    #[cfg(not(target_os = "windows"))]
+    pub fn signal(&self, signal: SessionSignal) -> anyhow::Result<()> {
+        let signo = match signal {
+            SessionSignal::Int => libc::SIGINT,
+            SessionSignal::Term => libc::SIGTERM,
+            SessionSignal::Kill => libc::SIGKILL,
+            SessionSignal::Hup => libc::SIGHUP,
+        };
+        let group = mutex_lock_or_panic(&self.master)
+            .process_group_leader()
+            .or_else(|| {
+                let pid = mutex_lock_or_panic(&self.child).process_id()?;
+                libc::pid_t::try_from(pid).ok()
+            })
+            .context("session has no running process")?;
+        // SAFETY: killpg has no memory-safety preconditions.
+        if unsafe { libc::killpg(group, signo) } != 0 {
+            return Err(std::io::Error::last_os_error()).context("failed to signal session");
+        }
+        Ok(())
+    }
+
+    /// Windows has no signals: `int` types Ctrl-C, which the console turns into a
+    /// CTRL_C_EVENT for the attached processes, and the others terminate the shell.
+    #[cfg(target_os = "windows")]
+    pub fn signal(&self, signal: SessionSignal) -> anyhow::Result<()> {
+        match signal {
+            SessionSignal::Int => self.write("\x03"),
+            SessionSignal::Term | SessionSignal::Kill | SessionSignal::Hup => self.kill(),
+        }
+    }
+}
+
+/// Signals that can be sent to a session with [`signal_session_with_manager`].
+#[derive(Clone, Copy, Debug, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionSignal {
+    Int,
+    Term,
+    Kill,
+    Hup,
 }
 
 #[derive(Clone, Serialize)]
@@ -89,13 +314,109 @@ pub struct SessionData {
 
 pub type SessionDataEmitter = Arc<dyn Fn(SessionData) + Send + Sync + 'static>;
 
+/// Exit state of one session in a group.
+#[derive(Clone, Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupMemberStatus {
+    pub id: String,
+    pub running: bool,
+    /// Set once the session exited, unless its exit status could not be read
+    pub exit_code: Option<u32>,
+}
+
+/// Sessions of a group in creation order. `complete` once every one of them has exited.
+#[derive(Clone, Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionGroupStatus {
+    pub group_id: String,
+    pub complete: bool,
+    pub sessions: Vec<GroupMemberStatus>,
+}
+
+pub type GroupCompleteEmitter = Arc<dyn Fn(SessionGroupStatus) + Send + Sync + 'static>;
+
+/// What to run in a new session. Unset fields fall back to the user's shell and the
+/// process's working directory, and `env` is added on top of the inherited environment.
+#[derive(Clone, Debug, Default, Deserialize, TS)]
+pub struct SessionSpec {
+    pub cmd: Option<String>,
+    pub cwd: Option<String>,
+    pub env: Option<HashMap<String, String>>,
+}
+
+/// Spawns a session and returns its id. With `attach`, the session starts with one client
+/// attachment, taken before its process can exit so a short-lived command is never missed.
 pub fn create_session_with_emitter(
     manager: &PtyManager,
     emitter: SessionDataEmitter,
     cmd: Option<String>,
     cwd: Option<String>,
+    attach: bool,
+) -> Result<String, CommandError> {
+    spawn_session(manager, emitter, SessionSpec { cmd, cwd, env: None }, None, attach)
+}
+
+/// Spawns one session per spec and returns their ids in the same order. Either all of
+/// them start or none do: if one fails, the sessions already started are killed.
+///
+/// With a `group` id, the sessions form a group whose status can be read with
+/// [`group_status_with_manager`], and `on_group_complete` fires once all of them exited.
+/// With `attach`, each session starts with one client attachment, as in
+/// [`create_session_with_emitter`].
+pub fn create_sessions_with_emitter(
+    manager: &PtyManager,
+    emitter: SessionDataEmitter,
+    specs: Vec<SessionSpec>,
+    group: Option<String>,
+    on_group_complete: GroupCompleteEmitter,
+    attach: bool,
+) -> Result<Vec<String>, CommandError> {
+    if let Some(group_id) = &group {
+        manager.create_group(group_id, on_group_complete)?;
+    }
+    let mut ids = Vec::with_capacity(specs.len());
+    for spec in specs {
+        match spawn_session(manager, emitter.clone(), spec, group.as_deref(), attach) {
+            Ok(id) => ids.push(id),
+            Err(err) => {
+                if let Some(group_id) = &group {
+                    manager.remove_group(group_id);
+                }
+                for id in ids {
+                    if let Some(session) = Uuid::parse_str(&id)
+                        .ok()
+                        .and_then(|session_id| manager.remove(&session_id))
+                    {
+                        let _ = session.kill();
+                    }
+                }
+                return Err(err);
+            }
+        }
+    }
+    if let Some(group_id) = &group {
+        manager.seal_group(group_id);
+    }
+    Ok(ids)
+}
+
+pub fn group_status_with_manager(
+    manager: &PtyManager,
+    group_id: String,
+) -> Result<SessionGroupStatus, CommandError> {
+    manager
+        .group_status(&group_id)
+        .ok_or_else(|| CommandError::new("not_found", "session group not found"))
+}
+
+fn spawn_session(
+    manager: &PtyManager,
+    emitter: SessionDataEmitter,
+    spec: SessionSpec,
+    group: Option<&str>,
+    attach: bool,
 ) -> Result<String, CommandError> {
-    let shell = cmd.unwrap_or_else(default_shell);
+    let shell = spec.cmd.unwrap_or_else(default_shell);
     let pty_system = native_pty_system();
     let pair = pty_system
         .openpty(PtySize {
@@ -115,7 +436,10 @@ pub fn create_session_with_emitter(
     command.env("TERM", "xterm-256color");
     #[cfg(not(target_os = "windows"))]
     apply_login_shell_args(&mut command, &shell);
-    if let Some(dir) = cwd {
+    for (key, value) in spec.env.unwrap_or_default() {
+        command.env(key, value);
+    }
+    if let Some(dir) = spec.cwd {
         command.cwd(dir);
     }
 
@@ -125,11 +449,22 @@ pub fn create_session_with_emitter(
         .map_err(CommandError::internal)?;
 
     let session_id = Uuid::new_v4();
-    manager.insert(
+    let session = Arc::new(PtySession::new(pair.master, writer, child, shell));
+    manager.insert(session_id, session.clone());
+    if attach {
+        manager.attach(&session_id);
+    }
+    if let Some(group_id) = group {
+        manager.add_group_member(group_id, session_id);
+    }
+    spawn_reader_loop(
+        manager.clone(),
+        session,
         session_id,
-        Arc::new(PtySession::new(pair.master, writer, child)),
+        group.map(str::to_string),
+        reader,
+        emitter,
     );
-    spawn_reader_loop(manager.clone(), session_id, reader, emitter);
 
     Ok(session_id.to_string())
 }
@@ -163,6 +498,44 @@ pub fn resize_session_with_manager(
     session.resize(cols, rows).map_err(CommandError::internal)
 }
 
+/// Changes the working directory of the session's shell by typing a `cd` into it. This
+/// only takes effect once the shell is back at its prompt.
+pub fn set_session_cwd_with_manager(
+    manager: &PtyManager,
+    id: String,
+    cwd: String,
+) -> Result<(), CommandError> {
+    let session_id = Uuid::parse_str(&id)
+        .map_err(|_| CommandError::new("invalid_argument", "invalid session id"))?;
+    let Some(session) = manager.get(&session_id) else {
+        return Err(CommandError::new("not_found", "session not found"));
+    };
+    if !Path::new(&cwd).is_dir() {
+        return Err(CommandError::new(
+            "not_found",
+            format!("{cwd} is not a directory"),
+        ));
+    }
+
+    session
+        .write(&cd_command(&session.shell, &cwd))
+        .map_err(CommandError::internal)
+}
+
+pub fn signal_session_with_manager(
+    manager: &PtyManager,
+    id: String,
+    signal: SessionSignal,
+) -> Result<(), CommandError> {
+    let session_id = Uuid::parse_str(&id)
+        .map_err(|_| CommandError::new("invalid_argument", "invalid session id"))?;
+    let Some(session) = manager.get(&session_id) else {
+        return Err(CommandError::new("not_found", "session not found"));
+    };
+
+    session.signal(signal).map_err(CommandError::internal)
+}
+
 pub fn kill_session_with_manager(
     manager: &PtyManager,
     id: String,
@@ -202,7 +575,32 @@ pub async fn create_session(
     let app_emitter: SessionDataEmitter = Arc::new(move |payload| {
         let _ = app.emit("session-data", payload);
     });
-    create_session_with_emitter(manager.inner(), app_emitter, cmd, cwd)
+    create_session_with_emitter(manager.inner(), app_emitter, cmd, cwd, false)
+}
+
+#[tauri::command]
+pub async fn create_sessions(
+    manager: State<'_, PtyManager>,
+    app: AppHandle,
+    specs: Vec<SessionSpec>,
+    group: Option<String>,
+) -> Result<Vec<String>, CommandError> {
+    let data_app = app.clone();
+    let app_emitter: SessionDataEmitter = Arc::new(move |payload| {
+        let _ = data_app.emit("session-data", payload);
+    });
+    let group_emitter: GroupCompleteEmitter = Arc::new(move |payload| {
+        let _ = app.emit("group-complete", payload);
+    });
+    create_sessions_with_emitter(manager.inner(), app_emitter, specs, group, group_emitter, false)
+}
+
+#[tauri::command]
+pub async fn group_status(
+    manager: State<'_, PtyManager>,
+    group_id: String,
+) -> Result<SessionGroupStatus, CommandError> {
+    group_status_with_manager(manager.inner(), group_id)
 }
 
 #[tauri::command]
@@ -214,6 +612,15 @@ pub async fn write_to_session(
     write_to_session_with_manager(manager.inner(), id, data)
 }
 
+#[tauri::command]
+pub async fn set_session_cwd(
+    manager: State<'_, PtyManager>,
+    id: String,
+    cwd: String,
+) -> Result<(), CommandError> {
+    set_session_cwd_with_manager(manager.inner(), id, cwd)
+}
+
 #[tauri::command]
 pub async fn resize_session(
     manager: State<'_, PtyManager>,
@@ -229,6 +636,15 @@ pub async fn kill_session(manager: State<'_, PtyManager>, id: String) -> Result<
     kill_session_with_manager(manager.inner(), id)
 }
 
+#[tauri::command]
+pub async fn signal_session(
+    manager: State<'_, PtyManager>,
+    id: String,
+    signal: SessionSignal,
+) -> Result<(), CommandError> {
+    signal_session_with_manager(manager.inner(), id, signal)
+}
+
 #[tauri::command]
 pub async fn broadcast_line(
     manager: State<'_, PtyManager>,
@@ -240,7 +656,9 @@ pub async fn broadcast_line(
 
 fn spawn_reader_loop(
     manager: PtyManager,
+    session: Arc<PtySession>,
     session_id: Uuid,
+    group: Option<String>,
     mut reader: Box<dyn Read + Send>,
     emitter: SessionDataEmitter,
 ) {
@@ -298,6 +716,10 @@ fn spawn_reader_loop(
 
         // Ensure sessions don't leak when the underlying process exits.
         let _ = manager.remove(&session_id);
+        if let Some(group_id) = group {
+            let exit_code = session.wait().ok();
+            manager.record_exit(&group_id, session_id, exit_code);
+        }
     });
 }
 
@@ -313,6 +735,24 @@ fn default_shell() -> String {
     }
 }
 
+/// Builds the line that changes `shell`'s directory to `cwd`, quoted so that spaces,
+/// quotes and other metacharacters in the path are taken literally. It ends with `\r`,
+/// which is what the Enter key sends.
+fn cd_command(shell: &str, cwd: &str) -> String {
+    let shell_name = Path::new(shell)
+        .file_stem()
+        .and_then(|name| name.to_str())
+        .unwrap_or(shell);
+    match shell_name.to_ascii_lowercase().as_str() {
+        // Windows paths can't contain `"`, so double quotes are enough for cmd
+        "cmd" => format!("cd /d \"{cwd}\"\r"),
+        "powershell" | "pwsh" => {
+            format!("Set-Location -LiteralPath '{}'\r", cwd.replace('\'', "''"))
+        }
+        _ => format!("cd '{}'\r", cwd.replace('\'', r"'\''")),
+    }
+}
+
 fn apply_login_shell_args(
     #[cfg(not(target_os = "windows"))] command: &mut CommandBuilder,
     #[cfg(not(target_os = "windows"))] shell: &str,
@@ -328,3 +768,83 @@ fn apply_login_shell_args(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use uuid::Uuid;
+
+    use super::{
+        cd_command, prune_finished_groups, GroupCompleteEmitter, PtyManager, FINISHED_GROUP_TTL,
+    };
+
+    #[test]
+    fn group_completes_once_every_member_has_exited() {
+        let manager = PtyManager::default();
+        let completions = Arc::new(AtomicUsize::new(0));
+        let counter = completions.clone();
+        let on_complete: GroupCompleteEmitter = Arc::new(move |status| {
+            assert!(status.complete);
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+        manager.create_group("build", on_complete).unwrap();
+        assert!(manager.create_group("build", Arc::new(|_| {})).is_err());
+        manager.add_group_member("build", first);
+        manager.record_exit("build", first, Some(0));
+        manager.add_group_member("build", second);
+        manager.seal_group("build");
+        assert_eq!(completions.load(Ordering::SeqCst), 0);
+
+        manager.record_exit("build", second, Some(2));
+        assert_eq!(completions.load(Ordering::SeqCst), 1);
+        let status = manager.group_status("build").unwrap();
+        let codes: Vec<_> = status.sessions.iter().map(|member| member.exit_code).collect();
+        assert_eq!(codes, [Some(0), Some(2)]);
+    }
+
+    #[test]
+    fn finished_groups_are_pruned_after_ttl() {
+        let manager = PtyManager::default();
+        let (done, running) = (Uuid::new_v4(), Uuid::new_v4());
+        for (group_id, id) in [("done", done), ("running", running)] {
+            manager.create_group(group_id, Arc::new(|_| {})).unwrap();
+            manager.add_group_member(group_id, id);
+            manager.seal_group(group_id);
+        }
+        manager.record_exit("done", done, Some(0));
+
+        let mut groups = manager.groups.lock().unwrap();
+        prune_finished_groups(&mut groups, Instant::now());
+        assert_eq!(groups.len(), 2);
+        let later = Instant::now() + FINISHED_GROUP_TTL + Duration::from_secs(1);
+        prune_finished_groups(&mut groups, later);
+        assert!(!groups.contains_key("done"));
+        assert!(groups.contains_key("running"));
+        drop(groups);
+
+        manager.create_group("done", Arc::new(|_| {})).unwrap();
+    }
+
+    #[test]
+    fn cd_command_quotes_paths_for_posix_shells() {
+        assert_eq!(
+            cd_command("/bin/zsh", "/tmp/it's a repo"),
+            "cd '/tmp/it'\\''s a repo'\r"
+        );
+        assert_eq!(cd_command("/bin/bash", "/tmp/$HOME \"x\""), "cd '/tmp/$HOME \"x\"'\r");
+    }
+
+    #[test]
+    fn cd_command_quotes_paths_for_windows_shells() {
+        assert_eq!(cd_command("cmd.exe", r"C:\My Repos\a"), "cd /d \"C:\\My Repos\\a\"\r");
+        assert_eq!(
+            cd_command("pwsh.exe", r"C:\it's"),
+            "Set-Location -LiteralPath 'C:\\it''s'\r"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+use arboard::Clipboard;
+
+use crate::command_error::CommandError;
+use crate::utils::mutex_lock_or_panic;
+
+/// Kept open for the life of the process: on X11 and Wayland the copied text is only
+/// served to other applications while its owning clipboard handle is alive.
+static CLIPBOARD: OnceLock<Mutex<Clipboard>> = OnceLock::new();
+
+/// Shows `path` selected in the platform file manager, instead of opening it with its
+/// default application.
+pub fn reveal_path(path: &str) -> Result<(), CommandError> {
+    let path = std::path::absolute(path).map_err(CommandError::internal)?;
+    if !path.exists() {
+        return Err(CommandError::new(
+            "not_found",
+            format!("{} does not exist", path.display()),
+        ));
+    }
+    reveal(&path)
+}
+
+#[cfg(target_os = "macos")]
+fn reveal(path: &Path) -> Result<(), CommandError> {
+    let status = Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .status()
+        .map_err(CommandError::internal)?;
+    if !status.success() {
+        return Err(CommandError::internal(format!("open -R exited with {status}")));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn reveal(path: &Path) -> Result<(), CommandError> {
+    use std::os::windows::process::CommandExt;
+
+    // explorer wants `/select,"<path>"` as one unescaped argument, and its exit code is
+    // non-zero even on success, so only a failure to launch it is reported.
+    Command::new("explorer")
+        .raw_arg(format!("/select,\"{}\"", path.display()))
+        .spawn()
+        .map_err(CommandError::internal)?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn reveal(path: &Path) -> Result<(), CommandError> {
+    // Most desktop file managers implement the freedesktop FileManager1 interface. Without
+    // one, fall back to opening the containing directory.
+    if let Ok(uri) = tauri::Url::from_file_path(path) {
+        // dbus-send splits array items on commas
+        let uri = uri.as_str().replace(',', "%2C");
+        let shown = Command::new("dbus-send")
+            .args([
+                "--session",
+                "--print-reply",
+                "--dest=org.freedesktop.FileManager1",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+            ])
+            .arg(format!("array:string:{uri}"))
+            .arg("string:")
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if shown {
+            return Ok(());
+        }
+    }
+    open::that(path.parent().unwrap_or(path)).map_err(CommandError::internal)
+}
+
+/// Replaces the system clipboard contents with `text`.
+pub fn set_clipboard(text: &str) -> Result<(), CommandError> {
+    let clipboard = match CLIPBOARD.get() {
+        Some(clipboard) => clipboard,
+        None => {
+            let clipboard = Clipboard::new().map_err(CommandError::internal)?;
+            CLIPBOARD.get_or_init(|| Mutex::new(clipboard))
+        }
+    };
+    mutex_lock_or_panic(clipboard)
+        .set_text(text)
+        .map_err(CommandError::internal)
+}
@@ -1,445 +1,1155 @@
 use agent_client_protocol::{
-    PermissionOptionId, RequestPermissionOutcome, SelectedPermissionOutcome,
+    PermissionOptionId, RequestPermissionOutcome, SelectedPermissionOutcome, StopReason,
 };
 use serde::Deserialize;
 use serde_json::Value;
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::broadcast;
 use uuid::Uuid;
 
 use crate::command_error::CommandError;
 use crate::acp;
+use crate::logging;
 use crate::git::{self, DiffRequestDto};
+use crate::shell;
 use crate::utils;
 use crate::pty::{
-    broadcast_line_with_manager, create_session_with_emitter, kill_session_with_manager,
-    resize_session_with_manager, write_to_session_with_manager, SessionDataEmitter,
+    broadcast_line_with_manager, create_session_with_emitter, create_sessions_with_emitter,
+    group_status_with_manager, kill_session_with_manager, resize_session_with_manager,
+    set_session_cwd_with_manager, signal_session_with_manager, write_to_session_with_manager,
+    GroupCompleteEmitter, PtyManager, SessionDataEmitter,
 };
 use crate::acp::types::AcpAgentConfig;
+use crate::utils::mutex_lock_or_panic;
 
 use super::types::*;
 
-pub async fn handle_request(
-    method: String,
-    params: Option<Value>,
-    state: WsState,
-) -> Result<Value, CommandError> {
-    match method.as_str() {
-        "create_session" => {
-            let params: CreateSessionParams = parse_params(params)?;
-            let manager = state.manager.clone();
-            let events = state.events.clone();
-            let session_id = run_blocking(move || {
-                let emitter = session_emitter(events);
-                create_session_with_emitter(&manager, emitter, params.cmd, params.cwd)
-            })
-            .await?;
-            to_value(session_id)
+/// How long a session with no attached clients survives before it is killed.
+const SESSION_DETACH_GRACE: Duration = Duration::from_secs(30);
+
+/// Lines returned by `tail_log` when the client does not ask for a count
+const DEFAULT_TAIL_LOG_LINES: usize = 200;
+
+/// Defines `handle_request` and `WS_METHODS` from a single list of method arms so the
+/// methods reported by `server_info` can't drift from what is actually dispatched.
+macro_rules! ws_methods {
+    ($params:ident, $state:ident; $($name:literal => $body:block)*) => {
+        /// Every method `handle_request` dispatches, in declaration order.
+        pub const WS_METHODS: &[&str] = &[$($name),*];
+
+        pub async fn handle_request(
+            method: String,
+            $params: Option<Value>,
+            $state: WsState,
+        ) -> Result<Value, CommandError> {
+            match method.as_str() {
+                $($name => $body)*
+                _ => Err(CommandError::new("not_found", "unknown method")),
+            }
         }
-        "write_to_session" => {
-            let params: WriteSessionParams = parse_params(params)?;
-            let manager = state.manager.clone();
-            run_blocking(move || write_to_session_with_manager(&manager, params.id, params.data))
-                .await?;
-            Ok(Value::Null)
+    };
+}
+
+ws_methods! {
+    params, state;
+
+    "server_info" => {
+        to_value(ServerInfo {
+            version: env!("CARGO_PKG_VERSION"),
+            methods: WS_METHODS.iter().map(|method| method.to_string()).collect(),
+            protocol_version: PROTOCOL_VERSION,
+        })
+    }
+    "set_log_level" => {
+        let params: SetLogLevelParams = parse_params(params)?;
+        logging::set_log_level(&params.level)?;
+        Ok(Value::Null)
+    }
+    "get_log_path" => {
+        to_value(logging::log_file_path())
+    }
+    "tail_log" => {
+        let params: TailLogParams = parse_params(params)?;
+        let lines = params.lines.unwrap_or(DEFAULT_TAIL_LOG_LINES);
+        let result =
+            run_blocking(move || logging::tail_log(lines).map_err(CommandError::from)).await?;
+        to_value(result)
+    }
+    "stream_log" => {
+        let params: StreamLogParams = parse_params(params)?;
+        set_client_log_stream(&state, params.enabled);
+        Ok(Value::Null)
+    }
+    "create_session" => {
+        let params: CreateSessionParams = parse_params(params)?;
+        let manager = state.manager.clone();
+        let events = state.events.clone();
+        let session_id = run_blocking(move || {
+            let emitter = session_emitter(events);
+            create_session_with_emitter(&manager, emitter, params.cmd, params.cwd, true)
+        })
+        .await?;
+        // Already attached during creation; the session may even have exited by now
+        mutex_lock_or_panic(&state.attached_sessions).insert(parse_uuid(&session_id)?);
+        to_value(session_id)
+    }
+    "create_sessions" => {
+        let params: CreateSessionsParams = parse_params(params)?;
+        let manager = state.manager.clone();
+        let events = state.events.clone();
+        let session_ids = run_blocking(move || {
+            let emitter = session_emitter(events.clone());
+            let on_group_complete: GroupCompleteEmitter = Arc::new(move |payload| {
+                emit_event(&events, "group-complete", payload);
+            });
+            create_sessions_with_emitter(
+                &manager,
+                emitter,
+                params.specs,
+                params.group,
+                on_group_complete,
+                true,
+            )
+        })
+        .await?;
+        // Already attached during creation, which kills every session if any failed to start
+        mutex_lock_or_panic(&state.attached_sessions)
+            .extend(session_ids.iter().filter_map(|id| Uuid::parse_str(id).ok()));
+        to_value(session_ids)
+    }
+    "group_status" => {
+        let params: GroupStatusParams = parse_params(params)?;
+        to_value(group_status_with_manager(&state.manager, params.group_id)?)
+    }
+    "attach_session" => {
+        let params: SessionIdParams = parse_params(params)?;
+        attach_client_session(&state, parse_uuid(&params.id)?)?;
+        Ok(Value::Null)
+    }
+    "detach_session" => {
+        let params: SessionIdParams = parse_params(params)?;
+        let session_id = parse_uuid(&params.id)?;
+        if mutex_lock_or_panic(&state.attached_sessions).remove(&session_id) {
+            release_session(&state.manager, session_id);
         }
-        "resize_session" => {
-            let params: ResizeSessionParams = parse_params(params)?;
-            let manager = state.manager.clone();
-            run_blocking(move || {
-                resize_session_with_manager(&manager, params.id, params.cols, params.rows)
-            })
+        Ok(Value::Null)
+    }
+    "write_to_session" => {
+        let params: WriteSessionParams = parse_params(params)?;
+        let manager = state.manager.clone();
+        run_blocking(move || write_to_session_with_manager(&manager, params.id, params.data))
             .await?;
-            Ok(Value::Null)
-        }
-        "kill_session" => {
-            let params: SessionIdParams = parse_params(params)?;
-            let manager = state.manager.clone();
-            run_blocking(move || kill_session_with_manager(&manager, params.id)).await?;
-            Ok(Value::Null)
-        }
-        "broadcast_line" => {
-            let params: BroadcastLineParams = parse_params(params)?;
-            let manager = state.manager.clone();
-            run_blocking(move || {
-                broadcast_line_with_manager(&manager, params.session_ids, params.line)
-            })
+        Ok(Value::Null)
+    }
+    "set_session_cwd" => {
+        let params: SetSessionCwdParams = parse_params(params)?;
+        let manager = state.manager.clone();
+        run_blocking(move || set_session_cwd_with_manager(&manager, params.id, params.cwd))
             .await?;
-            Ok(Value::Null)
-        }
-        "acp_connect" => {
-            let params: AcpAgentConfig = parse_params(params)?;
-            let manager = state.acp.clone();
-            let config = acp::normalize_agent_config(params);
-            let info = manager.connect(config).await.map_err(CommandError::internal)?;
-            to_value(info)
-        }
-        "acp_disconnect" => {
-            let params: AcpConnectionIdParams = parse_params(params)?;
-            let connection_id = parse_uuid(&params.id)?;
-            let manager = state.acp.clone();
-            if manager.get_info(connection_id).is_none() {
-                return Err(CommandError::new("not_found", "acp connection not found"));
-            }
-            manager
-                .disconnect(connection_id)
-                .await
-                .map_err(CommandError::internal)?;
-            Ok(Value::Null)
-        }
-        "acp_session_new" => {
-            let params: AcpSessionNewParams = parse_params(params)?;
-            let connection_id = parse_uuid(&params.connection_id)?;
-            let mcp_servers = params.mcp_servers.unwrap_or_default();
-            let manager = state.acp.clone();
-            let response = manager
-                .new_session(connection_id, params.cwd, mcp_servers)
-                .await
-                .map_err(CommandError::internal)?;
-            to_value(response.session_id.to_string())
-        }
-        "acp_session_load" => {
-            let params: AcpSessionLoadParams = parse_params(params)?;
-            let connection_id = parse_uuid(&params.connection_id)?;
-            let mcp_servers = params.mcp_servers.unwrap_or_default();
-            let manager = state.acp.clone();
-            let response = manager
-                .load_session(connection_id, params.session_id, params.cwd, mcp_servers)
-                .await
-                .map_err(CommandError::internal)?;
-            to_value(response)
+        Ok(Value::Null)
+    }
+    "resize_session" => {
+        let params: ResizeSessionParams = parse_params(params)?;
+        let manager = state.manager.clone();
+        run_blocking(move || {
+            resize_session_with_manager(&manager, params.id, params.cols, params.rows)
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "kill_session" => {
+        let params: SessionIdParams = parse_params(params)?;
+        if let Ok(session_id) = Uuid::parse_str(&params.id) {
+            mutex_lock_or_panic(&state.attached_sessions).remove(&session_id);
         }
-        "acp_session_prompt" => {
-            let params: AcpSessionPromptParams = parse_params(params)?;
-            let manager = state.acp.clone();
-            manager
-                .prompt(params.session_id, params.prompt)
-                .await
-                .map_err(CommandError::internal)?;
-            Ok(Value::Null)
+        let manager = state.manager.clone();
+        run_blocking(move || kill_session_with_manager(&manager, params.id)).await?;
+        Ok(Value::Null)
+    }
+    "signal_session" => {
+        let params: SignalSessionParams = parse_params(params)?;
+        let manager = state.manager.clone();
+        run_blocking(move || signal_session_with_manager(&manager, params.id, params.signal))
+            .await?;
+        Ok(Value::Null)
+    }
+    "broadcast_line" => {
+        let params: BroadcastLineParams = parse_params(params)?;
+        let manager = state.manager.clone();
+        run_blocking(move || {
+            broadcast_line_with_manager(&manager, params.session_ids, params.line)
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "acp_connect" => {
+        let params: AcpAgentConfig = parse_params(params)?;
+        let manager = state.acp.clone();
+        let config = acp::normalize_agent_config(params);
+        // Recorded before awaiting so closing the client mid-connect still stops the agent
+        let connection_id = Uuid::new_v4();
+        mutex_lock_or_panic(&state.acp_connections).insert(connection_id);
+        let info = manager.connect_with_id(connection_id, config).await.map_err(|err| {
+            mutex_lock_or_panic(&state.acp_connections).remove(&connection_id);
+            CommandError::internal(err)
+        })?;
+        to_value(info)
+    }
+    "acp_disconnect" => {
+        let params: AcpConnectionIdParams = parse_params(params)?;
+        let connection_id = parse_uuid(&params.id)?;
+        let manager = state.acp.clone();
+        if manager.get_info(connection_id).is_none() {
+            return Err(CommandError::new("not_found", "acp connection not found"));
         }
-        "acp_session_cancel" => {
-            let params: AcpSessionCancelParams = parse_params(params)?;
-            let manager = state.acp.clone();
-            manager
-                .cancel(params.session_id)
-                .await
-                .map_err(CommandError::internal)?;
-            Ok(Value::Null)
+        mutex_lock_or_panic(&state.acp_connections).remove(&connection_id);
+        manager
+            .disconnect(connection_id)
+            .await
+            .map_err(CommandError::internal)?;
+        Ok(Value::Null)
+    }
+    "acp_ping" => {
+        let params: AcpConnectionIdParams = parse_params(params)?;
+        let connection_id = parse_uuid(&params.id)?;
+        let alive = state
+            .acp
+            .ping_connection(connection_id)
+            .await
+            .map_err(|err| CommandError::new("not_found", err.to_string()))?;
+        to_value(alive)
+    }
+    "acp_get_stderr" => {
+        let params: AcpConnectionIdParams = parse_params(params)?;
+        let connection_id = parse_uuid(&params.id)?;
+        if state.acp.get_info(connection_id).is_none() {
+            return Err(CommandError::new("not_found", "acp connection not found"));
         }
-        "acp_permission_reply" => {
-            let params: AcpPermissionReplyParams = parse_params(params)?;
-            let outcome = match params.outcome {
-                AcpPermissionOutcomeDto::Cancelled => RequestPermissionOutcome::Cancelled,
-                AcpPermissionOutcomeDto::Selected { option_id } => {
-                    RequestPermissionOutcome::Selected(SelectedPermissionOutcome::new(
-                        PermissionOptionId::new(option_id),
-                    ))
-                }
-            };
-            state
-                .acp
-                .reply_permission(params.request_id, outcome)
-                .map_err(CommandError::internal)?;
-            Ok(Value::Null)
+        to_value(state.acp.get_agent_stderr(connection_id))
+    }
+    "acp_ext_method" => {
+        let params: AcpExtMethodParams = parse_params(params)?;
+        let connection_id = parse_uuid(&params.connection_id)?;
+        let response = state
+            .acp
+            .ext_method(connection_id, params.method, params.params.unwrap_or_default())
+            .await
+            .map_err(CommandError::internal)?;
+        Ok(response)
+    }
+    "acp_validate_mcp" => {
+        let params: AcpValidateMcpParams = parse_params(params)?;
+        acp::validate_mcp_servers(&params.mcp_servers)
+            .map_err(|err| CommandError::new("invalid_argument", err))?;
+        Ok(Value::Null)
+    }
+    "acp_session_new" => {
+        let params: AcpSessionNewParams = parse_params(params)?;
+        let connection_id = parse_uuid(&params.connection_id)?;
+        let mcp_servers = params.mcp_servers.unwrap_or_default();
+        let manager = state.acp.clone();
+        let response = manager
+            .new_session(connection_id, params.cwd, mcp_servers)
+            .await
+            .map_err(CommandError::internal)?;
+        to_value(response.session_id.to_string())
+    }
+    "acp_session_load" => {
+        let params: AcpSessionLoadParams = parse_params(params)?;
+        let connection_id = parse_uuid(&params.connection_id)?;
+        let mcp_servers = params.mcp_servers.unwrap_or_default();
+        let manager = state.acp.clone();
+        let response = manager
+            .load_session(connection_id, params.session_id, params.cwd, mcp_servers)
+            .await
+            .map_err(CommandError::internal)?;
+        to_value(response)
+    }
+    "acp_session_prompt" => {
+        let params: AcpSessionPromptParams = parse_params(params)?;
+        let manager = state.acp.clone();
+        let prompt_id = params
+            .prompt_id
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let response = manager
+            .prompt(params.session_id, params.prompt, prompt_id.clone())
+            .await
+            .map_err(CommandError::internal)?;
+        if response.stop_reason == StopReason::Cancelled {
+            return Err(CommandError::cancelled());
         }
-        "git_detect_repo" => {
-            let params: CwdParams = parse_params(params)?;
-            let result = run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| {
-                    git::detect_repo(path).map(|opt| opt.map(|p| p.to_string_lossy().to_string()))
+        to_value(prompt_id)
+    }
+    "acp_session_cancel" => {
+        let params: AcpSessionCancelParams = parse_params(params)?;
+        let manager = state.acp.clone();
+        manager
+            .cancel(params.session_id)
+            .await
+            .map_err(CommandError::internal)?;
+        Ok(Value::Null)
+    }
+    "acp_permission_reply" => {
+        let params: AcpPermissionReplyParams = parse_params(params)?;
+        let outcome = match params.outcome {
+            AcpPermissionOutcomeDto::Cancelled => RequestPermissionOutcome::Cancelled,
+            AcpPermissionOutcomeDto::Selected { option_id } => {
+                RequestPermissionOutcome::Selected(SelectedPermissionOutcome::new(
+                    PermissionOptionId::new(option_id),
+                ))
+            }
+        };
+        state
+            .acp
+            .reply_permission(params.request_id, outcome)
+            .map_err(CommandError::internal)?;
+        Ok(Value::Null)
+    }
+    "git_detect_repo" => {
+        let params: CwdParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::detect_repo(path).map(|opt| opt.map(|p| p.to_string_lossy().to_string()))
+            })
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_scan_repos" => {
+        let params: GitScanReposParams = parse_params(params)?;
+        let events = state.events.clone();
+        let cancel = state.active_scan.begin();
+        let cancelled = cancel.clone();
+        let follow_symlinks = params.follow_symlinks.unwrap_or(false);
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::scan_repos(path, follow_symlinks, &cancel, |p| {
+                    emit_event(&events, "scan-progress", p)
                 })
             })
-            .await?;
-            to_value(result)
+        })
+        .await?;
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(CommandError::cancelled());
         }
-        "git_scan_repos" => {
-            let params: CwdParams = parse_params(params)?;
-            let events = state.events.clone();
-            let result = run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| {
-                    git::scan_repos(path, |p| emit_event(&events, "scan-progress", p))
+        to_value(result)
+    }
+    "git_has_any_repo" => {
+        let params: GitHasAnyRepoParams = parse_params(params)?;
+        let max_checks = params.max_checks.unwrap_or(git::DEFAULT_REPO_PROBE_CHECKS);
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::has_any_repo(path, max_checks))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_cancel_scan" => {
+        state.active_scan.cancel();
+        Ok(Value::Null)
+    }
+    "git_status" => {
+        let params: CwdParams = parse_params(params)?;
+        let result = run_blocking(move || utils::with_cwd(params.cwd, git::status)).await?;
+        to_value(result)
+    }
+    "git_repo_overview" => {
+        let params: CwdParams = parse_params(params)?;
+        let result = run_blocking(move || utils::with_cwd(params.cwd, git::repo_overview)).await?;
+        to_value(result)
+    }
+    "git_watch_repo" => {
+        let params: CwdParams = parse_params(params)?;
+        let repo_path = git::canonicalize_path(Path::new(&params.cwd));
+        let events = state.events.clone();
+        let payload_path = repo_path.to_string_lossy().to_string();
+        add_watcher(&state.repo_watchers, &state.watched_repos, repo_path, move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::watch_repo(path, move || {
+                    let payload = RepoChangedPayload {
+                        repo_path: payload_path.clone(),
+                    };
+                    emit_event(&events, "repo-changed", payload);
                 })
             })
-            .await?;
-            to_value(result)
-        }
-        "git_status" => {
-            let params: CwdParams = parse_params(params)?;
-            let result = run_blocking(move || utils::with_cwd(params.cwd, git::status)).await?;
-            to_value(result)
-        }
-        "git_diff" => {
-            let params: GitDiffParams = parse_params(params)?;
-            let result = run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::diff(path, &params.pathspecs))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_unwatch_repo" => {
+        let params: CwdParams = parse_params(params)?;
+        let repo_path = git::canonicalize_path(Path::new(&params.cwd));
+        remove_watcher(&state.repo_watchers, &state.watched_repos, &repo_path);
+        Ok(Value::Null)
+    }
+    "git_watch_scan_root" => {
+        let params: CwdParams = parse_params(params)?;
+        let root_path = git::canonicalize_path(Path::new(&params.cwd));
+        let events = state.events.clone();
+        let payload_path = root_path.to_string_lossy().to_string();
+        add_watcher(&state.scan_root_watchers, &state.watched_scan_roots, root_path, move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::watch_scan_root(path, move || {
+                    let payload = ScanRootChangedPayload {
+                        root_path: payload_path.clone(),
+                    };
+                    emit_event(&events, "scan-root-changed", payload);
+                })
             })
-            .await?;
-            to_value(result)
-        }
-        "git_unified_diff" => {
-            let params: DiffRequestDto = parse_params(params)?;
-            let result = run_blocking(move || {
-                git::get_unified_diff(params).map_err(CommandError::from)
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_unwatch_scan_root" => {
+        let params: CwdParams = parse_params(params)?;
+        let root_path = git::canonicalize_path(Path::new(&params.cwd));
+        remove_watcher(&state.scan_root_watchers, &state.watched_scan_roots, &root_path);
+        Ok(Value::Null)
+    }
+    "git_is_clean" => {
+        let params: CwdParams = parse_params(params)?;
+        let result = run_blocking(move || utils::with_cwd(params.cwd, git::is_clean)).await?;
+        to_value(result)
+    }
+    "git_operation_state" => {
+        let params: CwdParams = parse_params(params)?;
+        let result =
+            run_blocking(move || utils::with_cwd(params.cwd, git::operation_state)).await?;
+        to_value(result)
+    }
+    "git_diff" => {
+        let params: GitDiffParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::diff(path, &params.pathspecs))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_unified_diff" => {
+        let params: DiffRequestDto = parse_params(params)?;
+        let result = run_blocking(move || {
+            git::get_unified_diff(params).map_err(CommandError::from)
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_repo_stats" => {
+        let params: CwdParams = parse_params(params)?;
+        let result = run_blocking(move || utils::with_cwd(params.cwd, git::repo_stats)).await?;
+        to_value(result)
+    }
+    "git_gc" => {
+        let params: GitGcParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::gc(path, params.aggressive))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_list_branches" => {
+        let params: CwdParams = parse_params(params)?;
+        let result = run_blocking(move || utils::with_cwd(params.cwd, git::list_branches)).await?;
+        to_value(result)
+    }
+    "git_list_remote_branches" => {
+        let params: CwdParams = parse_params(params)?;
+        let result =
+            run_blocking(move || utils::with_cwd(params.cwd, git::list_remote_branches)).await?;
+        to_value(result)
+    }
+    "git_branch_sync_status" => {
+        let params: GitBranchSyncStatusParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::branch_sync_status(path, &params.branch))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_list_commits" => {
+        let params: GitListCommitsParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::list_commits(path, params.limit, params.skip))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_list_commits_paged" => {
+        let params: GitListCommitsParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::list_commits_paged(path, params.limit, params.skip)
             })
-            .await?;
-            to_value(result)
-        }
-        "git_list_branches" => {
-            let params: CwdParams = parse_params(params)?;
-            let result = run_blocking(move || utils::with_cwd(params.cwd, git::list_branches)).await?;
-            to_value(result)
-        }
-        "git_list_remote_branches" => {
-            let params: CwdParams = parse_params(params)?;
-            let result =
-                run_blocking(move || utils::with_cwd(params.cwd, git::list_remote_branches)).await?;
-            to_value(result)
-        }
-        "git_list_commits" => {
-            let params: GitListCommitsParams = parse_params(params)?;
-            let result = run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::list_commits(path, params.limit, params.skip))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_list_commits_range" => {
+        let params: GitListCommitsRangeParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::list_commits_range(path, &params.include_branch, &params.exclude_branch)
             })
-            .await?;
-            to_value(result)
-        }
-        "git_list_commits_range" => {
-            let params: GitListCommitsRangeParams = parse_params(params)?;
-            let result = run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| {
-                    git::list_commits_range(path, &params.include_branch, &params.exclude_branch)
-                })
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_last_commit_for_path" => {
+        let params: GitLastCommitForPathParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::last_commit_for_path(path, &params.path, params.rev)
             })
-            .await?;
-            to_value(result)
-        }
-        "git_list_worktrees" => {
-            let params: CwdParams = parse_params(params)?;
-            let result = run_blocking(move || utils::with_cwd(params.cwd, git::list_worktrees)).await?;
-            to_value(result)
-        }
-        "git_list_remotes" => {
-            let params: CwdParams = parse_params(params)?;
-            let result = run_blocking(move || utils::with_cwd(params.cwd, git::list_remotes)).await?;
-            to_value(result)
-        }
-        "git_list_submodules" => {
-            let params: CwdParams = parse_params(params)?;
-            let result = run_blocking(move || utils::with_cwd(params.cwd, git::list_submodules)).await?;
-            to_value(result)
-        }
-        "git_list_stashes" => {
-            let params: CwdParams = parse_params(params)?;
-            let result = run_blocking(move || utils::with_cwd(params.cwd, git::list_stashes)).await?;
-            to_value(result)
-        }
-        "git_list_tags" => {
-            let params: GitListTagsParams = parse_params(params)?;
-            let result = run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::list_tags(path, params.limit, params.skip))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_log_for_path" => {
+        let params: GitLogForPathParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::log_for_path(path, &params.path, params.limit, params.follow.unwrap_or(false))
             })
-            .await?;
-            to_value(result)
-        }
-        "git_apply_stash" => {
-            let params: GitApplyStashParams = parse_params(params)?;
-            run_blocking(move || utils::with_cwd(params.cwd, |path| git::apply_stash(path, params.index)))
-                .await?;
-            Ok(Value::Null)
-        }
-        "git_drop_stash" => {
-            let params: GitApplyStashParams = parse_params(params)?;
-            run_blocking(move || utils::with_cwd(params.cwd, |path| git::drop_stash(path, params.index)))
-                .await?;
-            Ok(Value::Null)
-        }
-        "git_pull" => {
-            let params: CwdParams = parse_params(params)?;
-            run_blocking(move || utils::with_cwd(params.cwd, git::pull)).await?;
-            Ok(Value::Null)
-        }
-        "git_push" => {
-            let params: GitPushParams = parse_params(params)?;
-            run_blocking(move || utils::with_cwd(params.cwd, |path| git::push(path, params.force))).await?;
-            Ok(Value::Null)
-        }
-        "git_commit" => {
-            let params: GitCommitParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| {
-                    git::commit(path, &params.message, params.stage_all, params.amend)
-                })
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_unpushed_commits" => {
+        let params: GitUnpushedCommitsParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::unpushed_commits(path, params.branch))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_author_stats" => {
+        let params: GitAuthorStatsParams = parse_params(params)?;
+        let exclude_merges = params.exclude_merges.unwrap_or(false);
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::author_stats(path, params.limit, exclude_merges)
+            })
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_list_worktrees" => {
+        let params: CwdParams = parse_params(params)?;
+        let result = run_blocking(move || utils::with_cwd(params.cwd, git::list_worktrees)).await?;
+        to_value(result)
+    }
+    "git_worktrees_with_stats" => {
+        let params: CwdParams = parse_params(params)?;
+        let result =
+            run_blocking(move || utils::with_cwd(params.cwd, git::worktrees_with_stats)).await?;
+        to_value(result)
+    }
+    "git_list_remotes" => {
+        let params: CwdParams = parse_params(params)?;
+        let result = run_blocking(move || utils::with_cwd(params.cwd, git::list_remotes)).await?;
+        to_value(result)
+    }
+    "git_set_remote_url" => {
+        let params: GitSetRemoteUrlParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::set_remote_url(path, &params.name, &params.url, params.push)
+            })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_add_remote" => {
+        let params: GitAddRemoteParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::add_remote(path, &params.name, &params.url))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_remove_remote" => {
+        let params: GitRemoveRemoteParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::remove_remote(path, &params.name))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_list_submodules" => {
+        let params: CwdParams = parse_params(params)?;
+        let result = run_blocking(move || utils::with_cwd(params.cwd, git::list_submodules)).await?;
+        to_value(result)
+    }
+    "git_list_stashes" => {
+        let params: CwdParams = parse_params(params)?;
+        let result = run_blocking(move || utils::with_cwd(params.cwd, git::list_stashes)).await?;
+        to_value(result)
+    }
+    "git_list_tags" => {
+        let params: GitListTagsParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::list_tags(path, params.limit, params.skip))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_list_reflog" => {
+        let params: GitListReflogParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::list_reflog(path, params.reference, params.limit)
             })
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_restore_to_reflog" => {
+        let params: GitRestoreToReflogParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::restore_to_reflog(path, &params.reference, params.index)
+            })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_validate_commit_message" => {
+        let params: GitValidateCommitMessageParams = parse_params(params)?;
+        let max_subject_length = params
+            .max_subject_length
+            .unwrap_or(git::DEFAULT_MAX_SUBJECT_LENGTH);
+        let result = git::validate_commit_message_with_limit(&params.message, max_subject_length)
+            .map_err(CommandError::from)?;
+        to_value(result)
+    }
+    "git_apply_stash" => {
+        let params: GitApplyStashParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, |path| git::apply_stash(path, params.index)))
             .await?;
-            Ok(Value::Null)
-        }
-        "git_stage_files" => {
-            let params: GitStageFilesParams = parse_params(params)?;
-            run_blocking(move || utils::with_cwd(params.cwd, |path| git::stage_paths(path, &params.paths)))
+        Ok(Value::Null)
+    }
+    "git_drop_stash" => {
+        let params: GitApplyStashParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, |path| git::drop_stash(path, params.index)))
+            .await?;
+        Ok(Value::Null)
+    }
+    "git_rename_stash" => {
+        let params: GitRenameStashParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::rename_stash(path, params.index, &params.message))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_commit_files" => {
+        let params: GitCommitFilesParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::commit_files(path, &params.commit))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_stash_diff" => {
+        let params: GitApplyStashParams = parse_params(params)?;
+        let result =
+            run_blocking(move || utils::with_cwd(params.cwd, |path| git::stash_diff(path, params.index)))
                 .await?;
-            Ok(Value::Null)
-        }
-        "git_unstage_files" => {
-            let params: GitStageFilesParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::unstage_paths(path, &params.paths))
+        to_value(result)
+    }
+    "git_fetch" => {
+        let params: GitFetchParams = parse_params(params)?;
+        let network = params.network.unwrap_or_default();
+        let remote = params.remote.unwrap_or_else(|| "origin".to_string());
+        let events = state.events.clone();
+        let credentials = state.credentials.clone();
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                let prompt = credentials.prompt(|request| {
+                    emit_event(&events, "git-credential-request", request)
+                });
+                git::fetch(path, &remote, &network, &prompt)
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_discard_files" => {
-            let params: GitStageFilesParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::discard_paths(path, &params.paths))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_credential_reply" => {
+        let params: GitCredentialReplyParams = parse_params(params)?;
+        let credential = match (params.username, params.password) {
+            (Some(username), Some(password)) => Some(git::GitCredential { username, password }),
+            (None, None) => None,
+            _ => {
+                return Err(CommandError::new(
+                    "invalid_argument",
+                    "send both username and password to answer, or neither to cancel",
+                ));
+            }
+        };
+        state.credentials.reply(&params.request_id, credential)?;
+        Ok(Value::Null)
+    }
+    "git_pull" => {
+        let params: GitPullParams = parse_params(params)?;
+        let network = params.network.unwrap_or_default();
+        let events = state.events.clone();
+        let credentials = state.credentials.clone();
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                let prompt = credentials.prompt(|request| {
+                    emit_event(&events, "git-credential-request", request)
+                });
+                git::pull(path, &network, &prompt)
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_stage_all" => {
-            let params: CwdParams = parse_params(params)?;
-            run_blocking(move || utils::with_cwd(params.cwd, git::stage_all)).await?;
-            Ok(Value::Null)
-        }
-        "git_unstage_all" => {
-            let params: CwdParams = parse_params(params)?;
-            run_blocking(move || utils::with_cwd(params.cwd, git::unstage_all)).await?;
-            Ok(Value::Null)
-        }
-        "git_merge_into_branch" => {
-            let params: GitMergeParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_repo_root(params.repo_root, |path| {
-                    git::merge_into_branch(path, &params.target_branch, &params.source_branch)
-                })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_invalidate_proxy_cache" => {
+        git::invalidate_proxy_cache();
+        Ok(Value::Null)
+    }
+    "git_push" => {
+        let params: GitPushParams = parse_params(params)?;
+        let options = git::GitPushOptions {
+            force: params.force,
+            force_with_lease: params.force_with_lease.unwrap_or(false),
+            remote: params.remote,
+            local_branch: params.local_branch,
+            remote_branch: params.remote_branch,
+            set_upstream: params.set_upstream.unwrap_or(false),
+        };
+        let network = params.network.unwrap_or_default();
+        let events = state.events.clone();
+        let credentials = state.credentials.clone();
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                let prompt = credentials.prompt(|request| {
+                    emit_event(&events, "git-credential-request", request)
+                });
+                git::push(path, &options, &network, &prompt)
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_rebase_branch" => {
-            let params: GitRebaseParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_repo_root(params.repo_root, |path| {
-                    git::rebase_branch(path, &params.target_branch, &params.onto_branch)
-                })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_commit" => {
+        let params: GitCommitParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                let co_authors = params.co_authors.unwrap_or_default();
+                git::commit(path, &params.message, params.stage_all, params.amend, &co_authors)
             })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_amend_preview" => {
+        let params: GitAmendPreviewParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::amend_preview(path, params.stage_all))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_stage_files" => {
+        let params: GitStageFilesParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, |path| git::stage_paths(path, &params.paths)))
             .await?;
-            Ok(Value::Null)
-        }
-        "git_create_branch" => {
-            let params: GitCreateBranchParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| {
-                    git::create_branch(path, &params.branch_name, params.source_branch)
-                })
+        Ok(Value::Null)
+    }
+    "git_unstage_files" => {
+        let params: GitStageFilesParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::unstage_paths(path, &params.paths))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_discard_files" => {
+        let params: GitStageFilesParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::discard_paths(path, &params.paths))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_resolve_conflict" => {
+        let params: GitResolveConflictParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::resolve_conflict(path, &params.path, params.side)
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_checkout_branch" => {
-            let params: GitCheckoutBranchParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::checkout_local_branch(path, &params.branch_name))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_conflict_versions" => {
+        let params: GitConflictVersionsParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::conflict_versions(path, &params.path))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_expand_context" => {
+        let params: GitExpandContextParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::expand_context(
+                    path,
+                    &params.path,
+                    &params.rev,
+                    params.start_line,
+                    params.count,
+                )
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_detach_worktree_head" => {
-            let params: CwdParams = parse_params(params)?;
-            run_blocking(move || utils::with_cwd(params.cwd, git::detach_worktree_head)).await?;
-            Ok(Value::Null)
-        }
-        "git_smart_checkout_branch" => {
-            let params: GitSmartCheckoutParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::smart_checkout_branch(path, &params.branch_name))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_list_tree" => {
+        let params: GitListTreeParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::list_tree(path, &params.rev, &params.path))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_read_object" => {
+        let params: GitReadObjectParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::read_object(path, &params.oid))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_stage_all" => {
+        let params: CwdParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, git::stage_all)).await?;
+        Ok(Value::Null)
+    }
+    "git_unstage_all" => {
+        let params: CwdParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, git::unstage_all)).await?;
+        Ok(Value::Null)
+    }
+    "git_can_merge" => {
+        let params: GitCanMergeParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::can_merge_cleanly(path, &params.target, &params.source)
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_reset" => {
-            let params: GitResetParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::reset(path, &params.target, &params.mode))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_merge_into_branch" => {
+        let params: GitMergeParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_repo_root(params.repo_root, |path| {
+                let auto_stash = params.auto_stash.unwrap_or(true);
+                git::merge_into_branch_with_mode(
+                    path,
+                    &params.target_branch,
+                    &params.source_branch,
+                    auto_stash,
+                    params.fast_forward.unwrap_or_default(),
+                )
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_revert" => {
-            let params: GitRevertParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::revert(path, &params.commit))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_rebase_branch" => {
+        let params: GitRebaseParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_repo_root(params.repo_root, |path| {
+                git::rebase_branch(path, &params.target_branch, &params.onto_branch)
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_squash_commits" => {
-            let params: GitSquashParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::squash_commits(path, &params.commits))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_create_branch" => {
+        let params: GitCreateBranchParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::create_branch(path, &params.branch_name, params.source_branch)
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_commits_in_remote" => {
-            let params: GitCommitsInRemoteParams = parse_params(params)?;
-            let result = run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::commits_in_remote(path, &params.commits))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_checkout_branch" => {
+        let params: GitCheckoutBranchParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::checkout_local_branch(path, &params.branch_name))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_repair_worktrees" => {
+        let params: CwdParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, git::repair_worktrees)).await?;
+        Ok(Value::Null)
+    }
+    "git_worktree_disk_usage" => {
+        let params: CwdParams = parse_params(params)?;
+        let result =
+            run_blocking(move || utils::with_cwd(params.cwd, git::worktree_disk_usage)).await?;
+        to_value(result)
+    }
+    "git_get_sparse_checkout" => {
+        let params: CwdParams = parse_params(params)?;
+        let result =
+            run_blocking(move || utils::with_cwd(params.cwd, git::get_sparse_checkout)).await?;
+        to_value(result)
+    }
+    "git_set_sparse_checkout" => {
+        let params: GitSetSparseCheckoutParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::set_sparse_checkout(path, &params.patterns, params.cone)
             })
-            .await?;
-            to_value(result)
-        }
-        "git_add_worktree" => {
-            let params: GitAddWorktreeParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_repo_root(params.repo_root, |root| {
-                    let worktree_path = std::path::PathBuf::from(params.path);
-                    git::add_worktree(root, &worktree_path, &params.branch, &params.start_point)
-                })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_detach_worktree_head" => {
+        let params: CwdParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, git::detach_worktree_head)).await?;
+        Ok(Value::Null)
+    }
+    "git_smart_checkout_branch" => {
+        let params: GitSmartCheckoutParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                let auto_stash = params.auto_stash.unwrap_or(true);
+                git::smart_checkout_branch(path, &params.branch_name, auto_stash)
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_remove_worktree" => {
-            let params: GitRemoveWorktreeParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_repo_root(params.repo_root, |root| {
-                    let worktree_path = std::path::PathBuf::from(params.path);
-                    git::remove_worktree(root, &worktree_path, params.force)
-                })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_reset" => {
+        let params: GitResetParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::reset(path, &params.target, &params.mode))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_revert" => {
+        let params: GitRevertParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::revert(path, &params.commit))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_rebase_abort" => {
+        let params: CwdParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, git::rebase_abort)).await?;
+        Ok(Value::Null)
+    }
+    "git_rebase_continue" => {
+        let params: CwdParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, git::rebase_continue)).await?;
+        Ok(Value::Null)
+    }
+    "git_revert_abort" => {
+        let params: CwdParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, git::revert_abort)).await?;
+        Ok(Value::Null)
+    }
+    "git_cherry_pick_abort" => {
+        let params: CwdParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, git::cherry_pick_abort)).await?;
+        Ok(Value::Null)
+    }
+    "git_clear_pending_message" => {
+        let params: CwdParams = parse_params(params)?;
+        run_blocking(move || utils::with_cwd(params.cwd, git::clear_pending_message)).await?;
+        Ok(Value::Null)
+    }
+    "git_archive" => {
+        let params: GitArchiveParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                let format = params.format.parse::<git::ArchiveFormat>()?;
+                git::archive(path, &params.rev, format, Path::new(&params.out_path))
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_delete_branch" => {
-            let params: GitDeleteBranchParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_repo_root(params.repo_root, |root| {
-                    git::delete_branch(root, &params.branch, params.force)
-                })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_apply_patch" => {
+        let params: GitApplyPatchParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::apply_patch(path, &params.patch, params.check_only, params.three_way)
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "git_stash_save" => {
-            let params: GitStashSaveParams = parse_params(params)?;
-            run_blocking(move || {
-                utils::with_cwd(params.cwd, |path| git::stash_save(path, params.message, params.include_untracked))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_format_patch" => {
+        let params: GitFormatPatchParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::format_patch(path, &params.range, Path::new(&params.out_dir))
             })
-            .await?;
-            Ok(Value::Null)
-        }
-        "dialog.open" => {
-            let params: OpenDialogParams = parse_params(params)?;
-            let result = run_blocking(move || Ok(handle_dialog_open(params))).await?;
-            Ok(result)
-        }
-        "shell.openPath" => {
-            let params: OpenPathParams = parse_params(params)?;
-            run_blocking(move || handle_open_path(params)).await?;
-            Ok(Value::Null)
-        }
-        _ => Err(CommandError::new("not_found", "unknown method")),
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_squash_commits" => {
+        let params: GitSquashParams = parse_params(params)?;
+        let preview = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| {
+                git::squash_commits(
+                    path,
+                    &params.commits,
+                    params.message.as_deref(),
+                    params.dry_run,
+                )
+            })
+        })
+        .await?;
+        to_value(preview)
+    }
+    "git_drop_commits" => {
+        let params: GitDropCommitsParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::drop_commits(path, &params.commits))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_reorder_commits" => {
+        let params: GitReorderCommitsParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::reorder_commits(path, &params.commits))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_commits_in_remote" => {
+        let params: GitCommitsInRemoteParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::commits_in_remote(path, &params.commits))
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_diff_worktrees" => {
+        let params: GitDiffWorktreesParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_repo_root(params.repo_root, |root| {
+                git::diff_worktrees(root, Path::new(&params.path_a), Path::new(&params.path_b))
+            })
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_add_worktree" => {
+        let params: GitAddWorktreeParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_repo_root(params.repo_root, |root| {
+                let worktree_path = std::path::PathBuf::from(params.path);
+                git::add_worktree(root, &worktree_path, &params.branch, &params.start_point)
+            })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_validate_add_worktree" => {
+        let params: GitAddWorktreeParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_repo_root(params.repo_root, |root| {
+                let worktree_path = std::path::PathBuf::from(params.path);
+                git::validate_add_worktree(
+                    root,
+                    &worktree_path,
+                    &params.branch,
+                    &params.start_point,
+                )
+            })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_suggest_worktree_name" => {
+        let params: GitSuggestWorktreeNameParams = parse_params(params)?;
+        let result = run_blocking(move || {
+            utils::with_repo_root(params.repo_root, |root| {
+                git::suggest_worktree_name(root, &params.base)
+            })
+        })
+        .await?;
+        to_value(result)
+    }
+    "git_remove_worktree" => {
+        let params: GitRemoveWorktreeParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_repo_root(params.repo_root, |root| {
+                let worktree_path = std::path::PathBuf::from(params.path);
+                git::remove_worktree(root, &worktree_path, params.force)
+            })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_move_worktree" => {
+        let params: GitMoveWorktreeParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_repo_root(params.repo_root, |root| {
+                git::move_worktree(root, Path::new(&params.from), Path::new(&params.to))
+            })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_delete_branch" => {
+        let params: GitDeleteBranchParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_repo_root(params.repo_root, |root| {
+                git::delete_branch(root, &params.branch, params.force)
+            })
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "git_stash_save" => {
+        let params: GitStashSaveParams = parse_params(params)?;
+        run_blocking(move || {
+            utils::with_cwd(params.cwd, |path| git::stash_save(path, params.message, params.include_untracked))
+        })
+        .await?;
+        Ok(Value::Null)
+    }
+    "dialog.open" => {
+        let params: OpenDialogParams = parse_params(params)?;
+        let result = run_blocking(move || Ok(handle_dialog_open(params))).await?;
+        Ok(result)
+    }
+    "shell.openPath" => {
+        let params: OpenPathParams = parse_params(params)?;
+        run_blocking(move || handle_open_path(params)).await?;
+        Ok(Value::Null)
+    }
+    "shell.revealPath" => {
+        let params: RevealPathParams = parse_params(params)?;
+        run_blocking(move || shell::reveal_path(&params.path)).await?;
+        Ok(Value::Null)
+    }
+    "clipboard.write" => {
+        let params: ClipboardWriteParams = parse_params(params)?;
+        run_blocking(move || shell::set_clipboard(&params.text)).await?;
+        Ok(Value::Null)
     }
 }
 
@@ -452,6 +1162,147 @@ where
         .map_err(|err| CommandError::new("invalid_argument", err.to_string()))
 }
 
+/// Releases every session the connection attached, e.g. when the client disconnects.
+pub fn detach_client_sessions(state: &WsState) {
+    let session_ids: Vec<Uuid> = mutex_lock_or_panic(&state.attached_sessions)
+        .drain()
+        .collect();
+    for session_id in session_ids {
+        release_session(&state.manager, session_id);
+    }
+}
+
+/// Releases every path the connection watches, e.g. when the client disconnects.
+pub fn unwatch_client_paths(state: &WsState) {
+    let repo_paths: Vec<PathBuf> = mutex_lock_or_panic(&state.watched_repos).drain().collect();
+    for repo_path in repo_paths {
+        release_watcher(&state.repo_watchers, &repo_path);
+    }
+    let root_paths: Vec<PathBuf> = mutex_lock_or_panic(&state.watched_scan_roots)
+        .drain()
+        .collect();
+    for root_path in root_paths {
+        release_watcher(&state.scan_root_watchers, &root_path);
+    }
+}
+
+/// Counts the connection as a watcher of `path`, starting the shared watcher with `start`
+/// unless another connection already has. Watching the same path twice is a no-op.
+async fn add_watcher<W, F>(
+    shared: &SharedWatchers<W>,
+    watched: &Arc<Mutex<HashSet<PathBuf>>>,
+    path: PathBuf,
+    start: F,
+) -> Result<(), CommandError>
+where
+    W: Send + 'static,
+    F: FnOnce() -> Result<W, CommandError> + Send + 'static,
+{
+    {
+        let mut watched = mutex_lock_or_panic(watched);
+        if watched.contains(&path) {
+            return Ok(());
+        }
+        if let Some((_, count)) = mutex_lock_or_panic(shared).get_mut(&path) {
+            *count += 1;
+            watched.insert(path);
+            return Ok(());
+        }
+    }
+    let watcher = run_blocking(start).await?;
+    // Another request may have started the same watch meanwhile; the spare one is dropped
+    let mut watched = mutex_lock_or_panic(watched);
+    if watched.insert(path.clone()) {
+        mutex_lock_or_panic(shared).entry(path).or_insert((watcher, 0)).1 += 1;
+    }
+    Ok(())
+}
+
+/// Stops the connection watching `path`; the shared watcher goes with the last connection.
+fn remove_watcher<W>(
+    shared: &SharedWatchers<W>,
+    watched: &Arc<Mutex<HashSet<PathBuf>>>,
+    path: &Path,
+) {
+    if mutex_lock_or_panic(watched).remove(path) {
+        release_watcher(shared, path);
+    }
+}
+
+fn release_watcher<W>(shared: &SharedWatchers<W>, path: &Path) {
+    let released = {
+        let mut shared = mutex_lock_or_panic(shared);
+        match shared.get_mut(path) {
+            Some((_, count)) if *count > 1 => {
+                *count -= 1;
+                None
+            }
+            Some(_) => shared.remove(path),
+            None => None,
+        }
+    };
+    // Stopping a watcher joins its event thread, so it happens outside the lock
+    drop(released);
+}
+
+/// Turns log streaming on or off for the client. The shared log sink is installed with the
+/// first streaming client and removed when the last one stops or disconnects.
+pub fn set_client_log_stream(state: &WsState, enabled: bool) {
+    let mut streamers = mutex_lock_or_panic(&state.log_streamers);
+    if state.streams_log.swap(enabled, Ordering::SeqCst) == enabled {
+        return;
+    }
+    if enabled {
+        *streamers += 1;
+        if *streamers == 1 {
+            let events = state.events.clone();
+            logging::set_log_line_sink(Some(Box::new(move |line: &str| {
+                emit_event(&events, "log-line", LogLinePayload { line: line.to_string() });
+            })));
+        }
+    } else {
+        *streamers = streamers.saturating_sub(1);
+        if *streamers == 0 {
+            logging::set_log_line_sink(None);
+        }
+    }
+}
+
+/// Shuts down every ACP connection the client opened and didn't disconnect itself.
+pub async fn disconnect_client_acp_connections(state: &WsState) {
+    let connection_ids: Vec<Uuid> = mutex_lock_or_panic(&state.acp_connections)
+        .drain()
+        .collect();
+    for connection_id in connection_ids {
+        // Already gone if the agent exited on its own
+        let _ = state.acp.disconnect(connection_id).await;
+    }
+}
+
+fn attach_client_session(state: &WsState, session_id: Uuid) -> Result<(), CommandError> {
+    let mut attached = mutex_lock_or_panic(&state.attached_sessions);
+    if attached.contains(&session_id) {
+        return Ok(());
+    }
+    if state.manager.attach(&session_id).is_none() {
+        return Err(CommandError::new("not_found", "session not found"));
+    }
+    attached.insert(session_id);
+    Ok(())
+}
+
+/// Drops one attachment and kills the session after a grace period if nobody re-attaches.
+fn release_session(manager: &PtyManager, session_id: Uuid) {
+    if manager.detach(&session_id) != Some(0) {
+        return;
+    }
+    let manager = manager.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(SESSION_DETACH_GRACE).await;
+        manager.kill_if_detached(&session_id);
+    });
+}
+
 fn to_value<T: serde::Serialize>(value: T) -> Result<Value, CommandError> {
     serde_json::to_value(value).map_err(CommandError::internal)
 }
@@ -465,9 +1316,13 @@ where
     T: Send + 'static,
     F: FnOnce() -> Result<T, CommandError> + Send + 'static,
 {
-    tokio::task::spawn_blocking(task)
-        .await
-        .map_err(CommandError::internal)?
+    tokio::task::spawn_blocking(task).await.map_err(|err| {
+        if err.is_cancelled() {
+            CommandError::cancelled()
+        } else {
+            CommandError::internal(err)
+        }
+    })?
 }
 
 fn session_emitter(events: broadcast::Sender<EventMessage>) -> SessionDataEmitter {
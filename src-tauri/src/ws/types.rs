@@ -1,5 +1,15 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::git::{ConflictSide, FastForwardMode, GitNetworkOptions};
+pub use crate::pty::{GroupMemberStatus, SessionGroupStatus, SessionSignal, SessionSpec};
 
 #[derive(Clone)]
 pub struct EventMessage {
@@ -7,100 +17,261 @@ pub struct EventMessage {
     pub payload: Value,
 }
 
+/// Watchers shared by all connections, keyed by path, each with the number of connections
+/// watching it; a watcher is dropped when its last connection unwatches or disconnects.
+pub type SharedWatchers<W> = Arc<Mutex<HashMap<PathBuf, (W, usize)>>>;
+
 #[derive(Clone)]
 pub struct WsState {
     pub manager: crate::pty::PtyManager,
     pub acp: crate::acp::AcpManager,
     pub events: tokio::sync::broadcast::Sender<EventMessage>,
+    /// PTY sessions attached by the current connection
+    pub attached_sessions: Arc<Mutex<HashSet<Uuid>>>,
+    /// ACP connections opened by the current connection, shut down when it closes
+    pub acp_connections: Arc<Mutex<HashSet<Uuid>>>,
+    /// Repository watchers shared by all connections, keyed by repo path
+    pub repo_watchers: SharedWatchers<crate::git::RepoWatcher>,
+    /// Repositories watched by the current connection, released when it closes
+    pub watched_repos: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Scan root watchers shared by all connections, keyed by root path
+    pub scan_root_watchers: SharedWatchers<crate::git::ScanRootWatcher>,
+    /// Scan roots watched by the current connection, released when it closes
+    pub watched_scan_roots: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Credential prompts awaiting a `git_credential_reply`, shared by all connections
+    pub credentials: crate::git::CredentialBroker,
+    /// Repository scan started by the current connection, cancelled by `git_cancel_scan`
+    pub active_scan: crate::git::ActiveScan,
+    /// Number of connections streaming log lines; the log sink is installed while non-zero
+    pub log_streamers: Arc<Mutex<usize>>,
+    /// Whether the current connection enabled `stream_log`
+    pub streams_log: Arc<AtomicBool>,
+}
+
+impl WsState {
+    /// Clones the shared state for a new connection with no attached sessions, ACP
+    /// connections, watches, scan or log stream.
+    pub fn for_connection(&self) -> Self {
+        Self {
+            attached_sessions: Arc::default(),
+            acp_connections: Arc::default(),
+            watched_repos: Arc::default(),
+            watched_scan_roots: Arc::default(),
+            active_scan: Default::default(),
+            streams_log: Arc::default(),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Deserialize, TS)]
+pub struct TransportRequest {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: String,
+    pub method: String,
+    #[ts(type = "unknown | null")]
+    pub params: Option<Value>,
 }
 
-#[derive(Deserialize)]
-pub struct TransportRequest {
+#[derive(Deserialize, TS)]
+pub struct TransportBatchRequest {
     #[serde(rename = "type")]
     pub kind: String,
+    pub id: Option<String>,
+    pub requests: Vec<TransportBatchItem>,
+    /// Run the requests one after another instead of concurrently
+    #[serde(default)]
+    pub sequential: bool,
+}
+
+#[derive(Deserialize, TS)]
+pub struct TransportBatchItem {
     pub id: String,
     pub method: String,
+    #[ts(type = "unknown | null")]
     pub params: Option<Value>,
 }
 
-#[derive(Serialize)]
+#[derive(Deserialize, TS)]
+pub struct TransportCancel {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: String,
+}
+
+#[derive(Serialize, TS)]
 pub struct TransportResponse {
     #[serde(rename = "type")]
     pub kind: &'static str,
     pub id: String,
     pub ok: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(type = "unknown", optional)]
     pub result: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub error: Option<TransportError>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, TS)]
+pub struct TransportBatchResponse {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub id: Option<String>,
+    pub responses: Vec<TransportResponse>,
+}
+
+#[derive(Serialize, TS)]
 pub struct TransportError {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub code: Option<String>,
+    /// Full multi-line output behind `message`, such as git's stderr
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub details: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, TS)]
 pub struct TransportEvent {
     #[serde(rename = "type")]
     pub kind: &'static str,
     pub event: String,
+    #[ts(type = "unknown")]
     pub payload: Value,
 }
 
+/// Bumped when the transport envelope or method semantics change incompatibly.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Serialize, TS)]
+pub struct ServerInfo {
+    pub version: &'static str,
+    pub methods: Vec<String>,
+    pub protocol_version: u32,
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoChangedPayload {
+    pub repo_path: String,
+}
+
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanRootChangedPayload {
+    pub root_path: String,
+}
+
+#[derive(Serialize, TS)]
+pub struct EventsLaggedPayload {
+    #[ts(type = "number")]
+    pub skipped: u64,
+}
+
 // Parameter structs for various requests
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct SetLogLevelParams {
+    /// Filter directive such as `debug`, `trace`, or `parallel_cli_runner_lib=trace`
+    pub level: String,
+}
+
+#[derive(Deserialize, TS)]
+pub struct TailLogParams {
+    /// Number of trailing lines to return (default 200)
+    #[ts(optional)]
+    pub lines: Option<usize>,
+}
+
+#[derive(Deserialize, TS)]
+pub struct StreamLogParams {
+    pub enabled: bool,
+}
+
+#[derive(Serialize, TS)]
+pub struct LogLinePayload {
+    pub line: String,
+}
+
+#[derive(Deserialize, TS)]
 pub struct CreateSessionParams {
     pub cmd: Option<String>,
     pub cwd: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct CreateSessionsParams {
+    pub specs: Vec<SessionSpec>,
+    /// Id for the sessions as a group; `group-complete` fires once they have all exited
+    pub group: Option<String>,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupStatusParams {
+    pub group_id: String,
+}
+
+#[derive(Deserialize, TS)]
 pub struct SessionIdParams {
     pub id: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct WriteSessionParams {
     pub id: String,
     pub data: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct SetSessionCwdParams {
+    pub id: String,
+    pub cwd: String,
+}
+
+#[derive(Deserialize, TS)]
+pub struct SignalSessionParams {
+    pub id: String,
+    pub signal: SessionSignal,
+}
+
+#[derive(Deserialize, TS)]
 pub struct ResizeSessionParams {
     pub id: String,
     pub cols: u16,
     pub rows: u16,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct BroadcastLineParams {
     #[serde(rename = "sessionIds")]
     pub session_ids: Vec<String>,
     pub line: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct CwdParams {
     pub cwd: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitDiffParams {
     pub cwd: String,
     pub pathspecs: Vec<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitListCommitsParams {
     pub cwd: String,
     pub limit: usize,
     pub skip: Option<usize>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct GitListCommitsRangeParams {
     pub cwd: String,
@@ -108,75 +279,329 @@ pub struct GitListCommitsRangeParams {
     pub exclude_branch: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct GitLastCommitForPathParams {
+    pub cwd: String,
+    pub path: String,
+    /// Where to start looking; defaults to HEAD
+    #[serde(default)]
+    #[ts(optional)]
+    pub rev: Option<String>,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitLogForPathParams {
+    pub cwd: String,
+    pub path: String,
+    pub limit: usize,
+    /// Continue the history across renames of the file
+    #[serde(default)]
+    #[ts(optional)]
+    pub follow: Option<bool>,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GitScanReposParams {
+    pub cwd: String,
+    /// Descend into symlinked directories; off by default
+    #[serde(default)]
+    #[ts(optional)]
+    pub follow_symlinks: Option<bool>,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHasAnyRepoParams {
+    pub cwd: String,
+    /// Most directories to inspect before answering `false`
+    #[serde(default)]
+    #[ts(optional)]
+    pub max_checks: Option<usize>,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitUnpushedCommitsParams {
+    pub cwd: String,
+    /// Local branch to inspect; defaults to the checked-out branch
+    #[serde(default)]
+    #[ts(optional)]
+    pub branch: Option<String>,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GitAuthorStatsParams {
+    pub cwd: String,
+    /// Most recent commits to count
+    pub limit: usize,
+    /// Leave merge commits out of the counts
+    #[serde(default)]
+    #[ts(optional)]
+    pub exclude_merges: Option<bool>,
+}
+
+#[derive(Deserialize, TS)]
 pub struct GitListTagsParams {
     pub cwd: String,
     pub limit: usize,
     pub skip: Option<usize>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct GitListReflogParams {
+    pub cwd: String,
+    pub reference: Option<String>,
+    pub limit: usize,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GitValidateCommitMessageParams {
+    pub message: String,
+    pub max_subject_length: Option<usize>,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitRestoreToReflogParams {
+    pub cwd: String,
+    pub reference: String,
+    pub index: usize,
+}
+
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct GitApplyStashParams {
     pub cwd: String,
     pub index: i32,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct GitRenameStashParams {
+    pub cwd: String,
+    pub index: i32,
+    pub message: String,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitFetchParams {
+    pub cwd: String,
+    #[ts(optional)]
+    pub remote: Option<String>,
+    #[ts(optional)]
+    pub network: Option<GitNetworkOptions>,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCredentialReplyParams {
+    pub request_id: String,
+    /// Omit both fields to cancel the request
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitPullParams {
+    pub cwd: String,
+    #[ts(optional)]
+    pub network: Option<GitNetworkOptions>,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
 pub struct GitPushParams {
     pub cwd: String,
     pub force: bool,
+    /// Refuse to overwrite remote commits we haven't fetched; wins over `force`
+    #[serde(default)]
+    #[ts(optional)]
+    pub force_with_lease: Option<bool>,
+    /// Remote to push to; without it the current branch goes to its upstream
+    #[serde(default)]
+    #[ts(optional)]
+    pub remote: Option<String>,
+    /// Local branch to push; defaults to the current branch
+    #[serde(default)]
+    #[ts(optional)]
+    pub local_branch: Option<String>,
+    /// Branch name on the remote; defaults to the local branch name
+    #[serde(default)]
+    #[ts(optional)]
+    pub remote_branch: Option<String>,
+    /// Track the pushed branch so later pulls use it
+    #[serde(default)]
+    #[ts(optional)]
+    pub set_upstream: Option<bool>,
+    #[ts(optional)]
+    pub network: Option<GitNetworkOptions>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct GitCommitParams {
     pub cwd: String,
     pub message: String,
     pub stage_all: bool,
     pub amend: bool,
+    pub co_authors: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GitAmendPreviewParams {
+    pub cwd: String,
+    pub stage_all: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitStageFilesParams {
     pub cwd: String,
     pub paths: Vec<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitResetParams {
     pub cwd: String,
     pub target: String,
     pub mode: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct GitCommitFilesParams {
+    pub cwd: String,
+    pub commit: String,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitBranchSyncStatusParams {
+    pub cwd: String,
+    pub branch: String,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitArchiveParams {
+    pub cwd: String,
+    pub rev: String,
+    pub format: String,
+    #[serde(rename = "outPath")]
+    pub out_path: String,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitApplyPatchParams {
+    pub cwd: String,
+    pub patch: String,
+    #[serde(default, rename = "checkOnly")]
+    #[ts(optional, as = "Option<bool>")]
+    pub check_only: bool,
+    #[serde(default, rename = "threeWay")]
+    #[ts(optional, as = "Option<bool>")]
+    pub three_way: bool,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitFormatPatchParams {
+    pub cwd: String,
+    pub range: String,
+    #[serde(rename = "outDir")]
+    pub out_dir: String,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitGcParams {
+    pub cwd: String,
+    #[serde(default)]
+    #[ts(optional, as = "Option<bool>")]
+    pub aggressive: bool,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitSetRemoteUrlParams {
+    pub cwd: String,
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    #[ts(optional, as = "Option<bool>")]
+    pub push: bool,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitAddRemoteParams {
+    pub cwd: String,
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitRemoveRemoteParams {
+    pub cwd: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, TS)]
 pub struct GitRevertParams {
     pub cwd: String,
     pub commit: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitSquashParams {
     pub cwd: String,
     pub commits: Vec<String>,
+    #[ts(optional)]
+    pub message: Option<String>,
+    #[serde(default, rename = "dryRun")]
+    #[ts(optional, as = "Option<bool>")]
+    pub dry_run: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct GitDropCommitsParams {
+    pub cwd: String,
+    pub commits: Vec<String>,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitReorderCommitsParams {
+    pub cwd: String,
+    pub commits: Vec<String>,
+}
+
+#[derive(Deserialize, TS)]
 pub struct GitCommitsInRemoteParams {
     pub cwd: String,
     pub commits: Vec<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct GitCanMergeParams {
+    pub cwd: String,
+    /// Revision that would receive the merge
+    pub target: String,
+    /// Revision that would be merged in
+    pub source: String,
+}
+
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct GitMergeParams {
     pub repo_root: String,
     pub target_branch: String,
     pub source_branch: String,
+    /// Stash local changes around the merge (default); with `false`, the merge is refused
+    /// while the working tree has uncommitted changes
+    #[serde(default)]
+    #[ts(optional)]
+    pub auto_stash: Option<bool>,
+    /// Fast-forward policy: `never` (default) to always create a merge commit, `auto` to
+    /// fast-forward when possible, or `only` to fail unless the target can be fast-forwarded
+    #[serde(default)]
+    #[ts(optional)]
+    pub fast_forward: Option<FastForwardMode>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct GitRebaseParams {
     pub repo_root: String,
@@ -184,51 +609,104 @@ pub struct GitRebaseParams {
     pub onto_branch: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitCreateBranchParams {
     pub cwd: String,
     pub branch_name: String,
     pub source_branch: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitCheckoutBranchParams {
     pub cwd: String,
     #[serde(rename = "branchName")]
     pub branch_name: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct GitSmartCheckoutParams {
     pub cwd: String,
     #[serde(rename = "branchName")]
     pub branch_name: String,
+    /// Stash local changes around the operation (default); with `false`, git's own
+    /// refusal to overwrite local changes applies instead
+    #[serde(default)]
+    #[ts(optional)]
+    pub auto_stash: Option<bool>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitStageAllParams {
     pub cwd: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitUnstageAllParams {
     pub cwd: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitUnstageFilesParams {
     pub cwd: String,
     pub paths: Vec<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitDiscardFilesParams {
     pub cwd: String,
     pub paths: Vec<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct GitResolveConflictParams {
+    pub cwd: String,
+    pub path: String,
+    pub side: ConflictSide,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitConflictVersionsParams {
+    pub cwd: String,
+    pub path: String,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitListTreeParams {
+    pub cwd: String,
+    pub rev: String,
+    /// Directory to list, relative to the repository root; empty for the root
+    pub path: String,
+}
+
+#[derive(Deserialize, TS)]
+pub struct GitReadObjectParams {
+    pub cwd: String,
+    /// Full or abbreviated object id
+    pub oid: String,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GitExpandContextParams {
+    pub cwd: String,
+    pub path: String,
+    /// Revision to read the file at; empty for the working tree
+    pub rev: String,
+    /// 1-based line to start at
+    pub start_line: usize,
+    pub count: usize,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GitDiffWorktreesParams {
+    pub repo_root: String,
+    pub path_a: String,
+    pub path_b: String,
+}
+
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct GitAddWorktreeParams {
     pub repo_root: String,
@@ -237,7 +715,23 @@ pub struct GitAddWorktreeParams {
     pub start_point: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct GitSetSparseCheckoutParams {
+    pub cwd: String,
+    /// Directories in cone mode, gitignore-style patterns otherwise
+    pub patterns: Vec<String>,
+    pub cone: bool,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSuggestWorktreeNameParams {
+    pub repo_root: String,
+    /// Preferred branch name; a numeric suffix is added when it is taken
+    pub base: String,
+}
+
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct GitRemoveWorktreeParams {
     pub repo_root: String,
@@ -245,7 +739,15 @@ pub struct GitRemoveWorktreeParams {
     pub force: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GitMoveWorktreeParams {
+    pub repo_root: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct GitStashSaveParams {
     pub cwd: String,
@@ -253,7 +755,7 @@ pub struct GitStashSaveParams {
     pub include_untracked: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct GitDeleteBranchParams {
     pub repo_root: String,
@@ -261,13 +763,13 @@ pub struct GitDeleteBranchParams {
     pub force: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 pub struct GitDropStashParams {
     pub cwd: String,
     pub index: i32,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenDialogParams {
     pub directory: Option<bool>,
@@ -275,57 +777,89 @@ pub struct OpenDialogParams {
     pub title: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenPathParams {
     pub path: String,
     pub open_with: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+pub struct RevealPathParams {
+    pub path: String,
+}
+
+#[derive(Deserialize, TS)]
+pub struct ClipboardWriteParams {
+    pub text: String,
+}
+
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AcpConnectionIdParams {
     pub id: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct AcpExtMethodParams {
+    pub connection_id: String,
+    pub method: String,
+    #[ts(optional, type = "unknown")]
+    pub params: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct AcpValidateMcpParams {
+    #[ts(type = "Array<unknown>")]
+    pub mcp_servers: Vec<agent_client_protocol::McpServer>,
+}
+
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AcpSessionNewParams {
     pub connection_id: String,
     pub cwd: String,
+    #[ts(type = "Array<unknown> | null")]
     pub mcp_servers: Option<Vec<agent_client_protocol::McpServer>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AcpSessionLoadParams {
     pub connection_id: String,
     pub session_id: String,
     pub cwd: String,
+    #[ts(type = "Array<unknown> | null")]
     pub mcp_servers: Option<Vec<agent_client_protocol::McpServer>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AcpSessionPromptParams {
     pub session_id: String,
+    #[ts(type = "Array<unknown>")]
     pub prompt: Vec<agent_client_protocol::ContentBlock>,
+    /// Tags the `acp-session-update` events this prompt produces; generated when omitted
+    #[ts(optional)]
+    pub prompt_id: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AcpSessionCancelParams {
     pub session_id: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AcpPermissionReplyParams {
     pub request_id: String,
     pub outcome: AcpPermissionOutcomeDto,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(tag = "outcome", rename_all = "snake_case")]
 pub enum AcpPermissionOutcomeDto {
     Cancelled,
@@ -335,7 +869,7 @@ pub enum AcpPermissionOutcomeDto {
     },
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AcpAgentConfigParams {
     pub command: String,
@@ -343,4 +877,9 @@ pub struct AcpAgentConfigParams {
     pub env: std::collections::HashMap<String, String>,
     #[serde(default)]
     pub cwd: Option<String>,
+    /// Omit to advertise the protocol default capabilities
+    #[serde(default)]
+    #[ts(optional)]
+    #[ts(type = "{ readTextFile?: boolean, writeTextFile?: boolean }")]
+    pub capabilities: Option<serde_json::Value>,
 }
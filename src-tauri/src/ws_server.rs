@@ -1,19 +1,109 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use futures_util::{SinkExt, StreamExt};
 use http::StatusCode;
+use serde::Deserialize;
 use tokio::net::TcpListener as TokioTcpListener;
+use tokio::sync::broadcast::error::RecvError;
 use tokio_tungstenite::accept_hdr_async;
 use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
 use tokio_tungstenite::tungstenite::Message;
 
 use crate::acp::{self, types::AcpEvent};
+use crate::command_error::CommandError;
 use crate::pty::PtyManager;
+use crate::utils::mutex_lock_or_panic;
 use crate::ws::*;
 
-pub async fn run_ws_server(port: u16, auth_token: String) -> anyhow::Result<()> {
+/// A spawned request task that a `cancel` frame can abort.
+struct InFlightRequest {
+    abort: tokio::task::AbortHandle,
+    /// Sessions of the ACP prompts the request runs, told to stop when it is cancelled
+    acp_session_ids: Vec<String>,
+    /// Ids of the requests inside a batch, so cancelling it can answer each of them
+    batch_item_ids: Option<Vec<String>>,
+}
+
+type InFlightRequests = Arc<Mutex<HashMap<String, InFlightRequest>>>;
+type OutboundSender = tokio::sync::mpsc::UnboundedSender<Message>;
+
+/// Failed auth attempts allowed per IP within `AUTH_FAILURE_WINDOW` before it is blocked.
+const MAX_AUTH_FAILURES: u32 = 5;
+const AUTH_FAILURE_WINDOW: Duration = Duration::from_secs(60);
+const AUTH_BLOCK_DURATION: Duration = Duration::from_secs(300);
+
+struct AuthFailures {
+    count: u32,
+    window_start: Instant,
+    blocked_until: Option<Instant>,
+}
+
+/// Tracks failed handshakes per client IP and temporarily blocks repeat offenders.
+#[derive(Clone, Default)]
+struct AuthLimiter {
+    failures: Arc<Mutex<HashMap<IpAddr, AuthFailures>>>,
+}
+
+impl AuthLimiter {
+    fn is_blocked(&self, ip: IpAddr) -> bool {
+        let failures = mutex_lock_or_panic(&self.failures);
+        failures
+            .get(&ip)
+            .and_then(|entry| entry.blocked_until)
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    fn record_failure(&self, ip: IpAddr) {
+        let now = Instant::now();
+        let mut failures = mutex_lock_or_panic(&self.failures);
+        failures.retain(|_, entry| {
+            now.duration_since(entry.window_start) < AUTH_FAILURE_WINDOW
+                || entry.blocked_until.is_some_and(|until| now < until)
+        });
+
+        let entry = failures.entry(ip).or_insert(AuthFailures {
+            count: 0,
+            window_start: now,
+            blocked_until: None,
+        });
+        if now.duration_since(entry.window_start) >= AUTH_FAILURE_WINDOW {
+            entry.count = 0;
+            entry.window_start = now;
+        }
+        entry.count += 1;
+        if entry.count >= MAX_AUTH_FAILURES {
+            tracing::warn!("blocking ws auth from {ip} after {} failed attempts", entry.count);
+            entry.blocked_until = Some(now + AUTH_BLOCK_DURATION);
+        }
+    }
+
+    fn record_success(&self, ip: IpAddr) {
+        mutex_lock_or_panic(&self.failures).remove(&ip);
+    }
+}
+
+/// How long shutdown waits for ACP agents to exit after being asked to.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default capacity of the event broadcast channel shared by all ws clients.
+///
+/// Each slot holds one event until every client has received it, so a larger buffer costs
+/// memory but lets slow clients absorb bursts (e.g. heavy PTY output) before they start
+/// receiving `events-lagged` notices for dropped events.
+pub const DEFAULT_EVENT_BUFFER: usize = 256;
+
+pub async fn run_ws_server(
+    port: u16,
+    auth_token: String,
+    event_buffer: usize,
+) -> anyhow::Result<()> {
     let listener = TokioTcpListener::bind(("127.0.0.1", port)).await?;
-    run_ws_server_on_tokio_listener(listener, auth_token).await
+    run_ws_server_on_tokio_listener(listener, auth_token, event_buffer).await
 }
 
 pub fn bind_ws_listener(port: u16) -> anyhow::Result<(std::net::TcpListener, u16)> {
@@ -26,44 +116,131 @@ pub fn bind_ws_listener(port: u16) -> anyhow::Result<(std::net::TcpListener, u16
 pub async fn run_ws_server_on_listener(
     listener: std::net::TcpListener,
     auth_token: String,
+    event_buffer: usize,
 ) -> anyhow::Result<()> {
     listener.set_nonblocking(true)?;
     let listener = TokioTcpListener::from_std(listener)?;
-    run_ws_server_on_tokio_listener(listener, auth_token).await
+    run_ws_server_on_tokio_listener(listener, auth_token, event_buffer).await
 }
 
 async fn run_ws_server_on_tokio_listener(
     listener: TokioTcpListener,
     auth_token: String,
+    event_buffer: usize,
 ) -> anyhow::Result<()> {
-    let events = tokio::sync::broadcast::channel(256).0;
-    let state = WsState {
-        manager: PtyManager::default(),
-        acp: acp::AcpManager::new(acp_event_sink(events.clone())),
-        events,
-    };
+    anyhow::ensure!(event_buffer > 0, "event buffer must be at least 1");
+    let state = WsState::new(event_buffer);
+
+    let auth_limiter = AuthLimiter::default();
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
 
     loop {
-        let (stream, _addr) = listener.accept().await?;
-        let state = state.clone();
+        let (stream, addr) = tokio::select! {
+            accepted = listener.accept() => accepted?,
+            () = &mut shutdown => break,
+        };
+        let state = state.for_connection();
         let token = auth_token.clone();
+        let auth_limiter = auth_limiter.clone();
         tokio::spawn(async move {
-            if let Err(err) = handle_connection(stream, state, token).await {
+            let result = handle_connection(stream, addr.ip(), state, token, auth_limiter).await;
+            if let Err(err) = result {
                 tracing::error!("ws connection error: {err}");
             }
         });
     }
+
+    tracing::info!("ws server shutting down");
+    state.shutdown().await;
+    Ok(())
+}
+
+/// Resolves on Ctrl-C, or on SIGTERM on unix.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!("failed to listen for ctrl-c: {err}");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(err) => {
+                tracing::error!("failed to listen for SIGTERM: {err}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {}
+        () = terminate => {}
+    }
+}
+
+impl WsState {
+    /// Builds server-wide state with empty in-memory PTY and ACP managers, publishing events
+    /// on a new broadcast channel of `event_buffer` slots (which must be at least 1).
+    pub fn new(event_buffer: usize) -> Self {
+        let events = tokio::sync::broadcast::channel(event_buffer).0;
+        Self {
+            manager: PtyManager::default(),
+            acp: acp::AcpManager::new(acp_event_sink(events.clone())),
+            events,
+            attached_sessions: Default::default(),
+            acp_connections: Default::default(),
+            repo_watchers: Default::default(),
+            watched_repos: Default::default(),
+            scan_root_watchers: Default::default(),
+            watched_scan_roots: Default::default(),
+            credentials: Default::default(),
+            active_scan: Default::default(),
+            log_streamers: Default::default(),
+            streams_log: Default::default(),
+        }
+    }
+
+    /// Kills every PTY session and shuts down every ACP agent so no child process outlives
+    /// the server. Agents that don't exit within `SHUTDOWN_TIMEOUT` are left behind.
+    pub async fn shutdown(&self) {
+        let killed = self.manager.kill_all();
+        if killed > 0 {
+            tracing::info!("killed {killed} pty sessions");
+        }
+        if tokio::time::timeout(SHUTDOWN_TIMEOUT, self.acp.disconnect_all())
+            .await
+            .is_err()
+        {
+            tracing::warn!("timed out waiting for acp agents to exit");
+        }
+    }
 }
 
 async fn handle_connection(
     stream: tokio::net::TcpStream,
+    peer_ip: IpAddr,
     state: WsState,
     expected_token: String,
+    auth_limiter: AuthLimiter,
 ) -> anyhow::Result<()> {
     let ws_stream = accept_hdr_async(stream, |req: &Request, resp: Response| {
+        if auth_limiter.is_blocked(peer_ip) {
+            return Err(too_many_requests_response());
+        }
         if is_authorized(req, &expected_token) {
+            auth_limiter.record_success(peer_ip);
             Ok(resp)
         } else {
+            auth_limiter.record_failure(peer_ip);
             Err(unauthorized_response())
         }
     })
@@ -71,6 +248,7 @@ async fn handle_connection(
 
     let (mut write, mut read) = ws_stream.split();
     let (out_tx, mut out_rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
+    let in_flight = InFlightRequests::default();
     let mut event_rx = state.events.subscribe();
 
     let writer = tokio::spawn(async move {
@@ -83,8 +261,28 @@ async fn handle_connection(
 
     let event_forwarder = {
         let out_tx = out_tx.clone();
+        let streams_log = state.streams_log.clone();
         tokio::spawn(async move {
-            while let Ok(event) = event_rx.recv().await {
+            loop {
+                let event = match event_rx.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        // Tell the client it missed events instead of silently going quiet
+                        let Ok(payload) = serde_json::to_value(EventsLaggedPayload { skipped })
+                        else {
+                            continue;
+                        };
+                        EventMessage {
+                            event: "events-lagged".to_string(),
+                            payload,
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                // Log lines are shared by every connection but only go to those that asked
+                if event.event == "log-line" && !streams_log.load(Ordering::Relaxed) {
+                    continue;
+                }
                 let payload = TransportEvent {
                     kind: "event",
                     event: event.event,
@@ -106,56 +304,270 @@ async fn handle_connection(
         };
 
         if let Message::Text(text) = message {
+            if let Ok(batch) = serde_json::from_str::<TransportBatchRequest>(&text) {
+                if batch.kind != "batch" {
+                    // Answer rather than drop it, or the client waits for a reply forever
+                    if let Some(id) = batch.id {
+                        let error = CommandError::new(
+                            "invalid_argument",
+                            format!("unsupported frame type {:?}", batch.kind),
+                        );
+                        send_frame(&out_tx, &error_response(id, error));
+                    }
+                    continue;
+                }
+                let batch_id = batch.id.clone();
+                let item_ids = batch.requests.iter().map(|item| item.id.clone()).collect();
+                let acp_session_ids = batch
+                    .requests
+                    .iter()
+                    .filter_map(|item| prompt_session_id(&item.method, item.params.as_ref()))
+                    .collect();
+                let state = state.clone();
+                spawn_tracked(
+                    &in_flight,
+                    &out_tx,
+                    batch_id,
+                    acp_session_ids,
+                    Some(item_ids),
+                    async move { serde_json::to_string(&dispatch_batch(batch, state).await).ok() },
+                );
+                continue;
+            }
+
+            if let Ok(cancel) = serde_json::from_str::<TransportCancel>(&text) {
+                if cancel.kind == "cancel" {
+                    cancel_request(&in_flight, &state, &out_tx, cancel.id);
+                    continue;
+                }
+            }
+
             let Ok(request) = serde_json::from_str::<TransportRequest>(&text) else {
                 continue;
             };
             if request.kind != "request" {
+                let error = CommandError::new(
+                    "invalid_argument",
+                    format!("unsupported frame type {:?}", request.kind),
+                );
+                send_frame(&out_tx, &error_response(request.id, error));
                 continue;
             }
 
+            let acp_session_ids = prompt_session_id(&request.method, request.params.as_ref())
+                .into_iter()
+                .collect();
+            let request_id = request.id.clone();
             let state = state.clone();
-            let out_tx = out_tx.clone();
-            tokio::spawn(async move {
-                let response = match handle_request(request.method, request.params, state).await {
-                    Ok(result) => TransportResponse {
-                        kind: "response",
-                        id: request.id,
-                        ok: true,
-                        result: Some(result),
-                        error: None,
-                    },
-                    Err(err) => TransportResponse {
-                        kind: "response",
-                        id: request.id,
-                        ok: false,
-                        result: None,
-                        error: Some(TransportError {
-                            message: err.message,
-                            code: Some(err.code),
-                        }),
-                    },
-                };
-
-                if let Ok(text) = serde_json::to_string(&response) {
-                    let _ = out_tx.send(Message::Text(text.into()));
-                }
-            });
+            spawn_tracked(
+                &in_flight,
+                &out_tx,
+                Some(request_id),
+                acp_session_ids,
+                None,
+                async move {
+                    let response =
+                        dispatch_request(request.id, request.method, request.params, state).await;
+                    serde_json::to_string(&response).ok()
+                },
+            );
         }
     }
 
+    for (_, request) in mutex_lock_or_panic(&in_flight).drain() {
+        request.abort.abort();
+    }
+    detach_client_sessions(&state);
+    unwatch_client_paths(&state);
+    set_client_log_stream(&state, false);
+    disconnect_client_acp_connections(&state).await;
     drop(out_tx);
     let _ = writer.await;
     let _ = event_forwarder.await;
     Ok(())
 }
 
+/// Spawns a request task, tracking it under `id` so a later `cancel` frame can abort it.
+fn spawn_tracked<F>(
+    in_flight: &InFlightRequests,
+    out_tx: &OutboundSender,
+    id: Option<String>,
+    acp_session_ids: Vec<String>,
+    batch_item_ids: Option<Vec<String>>,
+    task: F,
+) where
+    F: Future<Output = Option<String>> + Send + 'static,
+{
+    // Hold the lock until the handle is recorded so a fast task can't finish first
+    let mut pending = mutex_lock_or_panic(in_flight);
+    let handle = tokio::spawn({
+        let in_flight = in_flight.clone();
+        let out_tx = out_tx.clone();
+        let id = id.clone();
+        async move {
+            let text = task.await;
+            if let Some(id) = id {
+                // A cancelled request has already been answered; drop its late result
+                if mutex_lock_or_panic(&in_flight).remove(&id).is_none() {
+                    return;
+                }
+            }
+            if let Some(text) = text {
+                let _ = out_tx.send(Message::Text(text.into()));
+            }
+        }
+    });
+    if let Some(id) = id {
+        pending.insert(
+            id,
+            InFlightRequest {
+                abort: handle.abort_handle(),
+                acp_session_ids,
+                batch_item_ids,
+            },
+        );
+    }
+}
+
+/// Answers a cancelled request right away; telling the agent to stop an ACP prompt runs in
+/// the background so a slow agent never holds up the connection's read loop.
+fn cancel_request(
+    in_flight: &InFlightRequests,
+    state: &WsState,
+    out_tx: &OutboundSender,
+    id: String,
+) {
+    let Some(request) = mutex_lock_or_panic(in_flight).remove(&id) else {
+        return;
+    };
+    // Blocking git work keeps running to completion, but its result is no longer forwarded
+    request.abort.abort();
+    for session_id in request.acp_session_ids {
+        let acp = state.acp.clone();
+        let request_id = id.clone();
+        tokio::spawn(async move {
+            if let Err(err) = acp.cancel(session_id).await {
+                tracing::warn!("failed to cancel acp prompt for request {request_id}: {err}");
+            }
+        });
+    }
+
+    match request.batch_item_ids {
+        // A batch is answered in kind, with every request in it cancelled
+        Some(item_ids) => send_frame(
+            out_tx,
+            &TransportBatchResponse {
+                kind: "batch-response",
+                id: Some(id),
+                responses: item_ids
+                    .into_iter()
+                    .map(|item_id| error_response(item_id, CommandError::cancelled()))
+                    .collect(),
+            },
+        ),
+        None => send_frame(out_tx, &error_response(id, CommandError::cancelled())),
+    }
+}
+
+/// The ACP session an `acp_session_prompt` request prompts, if that is what it is.
+fn prompt_session_id(method: &str, params: Option<&serde_json::Value>) -> Option<String> {
+    if method != "acp_session_prompt" {
+        return None;
+    }
+    let params = AcpSessionPromptParams::deserialize(params?).ok()?;
+    Some(params.session_id)
+}
+
+fn send_frame<T: serde::Serialize>(out_tx: &OutboundSender, frame: &T) {
+    if let Ok(text) = serde_json::to_string(frame) {
+        let _ = out_tx.send(Message::Text(text.into()));
+    }
+}
+
+fn error_response(id: String, error: CommandError) -> TransportResponse {
+    TransportResponse {
+        kind: "response",
+        id,
+        ok: false,
+        result: None,
+        error: Some(TransportError {
+            message: error.message,
+            code: Some(error.code),
+            details: error.details,
+        }),
+    }
+}
+
+/// Runs one request in-process against fresh server state, exactly as `handle_request`
+/// would for a connected client but without WebSocket framing. Use a shared
+/// [`WsState`] with `handle_request` instead when requests need to see each other's
+/// sessions or watchers.
+pub async fn request_once(
+    method: &str,
+    params: Option<serde_json::Value>,
+) -> Result<serde_json::Value, CommandError> {
+    let state = WsState::new(DEFAULT_EVENT_BUFFER).for_connection();
+    handle_request(method.to_string(), params, state).await
+}
+
+async fn dispatch_request(
+    id: String,
+    method: String,
+    params: Option<serde_json::Value>,
+    state: WsState,
+) -> TransportResponse {
+    match handle_request(method, params, state).await {
+        Ok(result) => TransportResponse {
+            kind: "response",
+            id,
+            ok: true,
+            result: Some(result),
+            error: None,
+        },
+        Err(err) => error_response(id, err),
+    }
+}
+
+async fn dispatch_batch(batch: TransportBatchRequest, state: WsState) -> TransportBatchResponse {
+    let responses = if batch.sequential {
+        let mut responses = Vec::with_capacity(batch.requests.len());
+        for item in batch.requests {
+            let response = dispatch_request(item.id, item.method, item.params, state.clone()).await;
+            responses.push(response);
+        }
+        responses
+    } else {
+        futures_util::future::join_all(batch.requests.into_iter().map(|item| {
+            dispatch_request(item.id, item.method, item.params, state.clone())
+        }))
+        .await
+    };
+
+    TransportBatchResponse {
+        kind: "batch-response",
+        id: batch.id,
+        responses,
+    }
+}
+
 fn is_authorized(request: &Request, expected_token: &str) -> bool {
-    request
-        .uri()
-        .query()
-        .and_then(extract_token)
-        .map(|token| token == expected_token)
-        .unwrap_or(false)
+    // The header keeps the token out of URLs and logs, so it wins when both are sent. Other
+    // schemes (e.g. Basic added by a proxy) don't carry our token and fall back to the query.
+    let token = request
+        .headers()
+        .get(http::header::AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+        .and_then(extract_bearer_token)
+        .or_else(|| request.uri().query().and_then(extract_token));
+    token.is_some_and(|token| token == expected_token)
+}
+
+fn extract_bearer_token(header: &str) -> Option<String> {
+    let (scheme, token) = header.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+    Some(token.trim().to_string())
 }
 
 fn extract_token(query: &str) -> Option<String> {
@@ -177,6 +589,13 @@ fn unauthorized_response() -> ErrorResponse {
         .unwrap_or_else(|_| http::Response::new(Some("unauthorized".to_string())))
 }
 
+fn too_many_requests_response() -> ErrorResponse {
+    http::Response::builder()
+        .status(StatusCode::TOO_MANY_REQUESTS)
+        .body(Some("too many failed attempts".to_string()))
+        .unwrap_or_else(|_| http::Response::new(Some("too many failed attempts".to_string())))
+}
+
 fn acp_event_sink(events: tokio::sync::broadcast::Sender<EventMessage>) -> acp::types::AcpEventSink {
     Arc::new(move |event| match event {
         AcpEvent::SessionUpdate(payload) => emit_event(&events, "acp-session-update", payload),
@@ -200,3 +619,70 @@ fn emit_event<T: serde::Serialize>(
         payload: value,
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use tokio_tungstenite::tungstenite::handshake::server::Request;
+
+    use super::{is_authorized, prompt_session_id, AuthLimiter, MAX_AUTH_FAILURES};
+
+    fn request(uri: &str, authorization: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(value) = authorization {
+            builder = builder.header(http::header::AUTHORIZATION, value);
+        }
+        builder.body(()).expect("request")
+    }
+
+    #[test]
+    fn bearer_header_wins_over_query_token() {
+        assert!(is_authorized(&request("/", Some("Bearer secret")), "secret"));
+        assert!(!is_authorized(&request("/?token=secret", Some("Bearer wrong")), "secret"));
+    }
+
+    #[test]
+    fn non_bearer_header_falls_back_to_query_token() {
+        let basic = Some("Basic dXNlcjpwYXNz");
+        assert!(is_authorized(&request("/?token=secret", basic), "secret"));
+        assert!(!is_authorized(&request("/", basic), "secret"));
+    }
+
+    #[test]
+    fn prompt_session_id_reads_prompt_params_only() {
+        let params = serde_json::json!({ "sessionId": "session-1", "prompt": [] });
+        let session_id = prompt_session_id("acp_session_prompt", Some(&params));
+        assert_eq!(session_id.as_deref(), Some("session-1"));
+        assert!(prompt_session_id("acp_session_cancel", Some(&params)).is_none());
+        assert!(prompt_session_id("acp_session_prompt", None).is_none());
+    }
+
+    #[test]
+    fn auth_limiter_blocks_ip_after_repeated_failures() {
+        let limiter = AuthLimiter::default();
+        let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let other = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+        for _ in 1..MAX_AUTH_FAILURES {
+            limiter.record_failure(client);
+        }
+        assert!(!limiter.is_blocked(client));
+        limiter.record_failure(client);
+        assert!(limiter.is_blocked(client));
+        assert!(!limiter.is_blocked(other));
+    }
+
+    #[test]
+    fn auth_limiter_success_clears_failures() {
+        let limiter = AuthLimiter::default();
+        let client = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        for _ in 1..MAX_AUTH_FAILURES {
+            limiter.record_failure(client);
+        }
+        limiter.record_success(client);
+        limiter.record_failure(client);
+        assert!(!limiter.is_blocked(client));
+    }
+}
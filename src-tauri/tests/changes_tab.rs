@@ -2,6 +2,7 @@
 //!
 //! These tests cover the core git operations used by the Changes tab:
 //! - Status (staged, unstaged, untracked files)
+//! - Clean/dirty checks
 //! - Stage/unstage operations
 //! - Diff operations
 //! - Commit operations
@@ -53,6 +54,33 @@ fn changes_tab_status_basic() {
     assert!(matches!(untracked_file.unstaged, Some(FileChangeType::Added)), "untracked.txt should be untracked");
 }
 
+/// Tests the clean check treats staged, unstaged, and untracked changes as dirty.
+#[test]
+fn changes_tab_is_clean() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("README.md", "# Initial\n")
+        .with_file(".gitignore", "ignored.log\n")
+        .commit("Initial commit")
+        .build();
+
+    assert!(git::is_clean(repo.path()).expect("is clean"), "fresh repo should be clean");
+
+    fs::write(repo.path().join("ignored.log"), "noise\n").expect("write ignored");
+    assert!(git::is_clean(repo.path()).expect("is clean"), "ignored files should not count");
+
+    fs::write(repo.path().join("new.txt"), "new\n").expect("write untracked");
+    assert!(!git::is_clean(repo.path()).expect("is clean"), "untracked file should be dirty");
+
+    git::stage_paths(repo.path(), &["new.txt".to_string()]).expect("stage");
+    assert!(!git::is_clean(repo.path()).expect("is clean"), "staged file should be dirty");
+
+    git::commit(repo.path(), "Add new", false, false, &[]).expect("commit");
+    assert!(git::is_clean(repo.path()).expect("is clean"), "committed repo should be clean");
+
+    fs::write(repo.path().join("README.md"), "# Changed\n").expect("modify");
+    assert!(!git::is_clean(repo.path()).expect("is clean"), "unstaged change should be dirty");
+}
+
 /// Tests staging and unstaging individual files.
 #[test]
 fn changes_tab_stage_unstage_files() {
@@ -178,7 +206,7 @@ fn changes_tab_commit_staged() {
     git::stage_paths(repo.path(), &["new.txt".to_string()]).expect("stage new.txt");
 
     // Commit without staging all (file is already staged)
-    git::commit(repo.path(), "Add new file", false, false).expect("commit");
+    git::commit(repo.path(), "Add new file", false, false, &[]).expect("commit");
 
     // Verify the commit was created
     let status = git::status(repo.path()).expect("status");
@@ -209,7 +237,7 @@ fn changes_tab_commit_with_stage_all() {
     assert!(status.has_unstaged, "should have unstaged changes");
 
     // Commit with stage_all=true should automatically stage and commit
-    git::commit(repo.path(), "Auto-stage and commit", true, false).expect("commit");
+    git::commit(repo.path(), "Auto-stage and commit", true, false, &[]).expect("commit");
 
     let status = git::status(repo.path()).expect("status");
     assert!(!status.has_staged, "should not have staged changes after commit");
@@ -326,7 +354,7 @@ fn changes_tab_complete_workflow() {
     assert!(status.has_untracked, "should still have untracked files");
 
     // 3. Commit with stage_all to include everything
-    git::commit(repo.path(), "Add both files", true, false).expect("commit");
+    git::commit(repo.path(), "Add both files", true, false, &[]).expect("commit");
 
     let status = git::status(repo.path()).expect("status");
     assert!(!status.has_staged, "should not have staged changes");
@@ -376,7 +404,7 @@ fn changes_tab_stage_and_commit_deletion() {
             "should have staged deletion or file deleted");
 
     // Commit the deletion
-    git::commit(repo.path(), "Delete file", false, false).expect("commit");
+    git::commit(repo.path(), "Delete file", false, false, &[]).expect("commit");
 
     let status = git::status(repo.path()).expect("status");
     assert!(!status.has_staged, "should not have staged changes");
@@ -392,7 +420,7 @@ fn changes_tab_amend_commit() {
     fs::write(repo.path().join("file.txt"), "original\n")
         .expect("write file");
     git::stage_all(repo.path()).expect("stage");
-    git::commit(repo.path(), "Initial commit", false, false).expect("commit");
+    git::commit(repo.path(), "Initial commit", false, false, &[]).expect("commit");
 
     // Get initial commit count
     let initial_count = git::list_commits(repo.path(), 10, None).expect("list commits").len();
@@ -402,7 +430,7 @@ fn changes_tab_amend_commit() {
         .expect("write file");
     git::stage_all(repo.path()).expect("stage");
 
-    let amend_result = git::commit(repo.path(), "Amended commit", false, true);
+    let amend_result = git::commit(repo.path(), "Amended commit", false, true, &[]);
 
     // Amend might fail depending on git state
     if amend_result.is_ok() {
@@ -421,6 +449,43 @@ fn changes_tab_amend_commit() {
     }
 }
 
+/// Tests that the amend preview reports the folded-in changes without touching the repo.
+#[test]
+fn changes_tab_amend_preview() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("file.txt", "original\n")
+        .commit("Initial commit")
+        .with_file("second.txt", "second\n")
+        .commit("Add second")
+        .build();
+    let head_before = git::list_commits(repo.path(), 1, None).expect("list commits")[0].id.clone();
+
+    fs::write(repo.path().join("second.txt"), "second\nchanged\n").expect("write file");
+    git::stage_paths(repo.path(), &["second.txt".to_string()]).expect("stage");
+    fs::write(repo.path().join("loose.txt"), "untracked\n").expect("write untracked");
+
+    let preview = git::amend_preview(repo.path(), false).expect("amend preview");
+    assert_eq!(preview.head_id, head_before);
+    assert_eq!(preview.message.trim(), "Add second");
+    assert_eq!(preview.parent_ids.len(), 1);
+    let staged: Vec<&str> = preview.staged_files.iter().map(|file| file.path.as_str()).collect();
+    assert_eq!(staged, ["second.txt"]);
+    assert_eq!(preview.files[0].status, git::DiffDeltaStatus::Added);
+
+    let with_all = git::amend_preview(repo.path(), true).expect("stage-all preview");
+    assert!(with_all
+        .staged_files
+        .iter()
+        .any(|file| file.path == "loose.txt" && file.status == git::DiffDeltaStatus::Added));
+
+    // Previewing must not stage anything or move HEAD
+    let status = git::status(repo.path()).expect("status");
+    let loose = status.modified_files.iter().find(|file| file.path == "loose.txt");
+    assert!(loose.is_some_and(|file| file.staged.is_none()));
+    let head_after = git::list_commits(repo.path(), 1, None).expect("list commits")[0].id.clone();
+    assert_eq!(head_after, head_before);
+}
+
 /// Tests diff_stats_against_branch for comparing branches.
 #[test]
 fn changes_tab_diff_stats_against_branch() {
@@ -438,7 +503,7 @@ fn changes_tab_diff_stats_against_branch() {
     // Add changes to main
     fs::write(repo.path().join("main.txt"), "main content\n")
         .expect("write main.txt");
-    git::commit(repo.path(), "Add main file", true, false).expect("commit");
+    git::commit(repo.path(), "Add main file", true, false, &[]).expect("commit");
 
     // Get diff stats against feature branch
     let stats = git::diff_stats_against_branch(repo.path(), "feature")
@@ -463,7 +528,7 @@ fn changes_tab_merge_conflict_detection() {
         .build();
 
     // Merge feature into main (should cause conflict)
-    let _result = git::merge_into_branch(repo.path(), "main", "feature");
+    let _result = git::merge_into_branch(repo.path(), "main", "feature", true);
     // We expect either success or conflict, both are valid test outcomes
     // Just verify the operation doesn't crash
 
@@ -4,9 +4,12 @@
 //! - Listing commits with pagination (limit, skip)
 //! - Listing commits between branches
 //! - Creating commits with various options
+//! - Co-author trailers on commits
+//! - Validating conventional commit messages
 //! - Amending commits
 //! - Reset operations (soft, mixed, hard)
 //! - Reverting commits
+//! - Listing the reflog and restoring from it
 //! - Checking if commits exist in remote
 
 mod common;
@@ -59,6 +62,30 @@ fn commits_tab_list_with_skip() {
     assert_eq!(skipped_commits[0].summary, "Commit 2", "first should be third commit");
 }
 
+/// Tests that paged listing reports whether more commits follow.
+#[test]
+fn commits_tab_list_paged_reports_has_more() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("file1.txt", "content1\n")
+        .commit("Commit 1")
+        .with_file("file2.txt", "content2\n")
+        .commit("Commit 2")
+        .with_file("file3.txt", "content3\n")
+        .commit("Commit 3")
+        .build();
+    let total = git::list_commits(repo.path(), 100, None).expect("list all commits").len();
+
+    let first_page = git::list_commits_paged(repo.path(), 2, None).expect("first page");
+    assert_eq!(first_page.commits.len(), 2);
+    assert_eq!(first_page.commits[0].summary, "Commit 3");
+    assert!(first_page.has_more, "more commits should follow the first page");
+
+    let last_page =
+        git::list_commits_paged(repo.path(), 2, Some(total - 2)).expect("last page");
+    assert_eq!(last_page.commits.len(), 2);
+    assert!(!last_page.has_more, "nothing should follow the last page");
+}
+
 /// Tests listing commits with both limit and skip for pagination.
 #[test]
 fn commits_tab_list_pagination() {
@@ -170,7 +197,7 @@ fn commits_tab_create_commit() {
     fs::write(repo.path().join("new.txt"), "new content\n").expect("write file");
     git::stage_paths(repo.path(), &["new.txt".to_string()]).expect("stage file");
 
-    git::commit(repo.path(), "Add new file", false, false).expect("commit");
+    git::commit(repo.path(), "Add new file", false, false, &[]).expect("commit");
 
     let status = git::status(repo.path()).expect("status after commit");
     assert!(!status.has_staged, "should not have staged changes");
@@ -196,7 +223,7 @@ fn commits_tab_commit_with_stage_all() {
     assert!(status.has_unstaged, "should have unstaged changes");
 
     // Commit with stage_all=true should automatically stage
-    git::commit(repo.path(), "Auto-stage and commit", true, false).expect("commit");
+    git::commit(repo.path(), "Auto-stage and commit", true, false, &[]).expect("commit");
 
     let status = git::status(repo.path()).expect("status after");
     assert!(!status.has_staged, "should not have staged changes after commit");
@@ -212,7 +239,7 @@ fn commits_tab_commit_nothing_to_commit() {
         .build();
 
     // Try to commit without any changes
-    let result = git::commit(repo.path(), "Empty commit", false, false);
+    let result = git::commit(repo.path(), "Empty commit", false, false, &[]);
     assert!(result.is_err(), "should error when nothing to commit");
 
     let err = result.unwrap_err();
@@ -221,6 +248,114 @@ fn commits_tab_commit_nothing_to_commit() {
             "error should mention nothing to commit");
 }
 
+/// Tests committing with only unstaged changes is reported apart from a clean tree.
+#[test]
+fn commits_tab_commit_nothing_staged() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("README.md", "# Initial\n")
+        .commit("Initial commit")
+        .build();
+
+    let clean = git::commit(repo.path(), "Empty commit", false, false, &[]).unwrap_err();
+    assert_eq!(clean.code(), "nothing_to_commit");
+
+    fs::write(repo.path().join("README.md"), "# Changed\n").expect("write file");
+    let unstaged = git::commit(repo.path(), "Unstaged commit", false, false, &[]).unwrap_err();
+    assert_eq!(unstaged.code(), "nothing_staged");
+
+    // Staging everything picks the change up
+    git::commit(repo.path(), "Staged commit", true, false, &[]).expect("commit with stage_all");
+}
+
+/// Tests co-authors are appended as trailers after a blank line.
+#[test]
+fn commits_tab_commit_with_co_authors() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("base.txt", "base\n")
+        .commit("Base")
+        .build();
+
+    fs::write(repo.path().join("new.txt"), "content\n").expect("write file");
+    let co_authors = vec![
+        "Ada Lovelace <ada@example.com>".to_string(),
+        "Alan Turing <alan@example.com>".to_string(),
+    ];
+    git::commit(repo.path(), "Pair work\n", true, false, &co_authors).expect("commit");
+
+    let head = repo.repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(
+        head.message().unwrap(),
+        "Pair work\n\nCo-authored-by: Ada Lovelace <ada@example.com>\nCo-authored-by: Alan Turing <alan@example.com>"
+    );
+}
+
+/// Tests malformed co-authors are rejected before anything is committed.
+#[test]
+fn commits_tab_commit_rejects_malformed_co_author() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("base.txt", "base\n")
+        .commit("Base")
+        .build();
+
+    fs::write(repo.path().join("new.txt"), "content\n").expect("write file");
+    let before = git::list_commits(repo.path(), 10, None).expect("list commits").len();
+    let co_authors = vec!["Ada Lovelace ada@example.com".to_string()];
+    let err = git::commit(repo.path(), "Pair work", true, false, &co_authors)
+        .expect_err("malformed co-author");
+    assert!(err.to_string().contains("invalid co-author"), "unexpected error: {err}");
+
+    let commits = git::list_commits(repo.path(), 10, None).expect("list commits");
+    assert_eq!(commits.len(), before, "no commit should be created");
+    let status = git::status(repo.path()).expect("status");
+    assert!(!status.has_staged, "nothing should be staged");
+}
+
+/// Tests a well-formed conventional commit message is parsed and accepted.
+#[test]
+fn commits_tab_validate_commit_message_valid() {
+    let lint = git::validate_commit_message("feat(parser)!: support arrays\n\nDetails here")
+        .expect("validate");
+    assert!(lint.valid, "unexpected errors: {:?}", lint.errors);
+    assert_eq!(lint.parsed_type.as_deref(), Some("feat"));
+    assert_eq!(lint.scope.as_deref(), Some("parser"));
+
+    let lint = git::validate_commit_message("fix: handle empty input").expect("validate");
+    assert!(lint.valid);
+    assert_eq!(lint.scope, None);
+}
+
+/// Tests malformed commit messages report lint errors.
+#[test]
+fn commits_tab_validate_commit_message_invalid() {
+    let lint = git::validate_commit_message("Update stuff").expect("validate");
+    assert!(!lint.valid);
+    assert_eq!(lint.parsed_type, None);
+
+    let lint = git::validate_commit_message("Feat(): ").expect("validate");
+    assert!(!lint.valid);
+    assert!(lint.errors.len() >= 3, "expected type, scope and subject errors: {:?}", lint.errors);
+
+    let lint = git::validate_commit_message("fix: subject\nno blank line").expect("validate");
+    assert!(!lint.valid);
+    assert!(lint.errors.iter().any(|err| err.contains("blank line")));
+
+    let lint = git::validate_commit_message("").expect("validate");
+    assert!(!lint.valid);
+}
+
+/// Tests the maximum subject length is configurable.
+#[test]
+fn commits_tab_validate_commit_message_subject_length() {
+    let lint = git::validate_commit_message_with_limit("chore: twelve chars", 10)
+        .expect("validate");
+    assert!(!lint.valid);
+    assert!(lint.errors.iter().any(|err| err.contains("at most 10")));
+
+    let lint = git::validate_commit_message_with_limit("chore: twelve chars", 20)
+        .expect("validate");
+    assert!(lint.valid, "unexpected errors: {:?}", lint.errors);
+}
+
 /// Tests amending the most recent commit.
 #[test]
 fn commits_tab_amend_commit() {
@@ -236,7 +371,7 @@ fn commits_tab_amend_commit() {
     fs::write(repo.path().join("file.txt"), "v1\nv2\n").expect("write file");
     git::stage_all(repo.path()).expect("stage");
 
-    let amend_result = git::commit(repo.path(), "Amended message", false, true);
+    let amend_result = git::commit(repo.path(), "Amended message", false, true, &[]);
 
     // Amend might fail if HEAD state is unexpected, but we can still verify the behavior
     if amend_result.is_ok() {
@@ -264,7 +399,7 @@ fn commits_tab_amend_unborn_branch() {
     fs::write(repo.path().join("file.txt"), "content\n").expect("write file");
     git::stage_all(repo.path()).expect("stage");
 
-    let result = git::commit(repo.path(), "Amend", false, true);
+    let result = git::commit(repo.path(), "Amend", false, true, &[]);
     assert!(result.is_err(), "should error when amending without commits");
 
     let err = result.unwrap_err();
@@ -339,6 +474,57 @@ fn commits_tab_reset_hard() {
     assert_eq!(content, "v1\n", "file should be reset to v1");
 }
 
+/// Tests the reflog records a hard reset so the lost commit can be found.
+#[test]
+fn commits_tab_reflog_after_hard_reset() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("file.txt", "v1\n")
+        .commit("Commit 1")
+        .with_file("file.txt", "v2\n")
+        .commit("Commit 2")
+        .build();
+
+    let commits = git::list_commits(repo.path(), 10, None).expect("list commits");
+    let lost_commit_id = commits[0].id.clone();
+    let commit1_id = commits[1].id.clone();
+
+    git::reset(repo.path(), &commit1_id, "hard").expect("hard reset");
+
+    let entries = git::list_reflog(repo.path(), None, 10).expect("list reflog");
+    assert!(entries.len() >= 2, "reflog should record commits and reset");
+    assert_eq!(entries[0].old_id, lost_commit_id, "newest entry should move away from lost commit");
+    assert_eq!(entries[0].new_id, commit1_id, "newest entry should point at reset target");
+
+    let limited = git::list_reflog(repo.path(), Some("HEAD".to_string()), 1).expect("list reflog");
+    assert_eq!(limited.len(), 1, "limit should cap entries");
+}
+
+/// Tests restoring a lost commit from the reflog after a hard reset.
+#[test]
+fn commits_tab_restore_to_reflog() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("file.txt", "v1\n")
+        .commit("Commit 1")
+        .with_file("file.txt", "v2\n")
+        .commit("Commit 2")
+        .build();
+
+    let commits = git::list_commits(repo.path(), 10, None).expect("list commits");
+    let lost_commit_id = commits[0].id.clone();
+    let commit1_id = commits[1].id.clone();
+
+    git::reset(repo.path(), &commit1_id, "hard").expect("hard reset");
+    git::restore_to_reflog(repo.path(), "HEAD", 1).expect("restore to reflog");
+
+    let commits = git::list_commits(repo.path(), 10, None).expect("list commits");
+    assert_eq!(commits[0].id, lost_commit_id, "HEAD should be back on the lost commit");
+    let content = fs::read_to_string(repo.path().join("file.txt")).unwrap();
+    assert_eq!(content, "v2\n");
+
+    let err = git::restore_to_reflog(repo.path(), "HEAD", 100).expect_err("out of range");
+    assert!(err.to_string().contains("out of range"), "unexpected error: {err}");
+}
+
 /// Tests reverting a commit creates a new revert commit.
 #[test]
 fn commits_tab_revert_commit() {
@@ -487,7 +673,7 @@ fn commits_tab_multiple_commits_sequence() {
         fs::write(repo.path().join(&filename), format!("content {}\n", i))
             .expect("write file");
         git::stage_paths(repo.path(), std::slice::from_ref(&filename)).expect("stage");
-        git::commit(repo.path(), &format!("Commit {}", i), false, false)
+        git::commit(repo.path(), &format!("Commit {}", i), false, false, &[])
             .expect("commit");
     }
 
@@ -513,7 +699,7 @@ fn commits_tab_multiline_commit_message() {
     git::stage_paths(repo.path(), &["new.txt".to_string()]).expect("stage");
 
     let message = "Add new feature\n\nThis adds a new feature\ndoes something useful";
-    git::commit(repo.path(), message, false, false).expect("commit");
+    git::commit(repo.path(), message, false, false, &[]).expect("commit");
 
     let commits = git::list_commits(repo.path(), 10, None).expect("list commits");
     assert!(commits[0].summary.contains("Add new feature"),
@@ -535,7 +721,7 @@ fn commits_tab_list_with_merge_commits() {
         .build();
 
     // Merge feature into main
-    let _ = git::merge_into_branch(repo.path(), "main", "feature");
+    let _ = git::merge_into_branch(repo.path(), "main", "feature", true);
 
     let commits = git::list_commits(repo.path(), 10, None).expect("list commits");
     // Should see merge commit, Main, Feature, Base
@@ -28,7 +28,7 @@ fn write_file(root: &Path, relative: &str, contents: &str) -> PathBuf {
 }
 
 fn commit_all(repo_root: &Path, message: &str) {
-    git::commit(repo_root, message, true, false).expect("commit")
+    git::commit(repo_root, message, true, false, &[]).expect("commit")
 }
 
 #[test]
@@ -85,7 +85,7 @@ fn create_worktree_from_existing_branch() {
 
     // Verify we can make commits in the worktree
     write_file(&worktree_path, "feature.txt", "Feature X content\n");
-    git::commit(&worktree_path, "Feature X commit", true, false).expect("commit in worktree");
+    git::commit(&worktree_path, "Feature X commit", true, false, &[]).expect("commit in worktree");
 
     // The worktree should be ahead of master
     let worktrees = git::list_worktrees(temp.path()).expect("list worktrees");
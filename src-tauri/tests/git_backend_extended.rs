@@ -25,7 +25,7 @@ fn write_file(root: &Path, relative: &str, contents: &str) -> PathBuf {
 }
 
 fn commit_all(repo_root: &Path, message: &str) {
-    git::commit(repo_root, message, true, false).expect("commit")
+    git::commit(repo_root, message, true, false, &[]).expect("commit")
 }
 
 fn head_oid(repo: &Repository) -> String {
@@ -156,11 +156,147 @@ fn push_test() {
     commit_all(local_temp.path(), "Commit 1");
     
     // Push should succeed
-    git::push(local_temp.path(), false).expect("push");
+    let network = git::GitNetworkOptions::default();
+    let options = git::GitPushOptions::default();
+    git::push(local_temp.path(), &options, &network, &git::NoCredentials).expect("push");
     
     let head = remote_repo.head().unwrap().target().unwrap();
     let local_head = local_repo.head().unwrap().target().unwrap();
     assert_eq!(head, local_head);
+
+    // Rewriting the pushed commit needs a forced push; the lease holds because nobody
+    // else has pushed since.
+    let pushed = local_repo.head().unwrap().peel_to_commit().unwrap();
+    pushed
+        .amend(Some("HEAD"), None, None, None, Some("Commit 1 amended"), None)
+        .expect("amend");
+    let result = git::push(local_temp.path(), &options, &network, &git::NoCredentials);
+    assert!(result.is_err(), "non-fast-forward push should be rejected");
+    let lease = git::GitPushOptions {
+        force_with_lease: true,
+        ..Default::default()
+    };
+    git::push(local_temp.path(), &lease, &network, &git::NoCredentials)
+        .expect("push with lease");
+    let head = remote_repo.head().unwrap().target().unwrap();
+    let local_head = local_repo.head().unwrap().target().unwrap();
+    assert_eq!(head, local_head);
+}
+
+#[test]
+fn push_branch_to_named_remote_under_new_name() {
+    let remote_temp = TempDir::new().expect("remote temp");
+    let remote_repo = Repository::init_bare(remote_temp.path()).expect("init bare");
+    let (local_temp, local_repo) = init_repo();
+    local_repo.remote("backup", remote_temp.path().to_str().unwrap()).unwrap();
+    write_file(local_temp.path(), "file.txt", "v1\n");
+    commit_all(local_temp.path(), "Commit 1");
+    git::create_branch(local_temp.path(), "feature", None).expect("create branch");
+
+    let network = git::GitNetworkOptions::default();
+    let options = git::GitPushOptions {
+        remote: Some("backup".to_string()),
+        local_branch: Some("feature".to_string()),
+        remote_branch: Some("topic".to_string()),
+        set_upstream: true,
+        ..Default::default()
+    };
+    git::push(local_temp.path(), &options, &network, &git::NoCredentials).expect("push");
+
+    let pushed = remote_repo.refname_to_id("refs/heads/topic").expect("remote branch");
+    assert_eq!(pushed, local_repo.refname_to_id("refs/heads/feature").unwrap());
+    let feature = local_repo.find_branch("feature", git2::BranchType::Local).unwrap();
+    assert_eq!(feature.upstream().unwrap().name().unwrap(), Some("backup/topic"));
+
+    let missing_remote = git::GitPushOptions {
+        remote: Some("missing".to_string()),
+        ..Default::default()
+    };
+    assert!(git::push(local_temp.path(), &missing_remote, &network, &git::NoCredentials).is_err());
+    let branch_without_remote = git::GitPushOptions {
+        local_branch: Some("feature".to_string()),
+        ..Default::default()
+    };
+    let result =
+        git::push(local_temp.path(), &branch_without_remote, &network, &git::NoCredentials);
+    assert!(result.is_err());
+}
+
+/// Records what `run_network_command` asks of its credential provider.
+#[derive(Default)]
+struct RecordingCredentials {
+    requested: std::sync::Mutex<Vec<String>>,
+    rejected: std::sync::Mutex<Vec<String>>,
+}
+
+impl git::CredentialProvider for RecordingCredentials {
+    fn credentials(&self, url: &str, _username: Option<&str>) -> Option<git::GitCredential> {
+        self.requested.lock().unwrap().push(url.to_string());
+        Some(git::GitCredential {
+            username: "user".to_string(),
+            password: "secret".to_string(),
+        })
+    }
+
+    fn rejected(&self, url: &str) {
+        self.rejected.lock().unwrap().push(url.to_string());
+    }
+}
+
+#[test]
+fn push_retries_with_credentials_after_auth_failure() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    // An HTTP remote that turns every request away with 401, remembering the auth headers
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let port = listener.local_addr().unwrap().port();
+    let auth_headers = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen = auth_headers.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                if let Some(value) = line.trim_end().strip_prefix("Authorization: ") {
+                    seen.lock().unwrap().push(value.to_string());
+                }
+                line.clear();
+            }
+            let _ = stream.write_all(
+                b"HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Basic realm=\"test\"\r\n\
+                  Content-Length: 0\r\nConnection: close\r\n\r\n",
+            );
+        }
+    });
+
+    let (local_temp, local_repo) = init_repo();
+    let url = format!("http://127.0.0.1:{port}/repo.git");
+    local_repo.remote("origin", &url).unwrap();
+    write_file(local_temp.path(), "file.txt", "v1\n");
+    commit_all(local_temp.path(), "Commit 1");
+
+    let network = git::GitNetworkOptions {
+        proxy: None,
+        use_system_proxy: false,
+    };
+    let options = git::GitPushOptions {
+        remote: Some("origin".to_string()),
+        remote_branch: Some("topic".to_string()),
+        ..Default::default()
+    };
+    let credentials = RecordingCredentials::default();
+    let result = git::push(local_temp.path(), &options, &network, &credentials);
+    assert!(result.is_err(), "the remote rejects every request");
+
+    let requested = credentials.requested.lock().unwrap().clone();
+    assert_eq!(requested.len(), 1, "credentials are asked for once");
+    assert!(requested[0].starts_with(&format!("http://127.0.0.1:{port}")));
+    // "user:secret" in base64, sent by the retry through the temporary helper
+    let auth_headers = auth_headers.lock().unwrap().clone();
+    assert!(auth_headers.iter().any(|header| header == "Basic dXNlcjpzZWNyZXQ="));
+    assert_eq!(*credentials.rejected.lock().unwrap(), requested);
 }
 
 #[test]
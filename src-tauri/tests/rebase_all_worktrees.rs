@@ -34,7 +34,7 @@ fn write_file(root: &Path, relative: &str, contents: &str) -> PathBuf {
 }
 
 fn commit_all(repo_root: &Path, message: &str) {
-    git::commit(repo_root, message, true, false).expect("commit")
+    git::commit(repo_root, message, true, false, &[]).expect("commit")
 }
 
 /// Test that demonstrates the bug: rebasing worktrees using the main repo path
@@ -64,10 +64,10 @@ fn rebase_all_worktrees_using_main_repo_path_fails() {
 
     // Add commits to each worktree
     write_file(&worktree_a_path, "feature-a.txt", "Feature A\n");
-    git::commit(&worktree_a_path, "Add feature A", true, false).expect("commit");
+    git::commit(&worktree_a_path, "Add feature A", true, false, &[]).expect("commit");
 
     write_file(&worktree_b_path, "feature-b.txt", "Feature B\n");
-    git::commit(&worktree_b_path, "Add feature B", true, false).expect("commit");
+    git::commit(&worktree_b_path, "Add feature B", true, false, &[]).expect("commit");
 
     // Add a commit to master branch (switching back to master first)
     git::checkout_local_branch(main_repo_path, "master").expect("checkout master");
@@ -140,10 +140,10 @@ fn rebase_all_worktrees_using_worktree_paths_succeeds() {
 
     // Add commits to each worktree
     write_file(&worktree_a_path, "feature-a.txt", "Feature A\n");
-    git::commit(&worktree_a_path, "Add feature A", true, false).expect("commit");
+    git::commit(&worktree_a_path, "Add feature A", true, false, &[]).expect("commit");
 
     write_file(&worktree_b_path, "feature-b.txt", "Feature B\n");
-    git::commit(&worktree_b_path, "Add feature B", true, false).expect("commit");
+    git::commit(&worktree_b_path, "Add feature B", true, false, &[]).expect("commit");
 
     // Add a commit to master branch (switching back to master first)
     git::checkout_local_branch(main_repo_path, "master").expect("checkout master");
@@ -229,7 +229,7 @@ fn rebase_all_on_active_workflow() {
         git::add_worktree(main_repo_path, worktree_path, branch, "HEAD")
             .expect("add worktree");
         write_file(worktree_path, &format!("{}.txt", branch), branch);
-        git::commit(worktree_path, &format!("Add {}", branch), true, false)
+        git::commit(worktree_path, &format!("Add {}", branch), true, false, &[])
             .expect("commit");
     }
 
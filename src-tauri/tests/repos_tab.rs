@@ -12,6 +12,8 @@ mod common;
 
 use parallel_cli_runner_lib::git;
 use std::fs;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use git2::Repository;
 
 /// Tests detecting a repository from a subdirectory.
@@ -90,7 +92,7 @@ fn repos_tab_scan_multiple_repos() {
         config.set_str("user.email", "test@example.com").unwrap();
     }
 
-    let repos = git::scan_repos(root, |_| {}).expect("scan repos");
+    let repos = git::scan_repos(root, false, &AtomicBool::new(false), |_| {}).expect("scan repos");
     let repo_paths: Vec<String> = repos.iter().map(|r| r.root_path.clone()).collect();
 
     assert_eq!(repos.len(), 3, "should find 3 repositories");
@@ -114,7 +116,7 @@ fn repos_tab_scan_returns_sorted() {
     Repository::init(&repo_a).expect("init a");
     Repository::init(&repo_m).expect("init m");
 
-    let repos = git::scan_repos(root, |_| {}).expect("scan repos");
+    let repos = git::scan_repos(root, false, &AtomicBool::new(false), |_| {}).expect("scan repos");
     assert_eq!(repos.len(), 3);
 
     // Should be sorted alphabetically
@@ -137,7 +139,7 @@ fn repos_tab_scan_excludes_non_git_dirs() {
     fs::create_dir_all(root.join("notes/docs")).expect("create notes");
     fs::create_dir_all(root.join("temp/files")).expect("create temp");
 
-    let repos = git::scan_repos(root, |_| {}).expect("scan repos");
+    let repos = git::scan_repos(root, false, &AtomicBool::new(false), |_| {}).expect("scan repos");
     assert_eq!(repos.len(), 1, "should only find git repo");
     assert!(repos[0].root_path.contains("git-repo"));
 }
@@ -155,7 +157,8 @@ fn repos_tab_scan_from_within_repo() {
     fs::create_dir_all(&nested).expect("create nested");
 
     // Scan from nested directory
-    let repos = git::scan_repos(&nested, |_| {}).expect("scan repos");
+    let repos = git::scan_repos(&nested, false, &AtomicBool::new(false), |_| {})
+        .expect("scan repos");
     assert_eq!(repos.len(), 1, "should find parent repo");
 
     let expected = git::canonicalize_path(repo.path()).to_string_lossy().to_string();
@@ -171,7 +174,8 @@ fn repos_tab_repo_info_metadata() {
         .build();
 
     // Scan the repo to get its info
-    let repos = git::scan_repos(repo.path(), |_| {}).expect("scan repos");
+    let repos = git::scan_repos(repo.path(), false, &AtomicBool::new(false), |_| {})
+        .expect("scan repos");
     assert_eq!(repos.len(), 1);
 
     let info = &repos[0];
@@ -190,7 +194,7 @@ fn repos_tab_repo_name_extraction() {
     let my_project = root.join("my-awesome-project");
     Repository::init(&my_project).expect("init repo");
 
-    let repos = git::scan_repos(root, |_| {}).expect("scan repos");
+    let repos = git::scan_repos(root, false, &AtomicBool::new(false), |_| {}).expect("scan repos");
     assert_eq!(repos.len(), 1);
     assert_eq!(repos[0].name, "my-awesome-project");
 }
@@ -210,7 +214,8 @@ fn repos_tab_scan_deduplicates_worktrees() {
         .expect("create worktree");
 
     // Scan should return only 1 repo (main repo, worktree excluded)
-    let repos = git::scan_repos(repo.path(), |_| {}).expect("scan repos");
+    let repos = git::scan_repos(repo.path(), false, &AtomicBool::new(false), |_| {})
+        .expect("scan repos");
     assert_eq!(repos.len(), 1, "should deduplicate worktrees");
 
     let expected = git::canonicalize_path(repo.path()).to_string_lossy().to_string();
@@ -223,7 +228,7 @@ fn repos_tab_scan_empty_directory() {
     let temp = tempfile::TempDir::new().expect("create temp dir");
     let root = temp.path();
 
-    let repos = git::scan_repos(root, |_| {}).expect("scan repos");
+    let repos = git::scan_repos(root, false, &AtomicBool::new(false), |_| {}).expect("scan repos");
     assert_eq!(repos.len(), 0, "should return empty list");
 }
 
@@ -243,7 +248,7 @@ fn repos_tab_scan_deeply_nested() {
     Repository::init(&deep1).expect("init repo1");
     Repository::init(&deep2).expect("init repo2");
 
-    let repos = git::scan_repos(root, |_| {}).expect("scan repos");
+    let repos = git::scan_repos(root, false, &AtomicBool::new(false), |_| {}).expect("scan repos");
     assert_eq!(repos.len(), 2, "should find both deeply nested repos");
 }
 
@@ -253,7 +258,8 @@ fn repos_tab_scan_nonexistent_directory() {
     let temp = tempfile::TempDir::new().expect("create temp dir");
     let nonexistent = temp.path().join("does/not/exist");
 
-    let repos = git::scan_repos(&nonexistent, |_| {}).expect("scan repos");
+    let repos = git::scan_repos(&nonexistent, false, &AtomicBool::new(false), |_| {})
+        .expect("scan repos");
     assert_eq!(repos.len(), 0, "should return empty list for non-existent dir");
 }
 
@@ -268,11 +274,43 @@ fn repos_tab_detect_bare_repository() {
     let detected = git::detect_repo(&bare_path).expect("detect bare repo");
     assert!(detected.is_some(), "should detect bare repo");
 
-    let repos = git::scan_repos(&bare_path, |_| {}).expect("scan bare repos");
+    let repos = git::scan_repos(&bare_path, false, &AtomicBool::new(false), |_| {})
+        .expect("scan bare repos");
     assert_eq!(repos.len(), 1);
     assert!(repos[0].is_bare, "should be marked as bare");
 }
 
+/// Tests scanning a folder of bare mirrors next to a regular clone.
+#[test]
+fn repos_tab_scan_identifies_bare_repositories() {
+    let temp = tempfile::TempDir::new().expect("create temp dir");
+    let root = temp.path();
+
+    let work = root.join("work");
+    Repository::init(&work).expect("init work repo");
+    let mirror = root.join("mirrors/project.git");
+    Repository::init_bare(&mirror).expect("init bare mirror");
+    // A git dir copied out of a clone keeps `core.bare = false`
+    let copied = root.join("mirrors/copied.git");
+    Repository::init_bare(&copied)
+        .and_then(|repo| repo.config()?.set_bool("core.bare", false))
+        .expect("init copied git dir");
+
+    let repos = git::scan_repos(root, false, &AtomicBool::new(false), |_| {}).expect("scan repos");
+    let find = |path: &Path| {
+        let root_path = git::canonicalize_path(path).to_string_lossy().to_string();
+        repos
+            .iter()
+            .find(|repo| repo.root_path == root_path)
+            .unwrap_or_else(|| panic!("{} should be scanned", path.display()))
+    };
+
+    assert_eq!(repos.len(), 3);
+    assert!(!find(&work).is_bare, "work should not be bare");
+    assert!(find(&mirror).is_bare, "project.git should be bare");
+    assert!(find(&copied).is_bare, "copied.git should be bare");
+}
+
 /// Tests scanning detects repositories at root level.
 #[test]
 fn repos_tab_scan_root_level_repos() {
@@ -284,7 +322,7 @@ fn repos_tab_scan_root_level_repos() {
     Repository::init(&repo1).expect("init repo1");
     Repository::init(&repo2).expect("init repo2");
 
-    let repos = git::scan_repos(root, |_| {}).expect("scan repos");
+    let repos = git::scan_repos(root, false, &AtomicBool::new(false), |_| {}).expect("scan repos");
     assert_eq!(repos.len(), 2);
 }
 
@@ -299,7 +337,8 @@ fn repos_tab_scan_with_submodules() {
         .build();
 
     // Scan should find at least the main repo
-    let repos = git::scan_repos(repo.path(), |_| {}).expect("scan repos");
+    let repos = git::scan_repos(repo.path(), false, &AtomicBool::new(false), |_| {})
+        .expect("scan repos");
     assert!(!repos.is_empty(), "should find main repo");
 }
 
@@ -318,7 +357,7 @@ fn repos_tab_scan_progress_callback() {
     let callback_count = Arc::new(Mutex::new(0));
     let callback_count_clone = Arc::clone(&callback_count);
 
-    let _repos = git::scan_repos(root, move |_| {
+    let _repos = git::scan_repos(root, false, &AtomicBool::new(false), move |_| {
         *callback_count_clone.lock().unwrap() += 1;
     }).expect("scan repos");
 
@@ -349,7 +388,7 @@ fn repos_tab_scan_handles_permission_errors() {
     }
 
     // Scan should still succeed and find the valid repo
-    let repos = git::scan_repos(root, |_| {}).expect("scan repos");
+    let repos = git::scan_repos(root, false, &AtomicBool::new(false), |_| {}).expect("scan repos");
     assert!(!repos.is_empty(), "should find valid repo despite restricted dir");
 
     // Clean up - restore permissions for cleanup
@@ -405,7 +444,7 @@ fn repos_tab_scan_mixed_content() {
     fs::create_dir_all(root.join("assets/images")).expect("create assets");
     fs::write(root.join("notes.txt"), "some notes").expect("write notes");
 
-    let repos = git::scan_repos(root, |_| {}).expect("scan repos");
+    let repos = git::scan_repos(root, false, &AtomicBool::new(false), |_| {}).expect("scan repos");
     assert_eq!(repos.len(), 2, "should only find git repos");
 }
 
@@ -417,7 +456,8 @@ fn repos_tab_repo_id_matches_root_path() {
         .commit("Initial commit")
         .build();
 
-    let repos = git::scan_repos(repo.path(), |_| {}).expect("scan repos");
+    let repos = git::scan_repos(repo.path(), false, &AtomicBool::new(false), |_| {})
+        .expect("scan repos");
     assert_eq!(repos.len(), 1);
 
     assert_eq!(repos[0].repo_id, repos[0].root_path,
@@ -432,7 +472,8 @@ fn repos_tab_scan_single_repo_at_root() {
         .commit("Initial commit")
         .build();
 
-    let repos = git::scan_repos(repo.path(), |_| {}).expect("scan repos");
+    let repos = git::scan_repos(repo.path(), false, &AtomicBool::new(false), |_| {})
+        .expect("scan repos");
     assert_eq!(repos.len(), 1);
 
     let expected = git::canonicalize_path(repo.path()).to_string_lossy().to_string();
@@ -446,7 +487,8 @@ fn repos_tab_scan_empty_repository() {
     let empty_repo = temp.path().join("empty-repo");
     Repository::init(&empty_repo).expect("init empty repo");
 
-    let repos = git::scan_repos(&empty_repo, |_| {}).expect("scan empty repo");
+    let repos = git::scan_repos(&empty_repo, false, &AtomicBool::new(false), |_| {})
+        .expect("scan empty repo");
     assert_eq!(repos.len(), 1, "should find empty repository");
 }
 
@@ -461,7 +503,8 @@ fn repos_tab_detect_special_chars_in_name() {
     let detected = git::detect_repo(&special_name).expect("detect repo");
     assert!(detected.is_some());
 
-    let repos = git::scan_repos(temp.path(), |_| {}).expect("scan repos");
+    let repos = git::scan_repos(temp.path(), false, &AtomicBool::new(false), |_| {})
+        .expect("scan repos");
     assert_eq!(repos.len(), 1);
     assert!(repos[0].name.contains("my-project_2024"));
 }
@@ -480,7 +523,8 @@ fn repos_tab_scan_git_dot_file() {
 
     if git::add_worktree(repo.path(), &worktree_path, "main", "HEAD").is_ok() {
         // Worktree created, scan should deduplicate
-        let repos = git::scan_repos(repo.path(), |_| {}).expect("scan repos");
+        let repos = git::scan_repos(repo.path(), false, &AtomicBool::new(false), |_| {})
+            .expect("scan repos");
         // Should find main repo but not duplicate worktree
         assert!(repos.len() <= 2, "should handle worktree correctly");
     }
@@ -505,8 +549,8 @@ fn repos_tab_scan_repo_at_top_level() {
         .build();
 
     // Scan from parent directory that contains the repo
-    let repos = git::scan_repos(repo.path().parent().unwrap(), |_| {})
-        .expect("scan repos");
+    let root = repo.path().parent().unwrap();
+    let repos = git::scan_repos(root, false, &AtomicBool::new(false), |_| {}).expect("scan repos");
 
     assert_eq!(repos.len(), 1, "should find repo at top level");
 }
@@ -24,7 +24,7 @@ fn write_file(root: &Path, relative: &str, contents: &str) -> PathBuf {
 }
 
 fn commit_all(repo_root: &Path, message: &str) {
-    git::commit(repo_root, message, true, false).expect("commit")
+    git::commit(repo_root, message, true, false, &[]).expect("commit")
 }
 
 #[test]
@@ -44,10 +44,10 @@ fn smart_update_worktrees_simple_case() {
         .expect("add worktree a");
 
     write_file(&worktree_a_path, "feature-a-1.txt", "Feature A commit 1\n");
-    git::commit(&worktree_a_path, "Feature A commit 1", true, false).expect("commit");
+    git::commit(&worktree_a_path, "Feature A commit 1", true, false, &[]).expect("commit");
 
     write_file(&worktree_a_path, "feature-a-2.txt", "Feature A commit 2\n");
-    git::commit(&worktree_a_path, "Feature A commit 2", true, false).expect("commit");
+    git::commit(&worktree_a_path, "Feature A commit 2", true, false, &[]).expect("commit");
 
     // Create feature-b worktree with same content as master (no commits ahead)
     let worktree_b_path = temp.path().join("worktree-b");
@@ -105,7 +105,7 @@ fn rebase_branch_checked_out_in_worktree_fails() {
 
     // Add a commit in the worktree
     write_file(&worktree_path, "feature.txt", "Feature\n");
-    git::commit(&worktree_path, "Feature commit", true, false).expect("commit");
+    git::commit(&worktree_path, "Feature commit", true, false, &[]).expect("commit");
 
     // Add a commit on master
     write_file(temp.path(), "master.txt", "Master\n");
@@ -6,6 +6,8 @@
 //! - Saving stashes with/without untracked files
 //! - Applying stashes
 //! - Dropping stashes
+//! - Previewing stash contents as a diff
+//! - Renaming stashes
 //! - Handling invalid stash indices
 
 mod common;
@@ -486,6 +488,103 @@ fn stashes_tab_save_with_no_changes() {
     assert_eq!(stashes.len(), 0, "should not create stash with no changes");
 }
 
+/// Tests stash diff shows tracked modifications against the stash base.
+#[test]
+fn stashes_tab_stash_diff_tracked_changes() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("file.txt", "base\n")
+        .commit("Initial commit")
+        .build();
+
+    fs::write(repo.path().join("file.txt"), "changed\n").expect("write file");
+    git::stash_save(repo.path(), Some("Tracked".to_string()), false).expect("stash save");
+
+    let diff = git::stash_diff(repo.path(), 0).expect("stash diff");
+    assert_eq!(diff.meta.file_summaries.len(), 1);
+    assert_eq!(diff.meta.file_summaries[0].path, "file.txt");
+    assert!(diff.diff_text.contains("base"));
+    assert!(diff.diff_text.contains("changed"));
+}
+
+/// Tests stash diff includes untracked files saved with the stash.
+#[test]
+fn stashes_tab_stash_diff_includes_untracked() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("file.txt", "base\n")
+        .commit("Initial commit")
+        .build();
+
+    fs::write(repo.path().join("file.txt"), "changed\n").expect("write file");
+    fs::write(repo.path().join("new.txt"), "brand new\n").expect("write untracked");
+    git::stash_save(repo.path(), None, true).expect("stash save");
+
+    let diff = git::stash_diff(repo.path(), 0).expect("stash diff");
+    let paths: Vec<&str> = diff
+        .meta
+        .file_summaries
+        .iter()
+        .map(|summary| summary.path.as_str())
+        .collect();
+    assert!(paths.contains(&"file.txt"), "tracked change missing: {paths:?}");
+    assert!(paths.contains(&"new.txt"), "untracked file missing: {paths:?}");
+    assert!(diff.diff_text.contains("brand new"));
+}
+
+/// Tests stash diff with an invalid index returns an error.
+#[test]
+fn stashes_tab_stash_diff_invalid_index() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("file.txt", "base\n")
+        .commit("Initial commit")
+        .build();
+
+    assert!(git::stash_diff(repo.path(), 0).is_err());
+    assert!(git::stash_diff(repo.path(), -1).is_err());
+}
+
+/// Tests renaming stashes keeps their order and contents.
+#[test]
+fn stashes_tab_rename_stash() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("file.txt", "base\n")
+        .commit("Initial commit")
+        .build();
+
+    fs::write(repo.path().join("file.txt"), "first\n").expect("write file");
+    git::stash_save(repo.path(), Some("First".to_string()), false).expect("stash save");
+    fs::write(repo.path().join("file.txt"), "second\n").expect("write file");
+    git::stash_save(repo.path(), Some("Second".to_string()), false).expect("stash save");
+
+    git::rename_stash(repo.path(), 1, "Renamed first").expect("rename older stash");
+    git::rename_stash(repo.path(), 0, "Renamed second").expect("rename newest stash");
+
+    let stashes = git::list_stashes(repo.path()).expect("list stashes");
+    assert_eq!(stashes.len(), 2, "renaming should not add or lose stashes");
+    assert_eq!(stashes[0].message, "Renamed second");
+    assert_eq!(stashes[1].message, "Renamed first");
+
+    git::apply_stash(repo.path(), 1).expect("apply renamed stash");
+    let content = fs::read_to_string(repo.path().join("file.txt")).unwrap();
+    assert_eq!(content, "first\n", "renamed stash should keep its contents");
+}
+
+/// Tests renaming a stash with an invalid index returns an error.
+#[test]
+fn stashes_tab_rename_invalid_index() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("file.txt", "base\n")
+        .commit("Initial commit")
+        .build();
+
+    fs::write(repo.path().join("file.txt"), "changed\n").expect("write file");
+    git::stash_save(repo.path(), None, false).expect("stash save");
+
+    assert!(git::rename_stash(repo.path(), 5, "Nope").is_err());
+    assert!(git::rename_stash(repo.path(), -1, "Nope").is_err());
+    let stashes = git::list_stashes(repo.path()).expect("list stashes");
+    assert_eq!(stashes.len(), 1, "failed rename should keep the stash");
+}
+
 /// Tests complete workflow: save, list, apply, drop.
 #[test]
 fn stashes_tab_complete_workflow() {
@@ -0,0 +1,518 @@
+//! Integration tests for the ws request router, driven in-process through
+//! `handle_request` and `request_once` rather than over a WebSocket.
+
+mod common;
+
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use parallel_cli_runner_lib::ws::{
+    detach_client_sessions, disconnect_client_acp_connections, handle_request,
+    set_client_log_stream, unwatch_client_paths, WsState,
+};
+use parallel_cli_runner_lib::ws_server::{
+    bind_ws_listener, request_once, run_ws_server_on_listener, DEFAULT_EVENT_BUFFER,
+};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+const TEST_TOKEN: &str = "test-token";
+
+/// Starts a server on an ephemeral port and opens an authenticated client connection to it.
+async fn connect_client() -> WebSocketStream<TcpStream> {
+    connect_to(start_server()).await
+}
+
+/// Starts a server on an ephemeral port and returns the port.
+fn start_server() -> u16 {
+    let (listener, port) = bind_ws_listener(0).expect("bind listener");
+    tokio::spawn(run_ws_server_on_listener(
+        listener,
+        TEST_TOKEN.to_string(),
+        DEFAULT_EVENT_BUFFER,
+    ));
+    port
+}
+
+/// Opens an authenticated client connection to the server on `port`.
+async fn connect_to(port: u16) -> WebSocketStream<TcpStream> {
+    let stream = TcpStream::connect(("127.0.0.1", port)).await.expect("connect");
+    let url = format!("ws://127.0.0.1:{port}/?token={TEST_TOKEN}");
+    let (client, _) = tokio_tungstenite::client_async(url, stream)
+        .await
+        .expect("handshake");
+    client
+}
+
+async fn send_frame(client: &mut WebSocketStream<TcpStream>, frame: Value) {
+    client
+        .send(Message::Text(frame.to_string().into()))
+        .await
+        .expect("send frame");
+}
+
+/// Reads frames until the next response or batch response, skipping events.
+async fn next_response(client: &mut WebSocketStream<TcpStream>) -> Value {
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(10), client.next())
+            .await
+            .expect("response within timeout")
+            .expect("connection open")
+            .expect("frame");
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let frame: Value = serde_json::from_str(&text).expect("json frame");
+        if frame["type"] != "event" {
+            return frame;
+        }
+    }
+}
+
+/// Tests server_info reports the dispatched methods.
+#[tokio::test]
+async fn ws_server_info_lists_methods() {
+    let info = request_once("server_info", None).await.expect("server_info");
+    let methods: Vec<&str> = info["methods"]
+        .as_array()
+        .expect("methods array")
+        .iter()
+        .filter_map(Value::as_str)
+        .collect();
+    assert!(methods.contains(&"git_status"));
+    assert!(methods.contains(&"create_session"));
+    assert!(methods.contains(&"acp_ping"));
+}
+
+/// Tests unknown methods and malformed params are rejected with an error code.
+#[tokio::test]
+async fn ws_rejects_unknown_method_and_bad_params() {
+    let err = request_once("no_such_method", None).await.unwrap_err();
+    assert_eq!(err.code, "not_found");
+
+    let err = request_once("git_status", Some(json!({ "path": "/tmp" }))).await.unwrap_err();
+    assert!(!err.message.is_empty());
+}
+
+/// Tests a credential reply with only one of username and password is rejected rather than
+/// treated as a cancel.
+#[tokio::test]
+async fn ws_credential_reply_requires_both_fields_or_neither() {
+    let params = json!({ "requestId": "missing", "username": "user", "password": null });
+    let err = request_once("git_credential_reply", Some(params)).await.unwrap_err();
+    assert_eq!(err.code, "invalid_argument");
+}
+
+/// Tests a git method runs against a real repository through the router.
+#[tokio::test]
+async fn ws_git_status_reports_repo_state() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("README.md", "# Initial\n")
+        .commit("Initial commit")
+        .with_file("untracked.txt", "untracked\n")
+        .build();
+    let cwd = repo.path().to_string_lossy().to_string();
+
+    let status = request_once("git_status", Some(json!({ "cwd": cwd })))
+        .await
+        .expect("git_status");
+    assert_eq!(status["branch"], "main");
+    assert_eq!(status["has_untracked"], true);
+}
+
+/// Tests requests sharing a state see each other's PTY sessions.
+#[tokio::test]
+async fn ws_session_lifecycle_on_shared_state() {
+    let state = WsState::new(DEFAULT_EVENT_BUFFER).for_connection();
+    let call = |method: &str, params: Value| {
+        handle_request(method.to_string(), Some(params), state.clone())
+    };
+
+    let id = call("create_session", json!({}))
+        .await
+        .expect("create_session");
+    let id = id.as_str().expect("session id").to_string();
+    call("kill_session", json!({ "id": id }))
+        .await
+        .expect("kill_session");
+    assert!(call("write_to_session", json!({ "id": id, "data": "echo\n" }))
+        .await
+        .is_err());
+}
+
+/// Tests creating a session whose command exits at once still succeeds.
+#[cfg(unix)]
+#[tokio::test]
+async fn ws_create_session_succeeds_for_fast_exiting_command() {
+    let state = WsState::new(DEFAULT_EVENT_BUFFER).for_connection();
+    for _ in 0..5 {
+        let id = handle_request(
+            "create_session".to_string(),
+            Some(json!({ "cmd": "true" })),
+            state.clone(),
+        )
+        .await
+        .expect("create_session");
+        assert!(id.as_str().is_some());
+    }
+}
+
+/// Tests every session of a batch is attached, and a failed spec leaves none behind.
+#[cfg(unix)]
+#[tokio::test]
+async fn ws_create_sessions_attaches_all_or_none() {
+    let state = WsState::new(DEFAULT_EVENT_BUFFER).for_connection();
+    let create = |specs: Value| {
+        handle_request(
+            "create_sessions".to_string(),
+            Some(json!({ "specs": specs })),
+            state.clone(),
+        )
+    };
+
+    let ids = create(json!([{ "cmd": "true" }, { "cmd": "true" }]))
+        .await
+        .expect("create_sessions");
+    assert_eq!(ids.as_array().expect("ids").len(), 2);
+    assert_eq!(state.attached_sessions.lock().unwrap().len(), 2);
+
+    let state = WsState::new(DEFAULT_EVENT_BUFFER).for_connection();
+    // `cat` keeps running, so only the rollback can remove it
+    let specs = json!([{ "cmd": "cat" }, { "cmd": "/nonexistent/command" }]);
+    let result = handle_request(
+        "create_sessions".to_string(),
+        Some(json!({ "specs": specs })),
+        state.clone(),
+    )
+    .await;
+    assert!(result.is_err());
+    assert!(state.attached_sessions.lock().unwrap().is_empty());
+    assert!(state.manager.sessions.lock().unwrap().is_empty());
+}
+
+/// Tests ACP methods answer for connections that don't exist.
+#[tokio::test]
+async fn ws_acp_ping_unknown_connection_fails() {
+    let id = "00000000-0000-0000-0000-000000000000";
+    let err = request_once("acp_ping", Some(json!({ "id": id }))).await.unwrap_err();
+    assert_eq!(err.code, "not_found");
+}
+
+/// Tests shutdown kills the PTY sessions the state still holds.
+#[tokio::test]
+async fn ws_shutdown_kills_sessions() {
+    let state = WsState::new(DEFAULT_EVENT_BUFFER).for_connection();
+    let id = handle_request("create_session".to_string(), Some(json!({})), state.clone())
+        .await
+        .expect("create_session");
+
+    state.shutdown().await;
+    let write = json!({ "id": id, "data": "echo\n" });
+    let result = handle_request("write_to_session".to_string(), Some(write), state).await;
+    assert_eq!(result.unwrap_err().code, "not_found");
+}
+
+/// Tests a session survives while any connection is attached and is only killed once the
+/// last one detached without re-attaching.
+#[tokio::test]
+async fn ws_session_is_killed_only_after_last_detach() {
+    let server = WsState::new(DEFAULT_EVENT_BUFFER);
+    let (first, second) = (server.for_connection(), server.for_connection());
+    let call = |state: &WsState, method: &str, params: Value| {
+        handle_request(method.to_string(), Some(params), state.clone())
+    };
+
+    let id = call(&first, "create_session", json!({})).await.expect("create_session");
+    let session_id = uuid::Uuid::parse_str(id.as_str().expect("session id")).expect("uuid");
+    call(&second, "attach_session", json!({ "id": id })).await.expect("attach");
+    // Attaching twice from one connection counts once
+    call(&second, "attach_session", json!({ "id": id })).await.expect("attach again");
+
+    detach_client_sessions(&first);
+    assert!(!server.manager.kill_if_detached(&session_id));
+
+    call(&second, "detach_session", json!({ "id": id })).await.expect("detach");
+    call(&first, "attach_session", json!({ "id": id })).await.expect("re-attach");
+    assert!(!server.manager.kill_if_detached(&session_id));
+
+    call(&first, "detach_session", json!({ "id": id })).await.expect("detach again");
+    assert!(server.manager.kill_if_detached(&session_id));
+    let write = json!({ "id": id, "data": "echo\n" });
+    let result = call(&first, "write_to_session", write).await;
+    assert_eq!(result.unwrap_err().code, "not_found");
+}
+
+/// Tests a batch frame is answered with one response per request, in request order.
+#[tokio::test]
+async fn ws_batch_returns_responses_in_order() {
+    let mut client = connect_client().await;
+    send_frame(
+        &mut client,
+        json!({
+            "type": "batch",
+            "id": "batch-1",
+            "sequential": true,
+            "requests": [
+                { "id": "a", "method": "server_info" },
+                { "id": "b", "method": "no_such_method" },
+            ],
+        }),
+    )
+    .await;
+
+    let response = next_response(&mut client).await;
+    assert_eq!(response["type"], "batch-response");
+    assert_eq!(response["id"], "batch-1");
+    let responses = response["responses"].as_array().expect("responses");
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["id"], "a");
+    assert_eq!(responses[0]["ok"], true);
+    assert_eq!(responses[1]["id"], "b");
+    assert_eq!(responses[1]["ok"], false);
+    assert_eq!(responses[1]["error"]["code"], "not_found");
+}
+
+/// Tests cancelling an in-flight request answers it right away and leaves the connection
+/// serving further requests.
+#[cfg(unix)]
+#[tokio::test]
+async fn ws_cancel_answers_in_flight_request() {
+    let mut client = connect_client().await;
+    // An agent that never answers `initialize` keeps the connect request pending
+    let connect = json!({ "command": "sleep", "args": ["5"] });
+    send_frame(
+        &mut client,
+        json!({ "type": "request", "id": "slow", "method": "acp_connect", "params": connect }),
+    )
+    .await;
+    send_frame(&mut client, json!({ "type": "cancel", "id": "slow" })).await;
+
+    let response = next_response(&mut client).await;
+    assert_eq!(response["id"], "slow");
+    assert_eq!(response["ok"], false);
+    assert_eq!(response["error"]["code"], "cancelled");
+
+    send_frame(
+        &mut client,
+        json!({ "type": "request", "id": "after", "method": "server_info" }),
+    )
+    .await;
+    let response = next_response(&mut client).await;
+    assert_eq!(response["id"], "after");
+    assert_eq!(response["ok"], true);
+}
+
+/// Tests an agent whose connect request is aborted mid-initialize is still owned by the
+/// client, so closing the client stops it.
+#[cfg(unix)]
+#[tokio::test]
+async fn ws_aborted_acp_connect_is_disconnected_with_client() {
+    let state = WsState::new(DEFAULT_EVENT_BUFFER).for_connection();
+    // An agent that never answers `initialize` keeps the connect request pending
+    let connect = json!({ "command": "sleep", "args": ["5"] });
+    let request = tokio::spawn(handle_request(
+        "acp_connect".to_string(),
+        Some(connect),
+        state.clone(),
+    ));
+    let connection_id = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(id) = state.acp_connections.lock().unwrap().iter().next().copied() {
+                return id;
+            }
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("connection registered before initialize finishes");
+    request.abort();
+    let _ = request.await;
+    assert!(state.acp.get_info(connection_id).is_some());
+
+    disconnect_client_acp_connections(&state).await;
+    assert!(state.acp_connections.lock().unwrap().is_empty());
+    assert!(state.acp.get_info(connection_id).is_none());
+}
+
+/// Tests a frame with an unknown type is answered instead of dropped, and cancelling a batch
+/// answers it as a batch.
+#[cfg(unix)]
+#[tokio::test]
+async fn ws_answers_unknown_frames_and_cancelled_batches() {
+    let mut client = connect_client().await;
+    send_frame(&mut client, json!({ "type": "bogus", "id": "odd", "requests": [] })).await;
+    let response = next_response(&mut client).await;
+    assert_eq!(response["type"], "response");
+    assert_eq!(response["id"], "odd");
+    assert_eq!(response["error"]["code"], "invalid_argument");
+
+    // An agent that never answers `initialize` keeps the batch pending
+    let connect = json!({ "command": "sleep", "args": ["5"] });
+    let batch = json!({
+        "type": "batch",
+        "id": "slow-batch",
+        "requests": [{ "id": "connect", "method": "acp_connect", "params": connect }],
+    });
+    send_frame(&mut client, batch).await;
+    send_frame(&mut client, json!({ "type": "cancel", "id": "slow-batch" })).await;
+    let response = next_response(&mut client).await;
+    assert_eq!(response["type"], "batch-response");
+    assert_eq!(response["id"], "slow-batch");
+    assert_eq!(response["responses"][0]["id"], "connect");
+    assert_eq!(response["responses"][0]["error"]["code"], "cancelled");
+}
+
+/// Tests connections share one repo watcher, which goes away with the last of them.
+#[tokio::test]
+async fn ws_watch_repo_is_shared_and_released_per_connection() {
+    let repo = common::GitRepoBuilder::new()
+        .with_file("README.md", "# Initial\n")
+        .commit("Initial commit")
+        .build();
+    let cwd = repo.path().to_string_lossy().to_string();
+    let server = WsState::new(DEFAULT_EVENT_BUFFER);
+    let (first, second) = (server.for_connection(), server.for_connection());
+    let mut events = server.events.subscribe();
+    let watch = |state: &WsState, method: &str| {
+        handle_request(method.to_string(), Some(json!({ "cwd": cwd })), state.clone())
+    };
+    let watcher_count = || {
+        let watchers = server.repo_watchers.lock().unwrap();
+        watchers.values().map(|(_, count)| *count).sum::<usize>()
+    };
+
+    watch(&first, "git_watch_repo").await.expect("watch");
+    watch(&first, "git_watch_repo").await.expect("watch again");
+    watch(&second, "git_watch_repo").await.expect("watch from second");
+    assert_eq!(server.repo_watchers.lock().unwrap().len(), 1);
+    assert_eq!(watcher_count(), 2, "repeat watches from one connection count once");
+
+    std::fs::write(repo.path().join("README.md"), "# Changed\n").expect("write");
+    let event = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let event = events.recv().await.expect("event");
+            if event.event == "repo-changed" {
+                return event;
+            }
+        }
+    })
+    .await
+    .expect("repo-changed event");
+    assert_eq!(event.payload["repoPath"], repo.canonical_path().to_string_lossy().as_ref());
+
+    watch(&first, "git_unwatch_repo").await.expect("unwatch");
+    assert_eq!(watcher_count(), 1);
+    unwatch_client_paths(&second);
+    assert!(server.repo_watchers.lock().unwrap().is_empty());
+}
+
+/// Tests a scan root watch reports new repositories and is released on disconnect.
+#[tokio::test]
+async fn ws_watch_scan_root_reports_repos_and_releases_on_disconnect() {
+    let root = tempfile::TempDir::new().expect("temp dir");
+    let cwd = root.path().to_string_lossy().to_string();
+    let server = WsState::new(DEFAULT_EVENT_BUFFER);
+    let connection = server.for_connection();
+    let mut events = server.events.subscribe();
+
+    let params = json!({ "cwd": cwd });
+    handle_request("git_watch_scan_root".to_string(), Some(params), connection.clone())
+        .await
+        .expect("watch scan root");
+    assert_eq!(server.scan_root_watchers.lock().unwrap().len(), 1);
+
+    git2::Repository::init(root.path().join("new-repo")).expect("init repo");
+    let event = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let event = events.recv().await.expect("event");
+            if event.event == "scan-root-changed" {
+                return event;
+            }
+        }
+    })
+    .await
+    .expect("scan-root-changed event");
+    let canonical = std::fs::canonicalize(root.path()).expect("canonical root");
+    assert_eq!(event.payload["rootPath"], canonical.to_string_lossy().as_ref());
+
+    unwatch_client_paths(&connection);
+    assert!(server.scan_root_watchers.lock().unwrap().is_empty());
+}
+
+/// Tests log lines only reach the connections that enabled `stream_log`, and streaming stays
+/// on until every connection that enabled it stops or disconnects. Kept in one test because
+/// the log sink is process-wide.
+#[tokio::test]
+async fn ws_stream_log_is_per_connection() {
+    let log_dir = tempfile::TempDir::new().expect("log dir");
+    let _guard = parallel_cli_runner_lib::logging::init_logging(Some(log_dir.path()), None);
+    let port = start_server();
+    let (mut streaming, mut quiet) = (connect_to(port).await, connect_to(port).await);
+    let request = |id: &str, method: &str, params: Value| {
+        json!({ "type": "request", "id": id, "method": method, "params": params })
+    };
+
+    send_frame(&mut streaming, request("on", "stream_log", json!({ "enabled": true }))).await;
+    assert_eq!(next_response(&mut streaming).await["ok"], true);
+    send_frame(&mut quiet, request("info", "server_info", Value::Null)).await;
+    assert_eq!(next_response(&mut quiet).await["ok"], true);
+
+    tracing::info!("stream log marker");
+    let received = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let message = streaming.next().await.expect("open").expect("frame");
+            let Message::Text(text) = message else {
+                continue;
+            };
+            let frame: Value = serde_json::from_str(&text).expect("json frame");
+            let line = frame["payload"]["line"].as_str().unwrap_or_default();
+            if frame["event"] == "log-line" && line.contains("stream log marker") {
+                return;
+            }
+        }
+    })
+    .await;
+    assert!(received.is_ok(), "streaming client got the log line");
+
+    // Anything forwarded to the quiet client has arrived by the time it answers again
+    send_frame(&mut quiet, request("again", "server_info", Value::Null)).await;
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(5), quiet.next())
+            .await
+            .expect("frame within timeout")
+            .expect("open")
+            .expect("frame");
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let frame: Value = serde_json::from_str(&text).expect("json frame");
+        assert_ne!(frame["event"], "log-line");
+        if frame["id"] == "again" {
+            break;
+        }
+    }
+
+    let server = WsState::new(DEFAULT_EVENT_BUFFER);
+    let (first, second) = (server.for_connection(), server.for_connection());
+    let stream_log = |state: &WsState, enabled: bool| {
+        handle_request(
+            "stream_log".to_string(),
+            Some(json!({ "enabled": enabled })),
+            state.clone(),
+        )
+    };
+    let streamers = || *server.log_streamers.lock().unwrap();
+
+    stream_log(&first, true).await.expect("enable first");
+    stream_log(&first, true).await.expect("enable first again");
+    stream_log(&second, true).await.expect("enable second");
+    assert_eq!(streamers(), 2);
+
+    stream_log(&second, false).await.expect("disable second");
+    assert_eq!(streamers(), 1);
+    // Closing the connection stops its stream as if it had disabled it
+    set_client_log_stream(&first, false);
+    assert_eq!(streamers(), 0);
+}
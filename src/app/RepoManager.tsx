@@ -1,6 +1,7 @@
 import { ReactNode, useCallback, useEffect, useState } from "react";
 import { gitScanRepos } from "../services/backend";
-import { formatInvokeError } from "../services/errors";
+import { formatInvokeError, parseError } from "../services/errors";
+import { ERROR_CODES } from "../lib/errors";
 import { openDialog } from "../platform/actions";
 import { getAppConfig } from "../platform/config";
 import { RepoPickerModal } from "../components/RepoPickerModal";
@@ -57,6 +58,8 @@ export function RepoManager({ repos, setRepos, children }: RepoManagerProps) {
       setSelectedRepoIds(repos.map((repo) => repo.repo_id));
       setIsRepoPickerOpen(true);
     } catch (err) {
+      // The user stopped the scan; there is nothing to pick from.
+      if (parseError(err).code === ERROR_CODES.CANCELLED) return;
       const message = formatInvokeError(err);
       setRepoScanError(message === "Unexpected error." ? "Failed to scan repos." : message);
       setRepoCandidates([]);
@@ -129,6 +129,14 @@ export function ErrorDisplay({
         </div>
       )}
 
+      {/* Full command output */}
+      {parsed.details && showDetails && (
+        <details className="error-display__details">
+          <summary>Output</summary>
+          <pre className="error-display__stack">{parsed.details}</pre>
+        </details>
+      )}
+
       {/* Technical Details (dev only) */}
       {import.meta.env.DEV && showDetails && parsed.original instanceof Error && (
         <details className="error-display__details">
@@ -152,7 +160,10 @@ function getErrorSeverity(parsed: ParsedError): "error" | "warning" | "info" {
     case ERROR_CODES.VALIDATION_ERROR:
       return "warning";
     case ERROR_CODES.NOT_FOUND:
+    case ERROR_CODES.NOTHING_TO_COMMIT:
       return "info";
+    case ERROR_CODES.NOTHING_STAGED:
+      return "warning";
     default:
       return "error";
   }
@@ -1,22 +1,21 @@
 import { useEffect, useState } from "react";
-import { subscribeScanProgress } from "../services/backend";
+import { gitCancelScan, subscribeScanProgress } from "../services/backend";
+import type { ScanProgress } from "../types/git";
 
 interface ScanProgressModalProps {
   open: boolean;
 }
 
 export function ScanProgressModal({ open }: ScanProgressModalProps) {
-  const [currentPath, setCurrentPath] = useState<string>("");
+  const [progress, setProgress] = useState<ScanProgress | null>(null);
 
   useEffect(() => {
     if (!open) return;
 
-    // Reset path when opening
-    setCurrentPath("");
+    // Reset progress when opening
+    setProgress(null);
 
-    const unsubscribe = subscribeScanProgress((path) => {
-      setCurrentPath(path);
-    });
+    const unsubscribe = subscribeScanProgress(setProgress);
 
     return () => {
       unsubscribe();
@@ -25,6 +24,8 @@ export function ScanProgressModal({ open }: ScanProgressModalProps) {
 
   if (!open) return null;
 
+  const currentPath = progress?.current_path ?? "";
+
   return (
     <div className="scan-progress-overlay">
       <div className="scan-progress-modal">
@@ -32,9 +33,17 @@ export function ScanProgressModal({ open }: ScanProgressModalProps) {
         <div className="scan-progress-path" title={currentPath}>
           {currentPath || "Starting scan..."}
         </div>
+        {progress && (
+          <div className="scan-progress-counts">
+            {progress.dirs_scanned} folders scanned, {progress.repos_found} repositories found
+          </div>
+        )}
         <div className="scan-progress-bar">
           <div className="scan-progress-bar-fill" />
         </div>
+        <button type="button" className="scan-progress-cancel" onClick={() => void gitCancelScan()}>
+          Cancel
+        </button>
       </div>
     </div>
   );
@@ -42,6 +42,20 @@ export const ERROR_CODES = {
   NETWORK_ERROR: "NETWORK_ERROR",
   /** Timeout error */
   TIMEOUT_ERROR: "TIMEOUT_ERROR",
+  /** Operation cancelled by the user */
+  CANCELLED: "cancelled",
+  /** Commit attempted with unstaged changes but nothing staged */
+  NOTHING_STAGED: "nothing_staged",
+  /** Commit attempted on a clean working tree */
+  NOTHING_TO_COMMIT: "nothing_to_commit",
+  /** Rebase stopped on conflicts and is waiting to be continued or aborted */
+  REBASE_CONFLICT: "rebase_conflict",
+  /** Branch name is malformed or already checked out in another worktree */
+  INVALID_BRANCH: "INVALID_BRANCH",
+  /** Worktree destination already exists or belongs to another worktree */
+  PATH_OCCUPIED: "PATH_OCCUPIED",
+  /** Start point does not resolve to a commit */
+  INVALID_START_POINT: "INVALID_START_POINT",
 } as const;
 
 /** Type of error code values */
@@ -104,6 +118,20 @@ export function getErrorTitle(code: ErrorCode): string {
       return "Network Error";
     case ERROR_CODES.TIMEOUT_ERROR:
       return "Timeout Error";
+    case ERROR_CODES.CANCELLED:
+      return "Cancelled";
+    case ERROR_CODES.NOTHING_STAGED:
+      return "Nothing Staged";
+    case ERROR_CODES.NOTHING_TO_COMMIT:
+      return "Nothing to Commit";
+    case ERROR_CODES.REBASE_CONFLICT:
+      return "Rebase Conflict";
+    case ERROR_CODES.INVALID_BRANCH:
+      return "Invalid Branch";
+    case ERROR_CODES.PATH_OCCUPIED:
+      return "Path Already Exists";
+    case ERROR_CODES.INVALID_START_POINT:
+      return "Invalid Start Point";
     default:
       return "Error";
   }
@@ -127,6 +155,16 @@ export function getSuggestedAction(code: ErrorCode): string | null {
       return "Resolve conflicts before continuing";
     case ERROR_CODES.VALIDATION_ERROR:
       return "Check your input and try again";
+    case ERROR_CODES.NOTHING_STAGED:
+      return "Stage the changes you want to commit first";
+    case ERROR_CODES.REBASE_CONFLICT:
+      return "Resolve the conflicts, then continue or abort the rebase";
+    case ERROR_CODES.INVALID_BRANCH:
+      return "Choose another branch name";
+    case ERROR_CODES.PATH_OCCUPIED:
+      return "Choose a path that does not exist yet";
+    case ERROR_CODES.INVALID_START_POINT:
+      return "Enter an existing branch, tag or commit";
     default:
       return null;
   }
@@ -69,10 +69,15 @@ export function parseError(error: AppError): ParsedError {
     isRetryable = true;
   }
 
+  const maybeDetails =
+    error && typeof error === "object" ? (error as { details?: unknown }).details : undefined;
+  const details = typeof maybeDetails === "string" && maybeDetails.trim() ? maybeDetails : null;
+
   return {
     code,
     message,
     isRetryable,
+    details,
     original: error,
     suggestedAction: code ? getSuggestedAction(code as ErrorCode) : null,
   };
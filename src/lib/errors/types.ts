@@ -39,6 +39,8 @@ export interface ParsedError {
   message: string;
   /** Whether the error is retryable */
   isRetryable: boolean;
+  /** Full multi-line output behind the message, such as git's stderr (if available) */
+  details: string | null;
   /** The original error object */
   original: AppError;
   /** Suggested action for the user (if available) */
@@ -23,6 +23,7 @@ type AcpSessionNotification = {
 
 type AcpSessionUpdateEvent = {
   connectionId: string;
+  promptId?: string;
   notification: AcpSessionNotification;
 };
 
@@ -192,6 +193,7 @@ export class AcpChatTransport implements ChatTransport<UIMessage> {
       throw new Error(EMPTY_PROMPT_ERROR);
     }
 
+    const promptId = createId("prompt");
     const textId = createId("text");
     const reasoningId = createId("reasoning");
     const toolStates = new Map<string, "input" | "output">();
@@ -210,6 +212,10 @@ export class AcpChatTransport implements ChatTransport<UIMessage> {
             if (event.notification.sessionId !== sessionId) {
               return;
             }
+            // Skip updates still streaming from an earlier prompt on this session
+            if (event.promptId && event.promptId !== promptId) {
+              return;
+            }
 
             const updateEntry = getUpdateEntry(event.notification.update);
             if (!updateEntry) {
@@ -408,9 +414,10 @@ export class AcpChatTransport implements ChatTransport<UIMessage> {
 
         const sendPrompt = async () => {
           try {
-            await this.transport.request<void>("acp_session_prompt", {
+            await this.transport.request<string>("acp_session_prompt", {
               sessionId,
               prompt,
+              promptId,
             });
             closeStream();
           } catch (error) {
@@ -50,6 +50,27 @@ export async function openPath(path: string, openWith?: string): Promise<void> {
   await tauriOpenPath(path, openWith);
 }
 
+export async function revealPath(path: string): Promise<void> {
+  const config = getAppConfig();
+  if (config.wsUrl) {
+    await getTransport().request<void>("shell.revealPath", { path });
+    return;
+  }
+
+  const { revealItemInDir } = await import("@tauri-apps/plugin-opener");
+  await revealItemInDir(path);
+}
+
+export async function writeClipboard(text: string): Promise<void> {
+  const config = getAppConfig();
+  if (config.wsUrl) {
+    await getTransport().request<void>("clipboard.write", { text });
+    return;
+  }
+
+  await navigator.clipboard.writeText(text);
+}
+
 export async function openFileInEditor(
   path: string,
   options?: { preview?: boolean }
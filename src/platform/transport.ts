@@ -15,6 +15,7 @@ type TransportResponse = {
   error?: {
     message: string;
     code?: string;
+    details?: string;
     data?: unknown;
   };
 };
@@ -153,6 +154,9 @@ class WsTransport implements Transport {
         if (message.error?.code) {
           (error as { code?: string }).code = message.error.code;
         }
+        if (message.error?.details) {
+          (error as { details?: string }).details = message.error.details;
+        }
         pending.reject(error);
       }
       return;
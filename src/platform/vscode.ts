@@ -13,6 +13,7 @@ type VscodeResponse = {
   error?: {
     message: string;
     code?: string;
+    details?: string;
     data?: unknown;
   };
 };
@@ -118,6 +119,9 @@ class VscodeBridge {
         if (message.error?.code) {
           (error as { code?: string }).code = message.error.code;
         }
+        if (message.error?.details) {
+          (error as { details?: string }).details = message.error.details;
+        }
         pending.reject(error);
       }
       return;
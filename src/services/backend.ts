@@ -1,17 +1,34 @@
 import { getTransport } from "../platform/transport";
 import type {
+  AmendPreviewDto,
+  AuthorStatDto,
   BranchInfoDto,
   CommitInfoDto,
+  CommitPageDto,
+  ConflictVersionsDto,
+  DiffFileSummaryDto,
   DiffRequestDto,
   DiffResponseDto,
+  FastForwardMode,
+  MergePreviewDto,
+  ObjectDto,
   RemoteInfoDto,
   RepoInfoDto,
+  RepoOverviewDto,
+  RepoStatsDto,
+  RepoOperationState,
   RepoStatusDto,
+  ScanProgress,
+  SquashPreviewDto,
   StashInfoDto,
   SubmoduleInfoDto,
   TagInfoDto,
+  TreeEntryDto,
   WorktreeInfoDto,
+  WorktreeStatusDto,
+  WorktreeSuggestionDto,
 } from "../types/git";
+import type { SessionGroupStatus, SessionSignal, SessionSpec } from "../types/transport";
 
 function request<T>(method: string, params?: unknown): Promise<T> {
   return getTransport().request<T>(method, params);
@@ -21,10 +38,31 @@ export function createSession(params: { cwd?: string }): Promise<string> {
   return request<string>("create_session", params);
 }
 
+export function createSessions(params: {
+  specs: SessionSpec[];
+  group?: string;
+}): Promise<string[]> {
+  return request<string[]>("create_sessions", params);
+}
+
+export function groupStatus(params: { groupId: string }): Promise<SessionGroupStatus> {
+  return request<SessionGroupStatus>("group_status", params);
+}
+
+export function subscribeGroupComplete(
+  handler: (status: SessionGroupStatus) => void,
+): () => void {
+  return getTransport().subscribe<SessionGroupStatus>("group-complete", handler);
+}
+
 export function writeToSession(params: { id: string; data: string }): Promise<void> {
   return request("write_to_session", params);
 }
 
+export function setSessionCwd(params: { id: string; cwd: string }): Promise<void> {
+  return request("set_session_cwd", params);
+}
+
 export function resizeSession(params: {
   id: string;
   cols: number;
@@ -33,6 +71,13 @@ export function resizeSession(params: {
   return request("resize_session", params);
 }
 
+export function signalSession(params: {
+  id: string;
+  signal: SessionSignal;
+}): Promise<void> {
+  return request("signal_session", params);
+}
+
 export function killSession(params: { id: string }): Promise<void> {
   return request("kill_session", params);
 }
@@ -46,30 +91,64 @@ export function subscribeSessionData(handler: (payload: SessionDataEvent) => voi
   return getTransport().subscribe<SessionDataEvent>("session-data", handler);
 }
 
-export function subscribeScanProgress(handler: (path: string) => void): () => void {
-  return getTransport().subscribe<string>("scan-progress", handler);
+export function subscribeScanProgress(handler: (progress: ScanProgress) => void): () => void {
+  return getTransport().subscribe<ScanProgress>("scan-progress", handler);
 }
 
 export function gitDetectRepo(params: { cwd: string }): Promise<string | null> {
   return request<string | null>("git_detect_repo", params);
 }
 
-export function gitScanRepos(params: { cwd: string }): Promise<RepoInfoDto[]> {
+export function gitScanRepos(params: {
+  cwd: string;
+  followSymlinks?: boolean;
+}): Promise<RepoInfoDto[]> {
   return request<RepoInfoDto[]>("git_scan_repos", params);
 }
 
+export function gitCancelScan(): Promise<void> {
+  return request<void>("git_cancel_scan");
+}
+
+export function gitHasAnyRepo(params: { cwd: string; maxChecks?: number }): Promise<boolean> {
+  return request<boolean>("git_has_any_repo", params);
+}
+
 export function gitStatus(params: { cwd: string }): Promise<RepoStatusDto> {
   return request<RepoStatusDto>("git_status", params);
 }
 
+export function gitRepoOverview(params: { cwd: string }): Promise<RepoOverviewDto> {
+  return request<RepoOverviewDto>("git_repo_overview", params);
+}
+
+export function gitOperationState(params: { cwd: string }): Promise<RepoOperationState> {
+  return request<RepoOperationState>("git_operation_state", params);
+}
+
 export function gitListBranches(params: { cwd: string }): Promise<BranchInfoDto[]> {
   return request<BranchInfoDto[]>("git_list_branches", params);
 }
 
+export function gitRepoStats(params: { cwd: string }): Promise<RepoStatsDto> {
+  return request<RepoStatsDto>("git_repo_stats", params);
+}
+
+export function gitGc(params: { cwd: string; aggressive?: boolean }): Promise<void> {
+  return request("git_gc", params);
+}
+
 export function gitListRemoteBranches(params: { cwd: string }): Promise<BranchInfoDto[]> {
   return request<BranchInfoDto[]>("git_list_remote_branches", params);
 }
 
+export function gitBranchSyncStatus(params: {
+  cwd: string;
+  branch: string;
+}): Promise<[number, number]> {
+  return request<[number, number]>("git_branch_sync_status", params);
+}
+
 export function gitListCommits(params: {
   cwd: string;
   limit: number;
@@ -78,6 +157,14 @@ export function gitListCommits(params: {
   return request<CommitInfoDto[]>("git_list_commits", params);
 }
 
+export function gitListCommitsPaged(params: {
+  cwd: string;
+  limit: number;
+  skip?: number;
+}): Promise<CommitPageDto> {
+  return request<CommitPageDto>("git_list_commits_paged", params);
+}
+
 export function gitListCommitsRange(params: {
   cwd: string;
   includeBranch: string;
@@ -86,14 +173,74 @@ export function gitListCommitsRange(params: {
   return request<CommitInfoDto[]>("git_list_commits_range", params);
 }
 
+export function gitLastCommitForPath(params: {
+  cwd: string;
+  path: string;
+  rev?: string;
+}): Promise<CommitInfoDto | null> {
+  return request<CommitInfoDto | null>("git_last_commit_for_path", params);
+}
+
+export function gitLogForPath(params: {
+  cwd: string;
+  path: string;
+  limit: number;
+  follow?: boolean;
+}): Promise<CommitInfoDto[]> {
+  return request<CommitInfoDto[]>("git_log_for_path", params);
+}
+
+export function gitUnpushedCommits(params: {
+  cwd: string;
+  branch?: string;
+}): Promise<CommitInfoDto[]> {
+  return request<CommitInfoDto[]>("git_unpushed_commits", params);
+}
+
+export function gitAuthorStats(params: {
+  cwd: string;
+  limit: number;
+  excludeMerges?: boolean;
+}): Promise<AuthorStatDto[]> {
+  return request<AuthorStatDto[]>("git_author_stats", params);
+}
+
+export function gitCommitFiles(params: {
+  cwd: string;
+  commit: string;
+}): Promise<DiffFileSummaryDto[]> {
+  return request<DiffFileSummaryDto[]>("git_commit_files", params);
+}
+
 export function gitListWorktrees(params: { cwd: string }): Promise<WorktreeInfoDto[]> {
   return request<WorktreeInfoDto[]>("git_list_worktrees", params);
 }
 
+export function gitWorktreesWithStats(params: { cwd: string }): Promise<WorktreeStatusDto[]> {
+  return request<WorktreeStatusDto[]>("git_worktrees_with_stats", params);
+}
+
 export function gitListRemotes(params: { cwd: string }): Promise<RemoteInfoDto[]> {
   return request<RemoteInfoDto[]>("git_list_remotes", params);
 }
 
+export function gitSetRemoteUrl(params: {
+  cwd: string;
+  name: string;
+  url: string;
+  push?: boolean;
+}): Promise<void> {
+  return request("git_set_remote_url", params);
+}
+
+export function gitAddRemote(params: { cwd: string; name: string; url: string }): Promise<void> {
+  return request("git_add_remote", params);
+}
+
+export function gitRemoveRemote(params: { cwd: string; name: string }): Promise<void> {
+  return request("git_remove_remote", params);
+}
+
 export function gitListSubmodules(params: { cwd: string }): Promise<SubmoduleInfoDto[]> {
   return request<SubmoduleInfoDto[]>("git_list_submodules", params);
 }
@@ -126,11 +273,31 @@ export function gitStashSave(params: {
   return request("git_stash_save", params);
 }
 
+export function gitFetch(params: { cwd: string; remote?: string }): Promise<void> {
+  return request("git_fetch", params);
+}
+
+export function gitCredentialReply(params: {
+  requestId: string;
+  username: string | null;
+  password: string | null;
+}): Promise<void> {
+  return request("git_credential_reply", params);
+}
+
 export function gitPull(params: { cwd: string }): Promise<void> {
   return request("git_pull", params);
 }
 
-export function gitPush(params: { cwd: string; force: boolean }): Promise<void> {
+export function gitPush(params: {
+  cwd: string;
+  force: boolean;
+  forceWithLease?: boolean;
+  remote?: string;
+  localBranch?: string;
+  remoteBranch?: string;
+  setUpstream?: boolean;
+}): Promise<void> {
   return request("git_push", params);
 }
 
@@ -143,6 +310,13 @@ export function gitCommit(params: {
   return request("git_commit", params);
 }
 
+export function gitAmendPreview(params: {
+  cwd: string;
+  stageAll: boolean;
+}): Promise<AmendPreviewDto> {
+  return request<AmendPreviewDto>("git_amend_preview", params);
+}
+
 export function gitStageFiles(params: { cwd: string; paths: string[] }): Promise<void> {
   return request("git_stage_files", params);
 }
@@ -155,6 +329,43 @@ export function gitDiscardFiles(params: { cwd: string; paths: string[] }): Promi
   return request("git_discard_files", params);
 }
 
+export function gitResolveConflict(params: {
+  cwd: string;
+  path: string;
+  side: "ours" | "theirs";
+}): Promise<void> {
+  return request("git_resolve_conflict", params);
+}
+
+export function gitListTree(params: {
+  cwd: string;
+  rev: string;
+  path: string;
+}): Promise<TreeEntryDto[]> {
+  return request<TreeEntryDto[]>("git_list_tree", params);
+}
+
+export function gitReadObject(params: { cwd: string; oid: string }): Promise<ObjectDto> {
+  return request<ObjectDto>("git_read_object", params);
+}
+
+export function gitExpandContext(params: {
+  cwd: string;
+  path: string;
+  rev: string;
+  startLine: number;
+  count: number;
+}): Promise<string[]> {
+  return request<string[]>("git_expand_context", params);
+}
+
+export function gitConflictVersions(params: {
+  cwd: string;
+  path: string;
+}): Promise<ConflictVersionsDto> {
+  return request<ConflictVersionsDto>("git_conflict_versions", params);
+}
+
 export function gitStageAll(params: { cwd: string }): Promise<void> {
   return request("git_stage_all", params);
 }
@@ -163,10 +374,20 @@ export function gitUnstageAll(params: { cwd: string }): Promise<void> {
   return request("git_unstage_all", params);
 }
 
+export function gitCanMerge(params: {
+  cwd: string;
+  target: string;
+  source: string;
+}): Promise<MergePreviewDto> {
+  return request<MergePreviewDto>("git_can_merge", params);
+}
+
 export function gitMergeIntoBranch(params: {
   repoRoot: string;
   targetBranch: string;
   sourceBranch: string;
+  autoStash?: boolean;
+  fastForward?: FastForwardMode;
 }): Promise<void> {
   return request("git_merge_into_branch", params);
 }
@@ -201,6 +422,7 @@ export function gitDetachWorktreeHead(params: { cwd: string }): Promise<void> {
 export function gitSmartCheckoutBranch(params: {
   cwd: string;
   branchName: string;
+  autoStash?: boolean;
 }): Promise<void> {
   return request("git_smart_checkout_branch", params);
 }
@@ -220,13 +442,75 @@ export function gitRevert(params: {
   return request("git_revert", params);
 }
 
+export function gitRebaseAbort(params: { cwd: string }): Promise<void> {
+  return request("git_rebase_abort", params);
+}
+
+export function gitRebaseContinue(params: { cwd: string }): Promise<void> {
+  return request("git_rebase_continue", params);
+}
+
+export function gitRevertAbort(params: { cwd: string }): Promise<void> {
+  return request("git_revert_abort", params);
+}
+
+export function gitCherryPickAbort(params: { cwd: string }): Promise<void> {
+  return request("git_cherry_pick_abort", params);
+}
+
+export function gitClearPendingMessage(params: { cwd: string }): Promise<void> {
+  return request("git_clear_pending_message", params);
+}
+
+export function gitArchive(params: {
+  cwd: string;
+  rev: string;
+  format: "zip" | "tar";
+  outPath: string;
+}): Promise<void> {
+  return request("git_archive", params);
+}
+
+export function gitApplyPatch(params: {
+  cwd: string;
+  patch: string;
+  checkOnly?: boolean;
+  threeWay?: boolean;
+}): Promise<void> {
+  return request("git_apply_patch", params);
+}
+
+export function gitFormatPatch(params: {
+  cwd: string;
+  range: string;
+  outDir: string;
+}): Promise<string[]> {
+  return request<string[]>("git_format_patch", params);
+}
+
 export function gitSquashCommits(params: {
   cwd: string;
   commits: string[];
-}): Promise<void> {
+  message?: string;
+  dryRun?: boolean;
+}): Promise<SquashPreviewDto> {
   return request("git_squash_commits", params);
 }
 
+export function gitDropCommits(params: {
+  cwd: string;
+  commits: string[];
+}): Promise<void> {
+  return request("git_drop_commits", params);
+}
+
+export function gitReorderCommits(params: {
+  cwd: string;
+  commits: string[];
+}): Promise<void> {
+  return request("git_reorder_commits", params);
+}
+
 export function gitCommitsInRemote(params: {
   cwd: string;
   commits: string[];
@@ -234,6 +518,14 @@ export function gitCommitsInRemote(params: {
   return request<boolean>("git_commits_in_remote", params);
 }
 
+export function gitDiffWorktrees(params: {
+  repoRoot: string;
+  pathA: string;
+  pathB: string;
+}): Promise<DiffResponseDto> {
+  return request<DiffResponseDto>("git_diff_worktrees", params);
+}
+
 export function gitAddWorktree(params: {
   repoRoot: string;
   path: string;
@@ -243,6 +535,34 @@ export function gitAddWorktree(params: {
   return request("git_add_worktree", params);
 }
 
+export function gitValidateAddWorktree(params: {
+  repoRoot: string;
+  path: string;
+  branch: string;
+  startPoint: string;
+}): Promise<void> {
+  return request("git_validate_add_worktree", params);
+}
+
+export function gitSuggestWorktreeName(params: {
+  repoRoot: string;
+  base: string;
+}): Promise<WorktreeSuggestionDto> {
+  return request<WorktreeSuggestionDto>("git_suggest_worktree_name", params);
+}
+
+export function gitGetSparseCheckout(params: { cwd: string }): Promise<string[]> {
+  return request<string[]>("git_get_sparse_checkout", params);
+}
+
+export function gitSetSparseCheckout(params: {
+  cwd: string;
+  patterns: string[];
+  cone: boolean;
+}): Promise<void> {
+  return request("git_set_sparse_checkout", params);
+}
+
 export function gitRemoveWorktree(params: {
   repoRoot: string;
   path: string;
@@ -251,6 +571,22 @@ export function gitRemoveWorktree(params: {
   return request("git_remove_worktree", params);
 }
 
+export function gitMoveWorktree(params: {
+  repoRoot: string;
+  from: string;
+  to: string;
+}): Promise<void> {
+  return request("git_move_worktree", params);
+}
+
+export function gitRepairWorktrees(params: { cwd: string }): Promise<void> {
+  return request("git_repair_worktrees", params);
+}
+
+export function gitWorktreeDiskUsage(params: { cwd: string }): Promise<number> {
+  return request<number>("git_worktree_disk_usage", params);
+}
+
 export function gitDeleteBranch(params: {
   repoRoot: string;
   branch: string;
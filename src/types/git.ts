@@ -11,7 +11,15 @@ export type CommitInfoDto = { id: string, summary: string, author: string, relat
 
 export type RepoInfoDto = { repo_id: string, root_path: string, name: string, is_bare: boolean, };
 
-export type RepoStatusDto = { repo_id: string, root_path: string, branch: string, ahead: number, behind: number, has_untracked: boolean, has_staged: boolean, has_unstaged: boolean, conflicted_files: number, modified_files: Array<FileStatusDto>, latest_commit: CommitInfoDto | null, };
+export type RepoStatusDto = { repo_id: string, root_path: string, 
+/**
+ * The checked-out branch, or the abbreviated commit id when `HEAD` is detached.
+ */
+branch: string, detached: boolean, 
+/**
+ * Full id of the checked-out commit when `HEAD` is detached.
+ */
+head_commit: string | null, ahead: number, behind: number, has_untracked: boolean, has_staged: boolean, has_unstaged: boolean, conflicted_files: number, modified_files: Array<FileStatusDto>, latest_commit: CommitInfoDto | null, };
 
 export type DiffStatDto = { files_changed: number, insertions: number, deletions: number, };
 
@@ -19,13 +27,58 @@ export type DiffCompareKind = "worktree_head" | "ref_ref" | "index_head";
 
 export type DiffDeltaStatus = "unmodified" | "added" | "deleted" | "modified" | "renamed" | "copied" | "ignored" | "untracked" | "typechange" | "unreadable" | "conflicted";
 
-export type DiffRequestOptionsDto = { context_lines: number | null, show_binary: boolean | null, include_untracked: boolean | null, };
+export type DiffRequestOptionsDto = { context_lines: number | null, show_binary: boolean | null, include_untracked: boolean | null, merge_diff_mode?: MergeDiffMode, 
+/**
+ * Upper bound on `diff_text` in bytes; longer diffs are cut at a line boundary.
+ */
+max_output_bytes?: number, 
+/**
+ * For `RefRef` with a `left`, diff `right` against the merge base of the two refs
+ * (`git diff left...right`) instead of against `left` itself.
+ */
+use_merge_base?: boolean, 
+/**
+ * Similarity percentage (0-100) at which a delete/add pair counts as a rename;
+ * defaults to 50 like `git diff -M`.
+ */
+rename_threshold?: number, 
+/**
+ * Also detect copies of modified files (`git diff -C`), using the same threshold.
+ */
+find_copies?: boolean, 
+/**
+ * Leave files whose only change is line endings out of `diff_text`. They are flagged
+ * with `eol_only` in `file_summaries` either way.
+ */
+ignore_eol?: boolean, 
+/**
+ * Skip the patch text and only count added/deleted lines per file (`git diff
+ * --numstat`); `diff_text` comes back empty. Ignored for combined merge diffs.
+ */
+stat_only?: boolean, };
 
 export type DiffRequestDto = { repo_path: string, compare_kind: DiffCompareKind, left: string | null, right: string | null, paths: Array<string> | null, options: DiffRequestOptionsDto | null, };
 
-export type DiffFileSummaryDto = { path: string, status: DiffDeltaStatus, is_binary: boolean, };
-
-export type DiffMetaDto = { compare_kind: DiffCompareKind, left: string | null, right: string | null, paths: Array<string>, context_lines: number, file_summaries: Array<DiffFileSummaryDto>, conflicted_paths: Array<string>, };
+export type DiffFileSummaryDto = { path: string, status: DiffDeltaStatus, is_binary: boolean, 
+/**
+ * The file differs only in line endings (CRLF vs LF). Always detected, whatever the
+ * request's `ignore_eol`.
+ */
+eol_only: boolean, 
+/**
+ * Added lines; only counted for `stat_only` diffs, and `None` for binary files.
+ */
+insertions: number | null, 
+/**
+ * Deleted lines, counted alongside `insertions`.
+ */
+deletions: number | null, };
+
+export type DiffMetaDto = { compare_kind: DiffCompareKind, left: string | null, right: string | null, paths: Array<string>, context_lines: number, file_summaries: Array<DiffFileSummaryDto>, conflicted_paths: Array<string>, truncated: boolean, 
+/**
+ * Rename similarity threshold the diff was computed with.
+ */
+rename_threshold: number, };
 
 export type DiffResponseDto = { diff_text: string, diff_hash: string, meta: DiffMetaDto, };
 
@@ -39,4 +92,113 @@ export type WorktreeInfoDto = { branch: string, path: string, ahead: number, beh
 
 export type StashInfoDto = { index: number, message: string, id: string, relative_time: string, };
 
-export type TagInfoDto = { name: string, };
\ No newline at end of file
+export type ReflogEntryDto = { old_id: string, new_id: string, message: string, committer: string, relative_time: string, };
+
+export type TagInfoDto = { name: string, 
+/**
+ * Commit the tag points at, after peeling annotated tags; the object id itself for
+ * tags of trees or blobs.
+ */
+target_commit: string, is_annotated: boolean, 
+/**
+ * Annotation message of an annotated tag
+ */
+message: string | null, 
+/**
+ * Tagger name of an annotated tag
+ */
+tagger: string | null, 
+/**
+ * When the tag was made for annotated tags, otherwise when the target was committed
+ */
+relative_time: string, };
+
+export type CommitLintDto = { valid: boolean, errors: Array<string>, parsed_type: string | null, scope: string | null, };
+
+export type SquashPreviewDto = { resulting_message: string, commit_count_before: number, commit_count_after: number, };
+
+export type RepoOperationState = "none" | "merge" | "rebase" | "cherry_pick" | "revert";
+
+export type ConflictVersionsDto = { path: string, base: string | null, ours: string | null, theirs: string | null, is_binary: boolean, };
+
+export type CommitPageDto = { commits: Array<CommitInfoDto>, has_more: boolean, };
+
+export type RepoStatsDto = { commit_count: number, branch_count: number, tag_count: number, object_count: number, pack_size_bytes: number, loose_object_count: number, gc_recommended: boolean, };
+
+export type MergeDiffMode = "first_parent" | "combined";
+
+export type GitCredentialRequestDto = { requestId: string, url: string, 
+/**
+ * Host the answer is cached under for the rest of the session
+ */
+host: string, username: string | null, };
+
+export type ScanProgress = { current_path: string, dirs_scanned: number, repos_found: number, };
+
+export type WorktreeStatusDto = { branch: string, path: string, stats: DiffStatDto | null, error: string | null, };
+
+export type TreeEntryDto = { name: string, is_dir: boolean, 
+/**
+ * Blob size in bytes; `None` for directories and submodules
+ */
+size: number | null, object_id: string, };
+
+export type AmendPreviewDto = { head_id: string, 
+/**
+ * Message the amended commit keeps unless a new one is given
+ */
+message: string, 
+/**
+ * Parents of the amended commit, which are those of the current HEAD
+ */
+parent_ids: Array<string>, 
+/**
+ * Changes folded into the commit, relative to the current HEAD
+ */
+staged_files: Array<DiffFileSummaryDto>, 
+/**
+ * Everything the amended commit changes relative to its first parent
+ */
+files: Array<DiffFileSummaryDto>, };
+
+export type RepoOverviewDto = { 
+/**
+ * Also carries the current branch, its ahead/behind counts and the latest commit.
+ */
+status: RepoStatusDto, default_branch: string, 
+/**
+ * Number of local branches.
+ */
+branch_count: number, remotes: Array<RemoteInfoDto>, };
+
+export type AuthorStatDto = { name: string, email: string, commit_count: number, };
+
+export type MergePreviewDto = { 
+/**
+ * `target` already contains `source`, so there is nothing to merge.
+ */
+up_to_date: boolean, 
+/**
+ * `target` can simply move forward to `source`.
+ */
+fast_forward: boolean, has_conflicts: boolean, conflicted_paths: Array<string>, };
+
+export type FastForwardMode = "auto" | "only" | "never";
+
+export type ObjectKind = "blob" | "tree" | "commit" | "tag";
+
+export type ObjectDto = { kind: ObjectKind, 
+/**
+ * Size of the raw object data in bytes
+ */
+size: number, 
+/**
+ * Pretty-printed object, as `git cat-file -p` shows it; `None` for binary blobs
+ */
+content: string | null, is_binary: boolean, };
+
+export type WorktreeSuggestionDto = { branch: string, 
+/**
+ * Relative to the repository root, e.g. `../repo-feature-2`
+ */
+path: string, };
\ No newline at end of file
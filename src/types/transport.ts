@@ -0,0 +1,356 @@
+// This file was generated by [ts-rs]. Do not edit this file manually.
+// To regenerate: cargo run --bin export_types --manifest-path src-tauri/Cargo.toml
+
+export type TransportRequest = { type: string, id: string, method: string, params: unknown | null, };
+
+export type TransportBatchRequest = { type: string, id: string | null, requests: Array<TransportBatchItem>, 
+/**
+ * Run the requests one after another instead of concurrently
+ */
+sequential: boolean, };
+
+export type TransportBatchItem = { id: string, method: string, params: unknown | null, };
+
+export type TransportCancel = { type: string, id: string, };
+
+export type TransportResponse = { type: string, id: string, ok: boolean, result?: unknown, error?: TransportError, };
+
+export type TransportBatchResponse = { type: string, id?: string, responses: Array<TransportResponse>, };
+
+export type TransportError = { message: string, code?: string, 
+/**
+ * Full multi-line output behind `message`, such as git's stderr
+ */
+details?: string, };
+
+export type TransportEvent = { type: string, event: string, payload: unknown, };
+
+export type ServerInfo = { version: string, methods: Array<string>, protocol_version: number, };
+
+export type EventsLaggedPayload = { skipped: number, };
+
+export type RepoChangedPayload = { repoPath: string, };
+
+export type ScanRootChangedPayload = { rootPath: string, };
+
+export type SetLogLevelParams = { 
+/**
+ * Filter directive such as `debug`, `trace`, or `parallel_cli_runner_lib=trace`
+ */
+level: string, };
+
+export type TailLogParams = { 
+/**
+ * Number of trailing lines to return (default 200)
+ */
+lines?: number, };
+
+export type StreamLogParams = { enabled: boolean, };
+
+export type LogLinePayload = { line: string, };
+
+export type CreateSessionParams = { cmd: string | null, cwd: string | null, };
+
+export type SessionSpec = { cmd: string | null, cwd: string | null, env: { [key in string]?: string } | null, };
+
+export type CreateSessionsParams = { specs: Array<SessionSpec>, 
+/**
+ * Id for the sessions as a group; `group-complete` fires once they have all exited
+ */
+group: string | null, };
+
+export type GroupStatusParams = { groupId: string, };
+
+export type GroupMemberStatus = { id: string, running: boolean, 
+/**
+ * Set once the session exited, unless its exit status could not be read
+ */
+exitCode: number | null, };
+
+export type SessionGroupStatus = { groupId: string, complete: boolean, sessions: Array<GroupMemberStatus>, };
+
+export type SessionIdParams = { id: string, };
+
+export type WriteSessionParams = { id: string, data: string, };
+
+export type SetSessionCwdParams = { id: string, cwd: string, };
+
+export type SessionSignal = "int" | "term" | "kill" | "hup";
+
+export type SignalSessionParams = { id: string, signal: SessionSignal, };
+
+export type ResizeSessionParams = { id: string, cols: number, rows: number, };
+
+export type BroadcastLineParams = { sessionIds: Array<string>, line: string, };
+
+export type CwdParams = { cwd: string, };
+
+export type GitDiffParams = { cwd: string, pathspecs: Array<string>, };
+
+export type GitListCommitsParams = { cwd: string, limit: number, skip: number | null, };
+
+export type GitListCommitsRangeParams = { cwd: string, includeBranch: string, excludeBranch: string, };
+
+export type GitLastCommitForPathParams = { cwd: string, path: string, 
+/**
+ * Where to start looking; defaults to HEAD
+ */
+rev?: string, };
+
+export type GitLogForPathParams = { cwd: string, path: string, limit: number, 
+/**
+ * Continue the history across renames of the file
+ */
+follow?: boolean, };
+
+export type GitUnpushedCommitsParams = { cwd: string, 
+/**
+ * Local branch to inspect; defaults to the checked-out branch
+ */
+branch?: string, };
+
+export type GitScanReposParams = { cwd: string, 
+/**
+ * Descend into symlinked directories; off by default
+ */
+followSymlinks?: boolean, };
+
+export type GitHasAnyRepoParams = { cwd: string, 
+/**
+ * Most directories to inspect before answering `false`
+ */
+maxChecks?: number, };
+
+export type GitAuthorStatsParams = { cwd: string, 
+/**
+ * Most recent commits to count
+ */
+limit: number, 
+/**
+ * Leave merge commits out of the counts
+ */
+excludeMerges?: boolean, };
+
+export type GitCanMergeParams = { cwd: string, 
+/**
+ * Revision that would receive the merge
+ */
+target: string, 
+/**
+ * Revision that would be merged in
+ */
+source: string, };
+
+export type GitListTagsParams = { cwd: string, limit: number, skip: number | null, };
+
+export type GitListReflogParams = { cwd: string, reference: string | null, limit: number, };
+
+export type GitValidateCommitMessageParams = { message: string, maxSubjectLength: number | null, };
+
+export type GitRestoreToReflogParams = { cwd: string, reference: string, index: number, };
+
+export type GitApplyStashParams = { cwd: string, index: number, };
+
+export type GitRenameStashParams = { cwd: string, index: number, message: string, };
+
+export type GitNetworkOptions = { 
+/**
+ * Explicit proxy URL; takes precedence over the system proxy
+ */
+proxy?: string, 
+/**
+ * Apply the auto-detected system proxy when no explicit proxy is set. When off, proxy
+ * variables inherited from the environment are cleared too
+ */
+useSystemProxy?: boolean, };
+
+export type GitPullParams = { cwd: string, network?: GitNetworkOptions, };
+
+export type GitPushParams = { cwd: string, force: boolean, 
+/**
+ * Refuse to overwrite remote commits we haven't fetched; wins over `force`
+ */
+forceWithLease?: boolean, 
+/**
+ * Remote to push to; without it the current branch goes to its upstream
+ */
+remote?: string, 
+/**
+ * Local branch to push; defaults to the current branch
+ */
+localBranch?: string, 
+/**
+ * Branch name on the remote; defaults to the local branch name
+ */
+remoteBranch?: string, 
+/**
+ * Track the pushed branch so later pulls use it
+ */
+setUpstream?: boolean, network?: GitNetworkOptions, };
+
+export type GitCommitParams = { cwd: string, message: string, stageAll: boolean, amend: boolean, coAuthors: Array<string> | null, };
+
+export type GitAmendPreviewParams = { cwd: string, stageAll: boolean, };
+
+export type GitStageFilesParams = { cwd: string, paths: Array<string>, };
+
+export type GitResetParams = { cwd: string, target: string, mode: string, };
+
+export type GitRevertParams = { cwd: string, commit: string, };
+
+export type GitCommitFilesParams = { cwd: string, commit: string, };
+
+export type GitSquashParams = { cwd: string, commits: Array<string>, message?: string, dryRun?: boolean, };
+
+export type GitDropCommitsParams = { cwd: string, commits: Array<string>, };
+
+export type GitReorderCommitsParams = { cwd: string, commits: Array<string>, };
+
+export type GitCommitsInRemoteParams = { cwd: string, commits: Array<string>, };
+
+export type GitMergeParams = { repoRoot: string, targetBranch: string, sourceBranch: string, 
+/**
+ * Stash local changes around the merge (default); with `false`, the merge is refused
+ * while the working tree has uncommitted changes
+ */
+autoStash?: boolean, 
+/**
+ * Fast-forward policy: `never` (default) to always create a merge commit, `auto` to
+ * fast-forward when possible, or `only` to fail unless the target can be fast-forwarded
+ */
+fastForward?: FastForwardMode, };
+
+export type GitRebaseParams = { repoRoot: string, targetBranch: string, ontoBranch: string, };
+
+export type GitCreateBranchParams = { cwd: string, branch_name: string, source_branch: string | null, };
+
+export type GitCheckoutBranchParams = { cwd: string, branchName: string, };
+
+export type GitSmartCheckoutParams = { cwd: string, branchName: string, 
+/**
+ * Stash local changes around the operation (default); with `false`, git's own
+ * refusal to overwrite local changes applies instead
+ */
+autoStash?: boolean, };
+
+export type GitStageAllParams = { cwd: string, };
+
+export type GitUnstageAllParams = { cwd: string, };
+
+export type GitUnstageFilesParams = { cwd: string, paths: Array<string>, };
+
+export type GitDiscardFilesParams = { cwd: string, paths: Array<string>, };
+
+export type ConflictSide = "ours" | "theirs";
+
+export type GitResolveConflictParams = { cwd: string, path: string, side: ConflictSide, };
+
+export type GitConflictVersionsParams = { cwd: string, path: string, };
+
+export type GitExpandContextParams = { cwd: string, path: string, 
+/**
+ * Revision to read the file at; empty for the working tree
+ */
+rev: string, 
+/**
+ * 1-based line to start at
+ */
+startLine: number, count: number, };
+
+export type GitListTreeParams = { cwd: string, rev: string, 
+/**
+ * Directory to list, relative to the repository root; empty for the root
+ */
+path: string, };
+
+export type GitReadObjectParams = { cwd: string, 
+/**
+ * Full or abbreviated object id
+ */
+oid: string, };
+
+export type GitBranchSyncStatusParams = { cwd: string, branch: string, };
+
+export type GitArchiveParams = { cwd: string, rev: string, format: string, outPath: string, };
+
+export type GitApplyPatchParams = { cwd: string, patch: string, checkOnly?: boolean, threeWay?: boolean, };
+
+export type GitFormatPatchParams = { cwd: string, range: string, outDir: string, };
+
+export type GitGcParams = { cwd: string, aggressive?: boolean, };
+
+export type GitSetRemoteUrlParams = { cwd: string, name: string, url: string, push?: boolean, };
+
+export type GitAddRemoteParams = { cwd: string, name: string, url: string, };
+
+export type GitRemoveRemoteParams = { cwd: string, name: string, };
+
+export type GitFetchParams = { cwd: string, remote?: string, network?: GitNetworkOptions, };
+
+export type GitCredentialReplyParams = { requestId: string, 
+/**
+ * Omit both fields to cancel the request
+ */
+username: string | null, password: string | null, };
+
+export type GitDiffWorktreesParams = { repoRoot: string, pathA: string, pathB: string, };
+
+export type GitMoveWorktreeParams = { repoRoot: string, from: string, to: string, };
+
+export type GitAddWorktreeParams = { repoRoot: string, path: string, branch: string, startPoint: string, };
+
+export type GitSuggestWorktreeNameParams = { repoRoot: string, 
+/**
+ * Preferred branch name; a numeric suffix is added when it is taken
+ */
+base: string, };
+
+export type GitSetSparseCheckoutParams = { cwd: string, 
+/**
+ * Directories in cone mode, gitignore-style patterns otherwise
+ */
+patterns: Array<string>, cone: boolean, };
+
+export type GitRemoveWorktreeParams = { repoRoot: string, path: string, force: boolean, };
+
+export type GitStashSaveParams = { cwd: string, message: string | null, includeUntracked: boolean, };
+
+export type GitDeleteBranchParams = { repoRoot: string, branch: string, force: boolean, };
+
+export type GitDropStashParams = { cwd: string, index: number, };
+
+export type OpenDialogParams = { directory: boolean | null, multiple: boolean | null, title: string | null, };
+
+export type OpenPathParams = { path: string, openWith: string | null, };
+
+export type RevealPathParams = { path: string, };
+
+export type ClipboardWriteParams = { text: string, };
+
+export type AcpConnectionIdParams = { id: string, };
+
+export type AcpExtMethodParams = { connectionId: string, method: string, params?: unknown, };
+
+export type AcpValidateMcpParams = { mcpServers: Array<unknown>, };
+
+export type AcpSessionNewParams = { connectionId: string, cwd: string, mcpServers: Array<unknown> | null, };
+
+export type AcpSessionLoadParams = { connectionId: string, sessionId: string, cwd: string, mcpServers: Array<unknown> | null, };
+
+export type AcpSessionPromptParams = { sessionId: string, prompt: Array<unknown>, 
+/**
+ * Tags the `acp-session-update` events this prompt produces; generated when omitted
+ */
+promptId?: string, };
+
+export type AcpSessionCancelParams = { sessionId: string, };
+
+export type AcpPermissionReplyParams = { requestId: string, outcome: AcpPermissionOutcomeDto, };
+
+export type AcpPermissionOutcomeDto = { "outcome": "cancelled" } | { "outcome": "selected", optionId: string, };
+
+export type AcpAgentConfigParams = { command: string, args: Array<string>, env: { [key in string]?: string }, cwd: string | null, 
+/**
+ * Omit to advertise the protocol default capabilities
+ */
+capabilities?: { readTextFile?: boolean, writeTextFile?: boolean }, };
\ No newline at end of file